@@ -610,6 +610,160 @@ enum accesskit_vertical_offset
 typedef uint8_t accesskit_vertical_offset;
 #endif  // __cplusplus
 
+enum accesskit_contrast_level
+#ifdef __cplusplus
+    : uint8_t
+#endif  // __cplusplus
+{
+  /**
+   * WCAG level AA: 4.5:1 for normal text, 3:1 for large text.
+   */
+  ACCESSKIT_CONTRAST_LEVEL_AA,
+  /**
+   * WCAG level AAA: 7:1 for normal text, 4.5:1 for large text.
+   */
+  ACCESSKIT_CONTRAST_LEVEL_AAA,
+};
+#ifndef __cplusplus
+typedef uint8_t accesskit_contrast_level;
+#endif  // __cplusplus
+
+enum accesskit_lint_rule
+#ifdef __cplusplus
+    : uint8_t
+#endif  // __cplusplus
+{
+  /**
+   * A focusable control, such as a button or a text input, has no name.
+   */
+  ACCESSKIT_LINT_RULE_CONTROL_NAME,
+  /**
+   * An image that isn't hidden has no name.
+   */
+  ACCESSKIT_LINT_RULE_IMAGE_NAME,
+  /**
+   * A slider, spin button, scroll bar or meter is missing its numeric
+   * value, minimum or maximum.
+   */
+  ACCESSKIT_LINT_RULE_RANGE_VALUE,
+  /**
+   * A numeric value is outside of its range, or the minimum is greater
+   * than the maximum.
+   */
+  ACCESSKIT_LINT_RULE_RANGE_BOUNDS,
+  /**
+   * A relation such as `labelled_by` or `controls` points to a node that
+   * isn't in the tree.
+   */
+  ACCESSKIT_LINT_RULE_RELATION_TARGET,
+  /**
+   * `position_in_set` is 0, or greater than `size_of_set`.
+   */
+  ACCESSKIT_LINT_RULE_POSITION_IN_SET,
+  /**
+   * The focused node is hidden.
+   */
+  ACCESSKIT_LINT_RULE_HIDDEN_FOCUS,
+};
+#ifndef __cplusplus
+typedef uint8_t accesskit_lint_rule;
+#endif  // __cplusplus
+
+enum accesskit_lint_severity
+#ifdef __cplusplus
+    : uint8_t
+#endif  // __cplusplus
+{
+  ACCESSKIT_LINT_SEVERITY_WARNING,
+  ACCESSKIT_LINT_SEVERITY_ERROR,
+};
+#ifndef __cplusplus
+typedef uint8_t accesskit_lint_severity;
+#endif  // __cplusplus
+
+enum accesskit_tree_change_kind
+#ifdef __cplusplus
+    : uint8_t
+#endif  // __cplusplus
+{
+  /**
+   * The node is only present in the new tree. `new_value` holds
+   * its role and name.
+   */
+  ACCESSKIT_TREE_CHANGE_KIND_ADDED,
+  /**
+   * The node is only present in the old tree. `old_value` holds
+   * its role and name.
+   */
+  ACCESSKIT_TREE_CHANGE_KIND_REMOVED,
+  /**
+   * The node has a different parent in each tree.
+   */
+  ACCESSKIT_TREE_CHANGE_KIND_REPARENTED,
+  /**
+   * `property` has a different value in each tree. A null value
+   * means the property isn't set.
+   */
+  ACCESSKIT_TREE_CHANGE_KIND_PROPERTY_CHANGED,
+};
+#ifndef __cplusplus
+typedef uint8_t accesskit_tree_change_kind;
+#endif  // __cplusplus
+
+enum accesskit_tree_update_error
+#ifdef __cplusplus
+    : uint8_t
+#endif  // __cplusplus
+{
+  /**
+   * There is no previous tree and the update doesn't set its `tree`
+   * field.
+   */
+  ACCESSKIT_TREE_UPDATE_ERROR_MISSING_TREE,
+  /**
+   * The root node is neither in the update nor in the previous tree.
+   */
+  ACCESSKIT_TREE_UPDATE_ERROR_MISSING_ROOT,
+  /**
+   * `node` lists `related` as a child, but `related` is neither in the
+   * update nor in the previous tree.
+   */
+  ACCESSKIT_TREE_UPDATE_ERROR_MISSING_CHILD,
+  /**
+   * `node` was pushed to the update, but isn't in the previous tree and
+   * can't be reached from the root.
+   */
+  ACCESSKIT_TREE_UPDATE_ERROR_UNATTACHED_NODE,
+  /**
+   * `node` lists `related` as a child, but `related` is `node` itself
+   * or one of its ancestors.
+   */
+  ACCESSKIT_TREE_UPDATE_ERROR_CYCLE,
+  /**
+   * `node` is listed as a child of `related` while already having
+   * another parent, or is listed twice by `related`.
+   */
+  ACCESSKIT_TREE_UPDATE_ERROR_MULTIPLE_PARENTS,
+  /**
+   * The focused node `node` can't be reached from the root.
+   */
+  ACCESSKIT_TREE_UPDATE_ERROR_MISSING_FOCUS,
+};
+#ifndef __cplusplus
+typedef uint8_t accesskit_tree_update_error;
+#endif  // __cplusplus
+
+/**
+ * Routes action requests to handlers registered for a node and an action,
+ * for a whole node, or for any request.
+ *
+ * The handlers are called on the thread the request is dispatched from,
+ * without the dispatcher being locked, so they can register or remove
+ * handlers. A handler removed while a request is being dispatched isn't
+ * called for it afterwards.
+ */
+typedef struct accesskit_action_dispatcher accesskit_action_dispatcher;
+
 #if defined(__ANDROID__)
 typedef struct accesskit_android_adapter accesskit_android_adapter;
 #endif
@@ -628,8 +782,42 @@ typedef struct accesskit_android_platform_action
 typedef struct accesskit_android_queued_events accesskit_android_queued_events;
 #endif
 
+typedef struct accesskit_braille_simulator accesskit_braille_simulator;
+
+typedef struct accesskit_contrast_findings accesskit_contrast_findings;
+
 typedef struct accesskit_custom_action accesskit_custom_action;
 
+#if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
+     defined(__NetBSD__) || defined(__OpenBSD__))
+/**
+ * A call to one of the handlers of an adapter, given to the application
+ * by its dispatcher so that it runs on a thread of its choosing.
+ */
+typedef struct accesskit_handler_task accesskit_handler_task;
+#endif
+
+typedef struct accesskit_html_import accesskit_html_import;
+
+/**
+ * State kept from one frame to the next by toolkits that declare their
+ * whole user interface every frame.
+ *
+ * A frame is declared by nesting calls to `accesskit_imm_begin_node` and
+ * `accesskit_imm_end_node`, with a single top-level node which becomes
+ * the root, then ended with `accesskit_imm_end_frame`. The ID of each
+ * node is derived from its key and the ID of its parent, so keys only
+ * need to be unique among siblings, and a node keeps its ID across
+ * frames as long as the keys leading to it don't change. The children
+ * of a node are the nodes declared while it is open, in order.
+ *
+ * A context isn't synchronized, so it must only be used on the thread
+ * declaring the frames. If the activation handler of an adapter runs on
+ * another thread, return null from it and give the full update to the
+ * `provide_initial_tree` function of the adapter from that thread.
+ */
+typedef struct accesskit_imm_context accesskit_imm_context;
+
 #if (defined(ACCESSKIT_IOS) || defined(ACCESSKIT_TVOS) || \
      defined(ACCESSKIT_VISIONOS) || defined(ACCESSKIT_WATCHOS))
 typedef struct accesskit_ios_adapter accesskit_ios_adapter;
@@ -646,6 +834,10 @@ typedef struct accesskit_ios_subclassing_adapter
     accesskit_ios_subclassing_adapter;
 #endif
 
+typedef struct accesskit_lint_findings accesskit_lint_findings;
+
+typedef struct accesskit_linter accesskit_linter;
+
 #if defined(ACCESSKIT_MACOS)
 typedef struct accesskit_macos_adapter accesskit_macos_adapter;
 #endif
@@ -661,10 +853,69 @@ typedef struct accesskit_macos_subclassing_adapter
 
 typedef struct accesskit_node accesskit_node;
 
+/**
+ * Allocates node IDs for the objects of an application and maps them back.
+ *
+ * IDs come either from string paths, which give the same ID from one run
+ * to the next, or from object pointers paired with a generation number
+ * that distinguishes objects allocated at the same address. Once released,
+ * an ID isn't handed out again until an update noted with
+ * `accesskit_node_id_registry_note_update` has removed it from the tree,
+ * so that adapters never mistake a new object for an old one.
+ */
+typedef struct accesskit_node_id_registry accesskit_node_id_registry;
+
+/**
+ * A copy of the accessibility tree, maintained by the application,
+ * from which the library computes tree updates.
+ *
+ * A retained tree isn't synchronized, so it must only be used on the
+ * thread that changes it. If the activation handler of an adapter runs
+ * on another thread, return null from it and give the full update to the
+ * `provide_initial_tree` function of the adapter from that thread.
+ */
+typedef struct accesskit_retained_tree accesskit_retained_tree;
+
+typedef struct accesskit_test_driver accesskit_test_driver;
+
 typedef struct accesskit_tree accesskit_tree;
 
+/**
+ * Builds a tree update by opening and closing nodes, the way a toolkit
+ * walks its widget hierarchy: nodes opened while another one is open
+ * become its children, in order, and no node can be added twice.
+ */
+typedef struct accesskit_tree_builder accesskit_tree_builder;
+
+typedef struct accesskit_tree_diff accesskit_tree_diff;
+
+typedef struct accesskit_tree_snapshot accesskit_tree_snapshot;
+
 typedef struct accesskit_tree_update accesskit_tree_update;
 
+typedef struct accesskit_tree_update_diagnostics
+    accesskit_tree_update_diagnostics;
+
+/**
+ * Tree updates whose storage is kept around to be filled again.
+ *
+ * Updates come back to the pool when they are released, when they are
+ * merged into another one using `accesskit_tree_update_pool_merge` or
+ * `accesskit_tree_update_pool_push_to_queue`, when a queue is cleared
+ * using `accesskit_tree_update_pool_clear_queue`, and when they are
+ * applied to a snapshot using `accesskit_tree_update_pool_update_snapshot`.
+ *
+ * Updates given to a platform adapter don't come back: the adapter frees
+ * them once applied, so the list of nodes of each of them is allocated
+ * again.
+ */
+typedef struct accesskit_tree_update_pool accesskit_tree_update_pool;
+
+/**
+ * Tree updates waiting to be given to an adapter, squashed into one.
+ */
+typedef struct accesskit_tree_update_queue accesskit_tree_update_queue;
+
 #if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
      defined(__NetBSD__) || defined(__OpenBSD__))
 typedef struct accesskit_unix_adapter accesskit_unix_adapter;
@@ -685,6 +936,16 @@ typedef struct accesskit_windows_subclassing_adapter
 
 typedef uint64_t accesskit_node_id;
 
+typedef void *accesskit_tree_update_factory_userdata;
+
+/**
+ * An array of node IDs owned by the caller.
+ */
+typedef struct accesskit_node_id_list {
+  size_t length;
+  accesskit_node_id *values;
+} accesskit_node_id_list;
+
 typedef struct accesskit_node_ids {
   size_t length;
   const accesskit_node_id *values;
@@ -1069,6 +1330,92 @@ typedef struct accesskit_action_request {
   struct accesskit_opt_action_data data;
 } accesskit_action_request;
 
+/**
+ * A text run or label whose contrast is too low.
+ *
+ * `foreground` and `background` are the effective colors, after
+ * inheritance and alpha blending. `level` is the lowest level the text
+ * fails to meet, and `required_ratio` the ratio that level requires.
+ */
+typedef struct accesskit_contrast_finding {
+  accesskit_node_id node;
+  struct accesskit_color foreground;
+  struct accesskit_color background;
+  double ratio;
+  double required_ratio;
+  bool large_text;
+  accesskit_contrast_level level;
+} accesskit_contrast_finding;
+
+/**
+ * A change between two versions of a tree.
+ *
+ * The strings are owned by the `accesskit_tree_diff` this was retrieved from.
+ */
+typedef struct accesskit_tree_change {
+  accesskit_tree_change_kind kind;
+  accesskit_node_id node;
+  struct accesskit_opt_node_id old_parent;
+  struct accesskit_opt_node_id new_parent;
+  const char *property;
+  const char *old_value;
+  const char *new_value;
+} accesskit_tree_change;
+
+/**
+ * Called by an action dispatcher with a request routed to it. Returns
+ * whether the request was handled; if not, the dispatcher tries the next
+ * handler that matches the request. `request` is owned by the dispatcher
+ * and is only valid during the call.
+ */
+typedef bool (*accesskit_dispatched_action_handler_callback)(
+    const struct accesskit_action_request *request, void *userdata);
+
+/**
+ * Called by an action dispatcher with a request that no handler took.
+ * `request` is owned by the dispatcher and is only valid during the call.
+ */
+typedef void (*accesskit_unhandled_action_callback)(
+    const struct accesskit_action_request *request, void *userdata);
+
+/**
+ * Which parts of each node to include in a tree dump.
+ */
+typedef struct accesskit_tree_dump_options {
+  /**
+   * Prefix each line with the node ID, as in `#3`.
+   */
+  bool ids;
+  /**
+   * Toggled, expanded, selected, focused, disabled and other states.
+   */
+  bool states;
+  /**
+   * The value, numeric value and range, level, and position in set.
+   */
+  bool values;
+  /**
+   * Description, placeholder, tooltip and other secondary strings.
+   */
+  bool text;
+  /**
+   * Bounding boxes and transforms.
+   */
+  bool bounds;
+  /**
+   * Relations to other nodes, such as `labelled_by` or `controls`.
+   */
+  bool relations;
+  /**
+   * The list of supported actions.
+   */
+  bool actions;
+  /**
+   * Include hidden nodes and their descendants.
+   */
+  bool hidden;
+} accesskit_tree_dump_options;
+
 /**
  * A 2D vector. Derived from [kurbo](https://github.com/linebender/kurbo).
  *
@@ -1101,27 +1448,154 @@ typedef struct accesskit_size {
   double height;
 } accesskit_size;
 
-typedef void *accesskit_tree_update_factory_userdata;
-
 /**
- * This function can't return a null pointer. Ownership of the returned value
- * will be transferred to the caller.
+ * A problem found by a lint rule.
+ *
+ * `message` is owned by the `accesskit_lint_findings` this was retrieved
+ * from.
  */
-typedef struct accesskit_tree_update *(*accesskit_tree_update_factory)(
-    accesskit_tree_update_factory_userdata);
+typedef struct accesskit_lint_finding {
+  accesskit_lint_rule rule;
+  accesskit_lint_severity severity;
+  accesskit_node_id node;
+  const char *message;
+} accesskit_lint_finding;
 
+/**
+ * Returns the initial tree of the adapter, or null if it isn't ready yet.
+ * The adapter then keeps activating until the tree is given to its
+ * `provide_initial_tree` function, or a full tree update to its
+ * `update_if_active` function. A null callback always returns null.
+ */
 typedef struct accesskit_tree_update *(*accesskit_activation_handler_callback)(
     void *userdata);
 
 /**
  * Ownership of `request` is transferred to the callback. `request` must
  * be freed using `accesskit_action_request_free`.
+ *
+ * If null, action requests are ignored.
  */
 typedef void (*accesskit_action_handler_callback)(
     struct accesskit_action_request *request, void *userdata);
 
+/**
+ * This function can't return a null pointer. Ownership of the returned value
+ * will be transferred to the caller.
+ *
+ * Passing a null factory to an `update_if_active` function leaves the tree
+ * unchanged.
+ */
+typedef struct accesskit_tree_update *(*accesskit_tree_update_factory)(
+    accesskit_tree_update_factory_userdata);
+
+/**
+ * A problem that would make a platform adapter reject a tree update.
+ *
+ * `message` is owned by the `accesskit_tree_update_diagnostics` this was
+ * retrieved from.
+ */
+typedef struct accesskit_tree_update_diagnostic {
+  accesskit_tree_update_error error;
+  struct accesskit_opt_node_id node;
+  struct accesskit_opt_node_id related;
+  const char *message;
+} accesskit_tree_update_diagnostic;
+
+/**
+ * Called by an adapter with validation turned on when it is given an
+ * invalid tree update, which is checked the same way as by
+ * `accesskit_tree_update_validate`. An invalid initial tree is treated as
+ * if the activation handler had returned null. Other invalid updates are
+ * replaced by one that leaves the tree unchanged, or if the adapter has no
+ * valid tree yet, by a tree made of the root alone. Validation should be
+ * turned on before the adapter is active, since incremental updates can
+ * only be checked against a known tree.
+ *
+ * The callback runs on the thread giving the update to the adapter. For an
+ * initial tree returned by the activation handler, that is the thread the
+ * activation handler is called on, which for the Unix adapter is always
+ * another thread than the one owning the adapter.
+ *
+ * Ownership of `diagnostics` is transferred to the callback. `diagnostics`
+ * must be freed using `accesskit_tree_update_diagnostics_free`.
+ */
+typedef void (*accesskit_validation_handler_callback)(
+    struct accesskit_tree_update_diagnostics *diagnostics, void *userdata);
+
+/**
+ * If null, deactivation goes unnoticed.
+ */
 typedef void (*accesskit_deactivation_handler_callback)(void *userdata);
 
+/**
+ * Called once the library no longer references the userdata given along
+ * with a handler, possibly from another thread, so that it can be freed.
+ */
+typedef void (*accesskit_destroy_notify_callback)(void *userdata);
+
+#if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
+     defined(__NetBSD__) || defined(__OpenBSD__))
+/**
+ * Called by an adapter, from any thread, instead of calling one of its
+ * handlers directly. The application must run the task later using
+ * `accesskit_handler_task_run`, or free it using
+ * `accesskit_handler_task_free`.
+ *
+ * The task must be run on the thread that owns the adapter, typically by
+ * posting it to the UI thread, and not from within a call to one of the
+ * functions of the adapter, since running the activation handler gives
+ * the adapter its initial tree. For the same reason, it must not be run
+ * before the dispatcher returns.
+ *
+ * Ownership of `task` is transferred to the callback.
+ */
+typedef void (*accesskit_handler_dispatcher_callback)(
+    struct accesskit_handler_task *task, void *userdata);
+#endif
+
+#if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
+     defined(__NetBSD__) || defined(__OpenBSD__))
+/**
+ * Something that happened to an adapter, returned by its `poll_event`
+ * function when it was created with an event queue.
+ */
+typedef enum accesskit_adapter_event_Tag {
+#if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
+     defined(__NetBSD__) || defined(__OpenBSD__))
+  /**
+   * Assistive technologies started using the adapter. It has no tree
+   * yet: give it one using its `provide_initial_tree` function.
+   */
+  ACCESSKIT_ADAPTER_EVENT_ACTIVATED,
+#endif
+#if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
+     defined(__NetBSD__) || defined(__OpenBSD__))
+  /**
+   * Ownership of the request is transferred to the caller. It must be
+   * freed using `accesskit_action_request_free`.
+   */
+  ACCESSKIT_ADAPTER_EVENT_ACTION_REQUESTED,
+#endif
+#if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
+     defined(__NetBSD__) || defined(__OpenBSD__))
+  ACCESSKIT_ADAPTER_EVENT_DEACTIVATED,
+#endif
+} accesskit_adapter_event_Tag;
+
+typedef struct accesskit_adapter_event {
+  accesskit_adapter_event_Tag tag;
+  union {
+#if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
+     defined(__NetBSD__) || defined(__OpenBSD__))
+    struct {
+      struct accesskit_action_request *action_requested;
+    };
+#endif
+  };
+} accesskit_adapter_event;
+#endif
+
 #if defined(_WIN32)
 /**
  * Represents an optional value.
@@ -1140,38 +1614,246 @@ extern "C" {
 
 extern const struct accesskit_tree_id ACCESSKIT_TREE_ID_ROOT;
 
-accesskit_role accesskit_node_role(const struct accesskit_node *node);
-
-void accesskit_node_set_role(struct accesskit_node *node, accesskit_role value);
-
-bool accesskit_node_supports_action(const struct accesskit_node *node,
-                                    accesskit_action action);
-
-void accesskit_node_add_action(struct accesskit_node *node,
-                               accesskit_action action);
+/**
+ * Simulates a braille display with `cell_count` cells. Returns null if
+ * `cell_count` is zero.
+ */
+struct accesskit_braille_simulator *accesskit_braille_simulator_new(
+    size_t cell_count);
 
-void accesskit_node_remove_action(struct accesskit_node *node,
-                                  accesskit_action action);
+void accesskit_braille_simulator_free(
+    struct accesskit_braille_simulator *simulator);
 
-void accesskit_node_clear_actions(struct accesskit_node *node);
+/**
+ * Renders the focused node of `snapshot` and its context into the
+ * braille line. The node's braille label and braille role description
+ * are used when present, otherwise its name and an abbreviation
+ * of its role are used.
+ *
+ * The display is panned back to the start of the line when the focus
+ * has moved since the last call.
+ */
+void accesskit_braille_simulator_render(
+    struct accesskit_braille_simulator *simulator,
+    const struct accesskit_tree_snapshot *snapshot);
 
 /**
- * Return whether the specified action is in the set supported on this node's
- * direct children in the filtered tree.
+ * Returns false if the display is already showing the start of the line.
  */
-bool accesskit_node_child_supports_action(const struct accesskit_node *node,
-                                          accesskit_action action);
+bool accesskit_braille_simulator_pan_left(
+    struct accesskit_braille_simulator *simulator);
 
 /**
- * Add the specified action to the set supported on this node's direct
- * children in the filtered tree.
+ * Returns false if the display is already showing the end of the line.
  */
-void accesskit_node_add_child_action(struct accesskit_node *node,
-                                     accesskit_action action);
+bool accesskit_braille_simulator_pan_right(
+    struct accesskit_braille_simulator *simulator);
 
 /**
- * Remove the specified action from the set supported on this node's direct
- * children in the filtered tree.
+ * Returns the index in characters of the first character on the display.
+ */
+size_t accesskit_braille_simulator_offset(
+    const struct accesskit_braille_simulator *simulator);
+
+/**
+ * Returns the whole braille line as text.
+ *
+ * Caller must call `accesskit_string_free` with the return value.
+ */
+char *accesskit_braille_simulator_line(
+    const struct accesskit_braille_simulator *simulator);
+
+/**
+ * Returns the part of the line currently shown on the display as text.
+ *
+ * Caller must call `accesskit_string_free` with the return value.
+ */
+char *accesskit_braille_simulator_text(
+    const struct accesskit_braille_simulator *simulator);
+
+/**
+ * Returns the content of every cell of the display as Unicode braille
+ * patterns encoded in UTF-8, using North American Braille Computer Code.
+ * Unused cells are blank; characters that can't be represented
+ * have all eight dots raised.
+ *
+ * Caller must call `accesskit_string_free` with the return value.
+ */
+char *accesskit_braille_simulator_cells(
+    const struct accesskit_braille_simulator *simulator);
+
+struct accesskit_tree_builder *accesskit_tree_builder_new(
+    accesskit_node_id focus);
+
+void accesskit_tree_builder_free(struct accesskit_tree_builder *builder);
+
+/**
+ * Takes ownership of `tree`.
+ */
+void accesskit_tree_builder_set_tree(struct accesskit_tree_builder *builder,
+                                     struct accesskit_tree *tree);
+
+void accesskit_tree_builder_set_focus(struct accesskit_tree_builder *builder,
+                                      accesskit_node_id focus);
+
+/**
+ * Returns the lowest ID, counting up from the one previously returned,
+ * that hasn't been given to a node of this builder. IDs are only known
+ * to be unique within the builder, so this is meant for updates that
+ * hold the whole tree.
+ */
+accesskit_node_id accesskit_tree_builder_next_id(
+    struct accesskit_tree_builder *builder);
+
+/**
+ * Opens a node, which is appended to the children of the node that
+ * is currently open, if any. Nodes opened with nothing else open are
+ * the top-level nodes of the update, the first of which is usually
+ * the root or a node that was already in the tree. Returns the new
+ * node so that its properties can be set; its children are filled in
+ * by the builder.
+ *
+ * Returns null, and opens nothing, if a node with this ID was already
+ * added to the builder.
+ *
+ * The returned node is owned by the builder, and is only valid until
+ * the next call to `accesskit_tree_builder_open_node`.
+ */
+struct accesskit_node *accesskit_tree_builder_open_node(
+    struct accesskit_tree_builder *builder, accesskit_node_id id,
+    accesskit_role role);
+
+/**
+ * Appends `id` to the children of the node that is currently open,
+ * without adding a node for it, for a child that is already in the
+ * tree and hasn't changed. Returns false if no node is open, or if
+ * `id` was already added to the builder.
+ */
+bool accesskit_tree_builder_push_existing_child(
+    struct accesskit_tree_builder *builder, accesskit_node_id id);
+
+/**
+ * Closes the node that is currently open. Returns false if no node
+ * is open.
+ */
+bool accesskit_tree_builder_close_node(struct accesskit_tree_builder *builder);
+
+/**
+ * Returns the number of nodes that are open.
+ */
+size_t accesskit_tree_builder_depth(
+    const struct accesskit_tree_builder *builder);
+
+/**
+ * Returns the tree update built so far, with nodes in the order they
+ * were opened. Takes ownership of `builder`.
+ *
+ * Returns null, freeing `builder`, if a node is still open.
+ */
+struct accesskit_tree_update *accesskit_tree_builder_finish(
+    struct accesskit_tree_builder *builder);
+
+/**
+ * Merges `src` into `dst`, so that applying `dst` has the same effect
+ * as applying the original `dst` followed by `src`. Nodes of `src`
+ * replace the nodes of `dst` with the same ID, and the others are
+ * appended. `dst` gets the focus of `src`, and its `tree` field if set.
+ * Nodes that `src` removes from a parent listed in either update are
+ * dropped, so that a node added by `dst` and removed by `src` is not
+ * sent at all. A node whose parent is in neither update is kept, since
+ * that requires knowing the current tree. Takes ownership of `src`.
+ *
+ * Returns false, leaving `dst` unchanged and freeing `src`, if the two
+ * updates have different tree IDs.
+ */
+bool accesskit_tree_update_merge(struct accesskit_tree_update *dst,
+                                 struct accesskit_tree_update *src);
+
+/**
+ * Creates an empty queue for the root tree. `focus` is the focus
+ * given to the adapter until an update is taken out of the queue.
+ */
+struct accesskit_tree_update_queue *accesskit_tree_update_queue_new(
+    accesskit_node_id focus);
+
+void accesskit_tree_update_queue_free(
+    struct accesskit_tree_update_queue *queue);
+
+/**
+ * Merges `update` into the pending update, as with
+ * `accesskit_tree_update_merge`, including its handling of removed
+ * nodes. Takes ownership of `update`.
+ *
+ * Returns false, freeing `update`, if it is for another tree than the
+ * pending update.
+ */
+bool accesskit_tree_update_queue_push(struct accesskit_tree_update_queue *queue,
+                                      struct accesskit_tree_update *update);
+
+bool accesskit_tree_update_queue_is_empty(
+    const struct accesskit_tree_update_queue *queue);
+
+/**
+ * Drops the pending update.
+ */
+void accesskit_tree_update_queue_clear(
+    struct accesskit_tree_update_queue *queue);
+
+/**
+ * Removes the pending update from the queue and returns it, or null
+ * if the queue is empty.
+ */
+struct accesskit_tree_update *accesskit_tree_update_queue_take(
+    struct accesskit_tree_update_queue *queue);
+
+/**
+ * A tree update factory taking the pending update out of the queue
+ * passed as userdata, to be given to the `update_if_active` function
+ * of an adapter when the queue isn't empty. The factory isn't called
+ * if the adapter is inactive, in which case the update stays queued;
+ * call `accesskit_tree_update_queue_clear` if it is no longer needed.
+ *
+ * If the queue is empty, returns an update without nodes, keeping the
+ * tree and focus of the last update taken out of the queue, or the
+ * focus the queue was created with if none was taken yet.
+ */
+struct accesskit_tree_update *accesskit_tree_update_queue_factory(
+    accesskit_tree_update_factory_userdata userdata);
+
+void accesskit_node_id_list_free(struct accesskit_node_id_list *value);
+
+accesskit_role accesskit_node_role(const struct accesskit_node *node);
+
+void accesskit_node_set_role(struct accesskit_node *node, accesskit_role value);
+
+bool accesskit_node_supports_action(const struct accesskit_node *node,
+                                    accesskit_action action);
+
+void accesskit_node_add_action(struct accesskit_node *node,
+                               accesskit_action action);
+
+void accesskit_node_remove_action(struct accesskit_node *node,
+                                  accesskit_action action);
+
+void accesskit_node_clear_actions(struct accesskit_node *node);
+
+/**
+ * Return whether the specified action is in the set supported on this node's
+ * direct children in the filtered tree.
+ */
+bool accesskit_node_child_supports_action(const struct accesskit_node *node,
+                                          accesskit_action action);
+
+/**
+ * Add the specified action to the set supported on this node's direct
+ * children in the filtered tree.
+ */
+void accesskit_node_add_child_action(struct accesskit_node *node,
+                                     accesskit_action action);
+
+/**
+ * Remove the specified action from the set supported on this node's direct
+ * children in the filtered tree.
  */
 void accesskit_node_remove_child_action(struct accesskit_node *node,
                                         accesskit_action action);
@@ -2307,6 +2989,8 @@ struct accesskit_tree *accesskit_tree_new(accesskit_node_id root);
 
 void accesskit_tree_free(struct accesskit_tree *tree);
 
+accesskit_node_id accesskit_tree_get_root(const struct accesskit_tree *tree);
+
 /**
  * Caller must call `accesskit_string_free` with the return value.
  */
@@ -2360,6 +3044,14 @@ struct accesskit_tree_update *accesskit_tree_update_with_capacity_and_focus(
 
 void accesskit_tree_update_free(struct accesskit_tree_update *update);
 
+/**
+ * Removes all nodes and unsets the `tree` field, keeping the memory
+ * allocated for the list of nodes so that the update can be refilled
+ * without reallocating it. The tree ID is reset to the root tree.
+ */
+void accesskit_tree_update_clear(struct accesskit_tree_update *update,
+                                 accesskit_node_id focus);
+
 /**
  * Appends the provided node to the tree update's list of nodes.
  * Takes ownership of `node`.
@@ -2368,11 +3060,83 @@ void accesskit_tree_update_push_node(struct accesskit_tree_update *update,
                                      accesskit_node_id id,
                                      struct accesskit_node *node);
 
+/**
+ * Appends a node with the provided role to the tree update's list of
+ * nodes, and returns it so that its properties can be set. The node
+ * is built in place, without the separate allocation made by
+ * `accesskit_node_new`. It is owned by the tree update, and the
+ * pointer is only valid until nodes are next added to or removed from
+ * the update, since the list may then be moved to a larger buffer.
+ * Use `accesskit_tree_update_with_capacity_and_focus` to allocate the
+ * list once when the number of nodes is known.
+ */
+struct accesskit_node *accesskit_tree_update_push_new_node(
+    struct accesskit_tree_update *update, accesskit_node_id id,
+    accesskit_role role);
+
+size_t accesskit_tree_update_node_count(
+    const struct accesskit_tree_update *update);
+
+/**
+ * `index` must be lower than the value returned by
+ * `accesskit_tree_update_node_count`.
+ */
+accesskit_node_id accesskit_tree_update_node_id_at(
+    const struct accesskit_tree_update *update, size_t index);
+
+/**
+ * `index` must be lower than the value returned by
+ * `accesskit_tree_update_node_count`.
+ * The returned node is owned by the tree update.
+ */
+const struct accesskit_node *accesskit_tree_update_node_at(
+    const struct accesskit_tree_update *update, size_t index);
+
+/**
+ * Returns null if no node with this ID was pushed. If there are several,
+ * returns the last one, which is the one platform adapters use.
+ * The returned node is owned by the tree update.
+ */
+const struct accesskit_node *accesskit_tree_update_find_node(
+    const struct accesskit_tree_update *update, accesskit_node_id id);
+
+/**
+ * Same as `accesskit_tree_update_find_node`, but the node can be
+ * modified in place.
+ */
+struct accesskit_node *accesskit_tree_update_find_node_mut(
+    struct accesskit_tree_update *update, accesskit_node_id id);
+
+/**
+ * Replaces the node that `accesskit_tree_update_find_node` would return,
+ * keeping its position in the list. Takes ownership of `node`, which is
+ * freed if no node with this ID was pushed; returns false in that case.
+ */
+bool accesskit_tree_update_replace_node(struct accesskit_tree_update *update,
+                                        accesskit_node_id id,
+                                        struct accesskit_node *node);
+
+/**
+ * Removes every node pushed with this ID. Returns false if there was none.
+ */
+bool accesskit_tree_update_remove_node(struct accesskit_tree_update *update,
+                                       accesskit_node_id id);
+
+/**
+ * Returns null if the tree update doesn't have its `tree` field set.
+ * The returned tree is owned by the tree update.
+ */
+const struct accesskit_tree *accesskit_tree_update_get_tree(
+    const struct accesskit_tree_update *update);
+
 void accesskit_tree_update_set_tree(struct accesskit_tree_update *update,
                                     struct accesskit_tree *tree);
 
 void accesskit_tree_update_clear_tree(struct accesskit_tree_update *update);
 
+accesskit_node_id accesskit_tree_update_get_focus(
+    const struct accesskit_tree_update *update);
+
 void accesskit_tree_update_set_focus(struct accesskit_tree_update *update,
                                      accesskit_node_id focus);
 
@@ -2390,6 +3154,186 @@ char *accesskit_tree_update_debug(
 
 void accesskit_action_request_free(struct accesskit_action_request *request);
 
+/**
+ * Computes the WCAG contrast ratio of every text run and label that
+ * isn't hidden, and lists the ones that don't meet `level`.
+ *
+ * Foreground color, background color, font size and font weight are
+ * inherited from the nearest ancestor that sets them. Translucent
+ * colors are blended over the background beneath them, ending with
+ * white if no background is opaque. Nodes without an effective
+ * foreground or background color are skipped. Text is large if its
+ * font size is at least 24px, or at least 18.66px with a font weight
+ * of 700 or more; text without a font size is considered normal.
+ */
+struct accesskit_contrast_findings *accesskit_contrast_audit(
+    const struct accesskit_tree_snapshot *snapshot,
+    accesskit_contrast_level level);
+
+void accesskit_contrast_findings_free(
+    struct accesskit_contrast_findings *findings);
+
+size_t accesskit_contrast_findings_len(
+    const struct accesskit_contrast_findings *findings);
+
+/**
+ * `index` must be lower than the value returned by
+ * `accesskit_contrast_findings_len`.
+ */
+struct accesskit_contrast_finding accesskit_contrast_findings_get(
+    const struct accesskit_contrast_findings *findings, size_t index);
+
+/**
+ * Renders the findings one per line, for instance
+ * `#4 label: #777777 on #ffffff is 4.47:1, AA requires 4.5:1 for normal text`.
+ *
+ * Caller must call `accesskit_string_free` with the return value.
+ */
+char *accesskit_contrast_findings_to_string(
+    const struct accesskit_contrast_findings *findings);
+
+/**
+ * Returns the WCAG contrast ratio between two colors, from 1 to 21.
+ * The alpha channel of both colors is ignored.
+ */
+double accesskit_contrast_ratio(struct accesskit_color first,
+                                struct accesskit_color second);
+
+/**
+ * Lists the differences between two snapshots: nodes that were added,
+ * removed or moved to another parent, and properties whose value
+ * changed. Properties are compared by their rendering in
+ * `accesskit_tree_dump`, with all property groups included, along with
+ * the node's role, name and children. A change of focus shows up as
+ * a change of the `focused` state.
+ */
+struct accesskit_tree_diff *accesskit_tree_diff(
+    const struct accesskit_tree_snapshot *old_snapshot,
+    const struct accesskit_tree_snapshot *new_snapshot);
+
+/**
+ * Lists what applying `update` to `snapshot` would change, without
+ * modifying `snapshot`. Takes ownership of `update`.
+ */
+struct accesskit_tree_diff *accesskit_tree_diff_update(
+    const struct accesskit_tree_snapshot *snapshot,
+    struct accesskit_tree_update *update);
+
+void accesskit_tree_diff_free(struct accesskit_tree_diff *diff);
+
+size_t accesskit_tree_diff_len(const struct accesskit_tree_diff *diff);
+
+/**
+ * `index` must be lower than the value returned by
+ * `accesskit_tree_diff_len`.
+ */
+struct accesskit_tree_change accesskit_tree_diff_get(
+    const struct accesskit_tree_diff *diff, size_t index);
+
+/**
+ * Renders the changes one per line, for instance
+ * `changed #3 toggled: true -> false`.
+ *
+ * Caller must call `accesskit_string_free` with the return value.
+ */
+char *accesskit_tree_diff_to_string(const struct accesskit_tree_diff *diff);
+
+struct accesskit_action_dispatcher *accesskit_action_dispatcher_new(void);
+
+void accesskit_action_dispatcher_free(
+    struct accesskit_action_dispatcher *dispatcher);
+
+/**
+ * Sets the handler for `action` on `node`, replacing any previous one.
+ * Passing a null callback removes the handler.
+ */
+void accesskit_action_dispatcher_set_handler(
+    const struct accesskit_action_dispatcher *dispatcher,
+    accesskit_node_id node, accesskit_action action,
+    accesskit_dispatched_action_handler_callback callback, void *userdata);
+
+/**
+ * Sets the handler for the actions on `node` that have no handler of
+ * their own, replacing any previous one. Passing a null callback
+ * removes the handler.
+ */
+void accesskit_action_dispatcher_set_node_handler(
+    const struct accesskit_action_dispatcher *dispatcher,
+    accesskit_node_id node,
+    accesskit_dispatched_action_handler_callback callback, void *userdata);
+
+/**
+ * Sets the handler tried last for every request. Passing a null
+ * callback removes it.
+ */
+void accesskit_action_dispatcher_set_fallback(
+    const struct accesskit_action_dispatcher *dispatcher,
+    accesskit_dispatched_action_handler_callback callback, void *userdata);
+
+/**
+ * Sets the callback told about requests that no handler took. Passing
+ * a null callback removes it.
+ */
+void accesskit_action_dispatcher_set_unhandled_callback(
+    const struct accesskit_action_dispatcher *dispatcher,
+    accesskit_unhandled_action_callback callback, void *userdata);
+
+/**
+ * Removes all the handlers registered for `node`, to be called when
+ * it is removed from the tree.
+ */
+void accesskit_action_dispatcher_remove_node(
+    const struct accesskit_action_dispatcher *dispatcher,
+    accesskit_node_id node);
+
+/**
+ * Calls the handlers matching `request`, from the one registered for
+ * its target node and action, then the one for its target node, to the
+ * fallback, until one of them handles it. Returns false, after calling
+ * the unhandled callback, if none did. Takes ownership of `request`,
+ * which is freed before returning.
+ */
+bool accesskit_action_dispatcher_dispatch(
+    const struct accesskit_action_dispatcher *dispatcher,
+    struct accesskit_action_request *request);
+
+/**
+ * An action handler calling `accesskit_action_dispatcher_dispatch` on
+ * the dispatcher passed as userdata, to be given to an adapter.
+ */
+void accesskit_action_dispatcher_action_handler(
+    struct accesskit_action_request *request, void *userdata);
+
+/**
+ * Renders the tree in the Graphviz DOT language. Each node is labelled
+ * with its ID, role and name; the focused node has a thicker border
+ * and hidden nodes a dotted one. Parent-child links are solid edges,
+ * and relations such as `labelled_by` or `controls` are dashed edges
+ * labelled with the relation name and colored by relation type.
+ * Relations pointing to nodes that aren't in the tree lead to a red
+ * placeholder node.
+ *
+ * Caller must call `accesskit_string_free` with the return value.
+ */
+char *accesskit_tree_to_dot(const struct accesskit_tree_snapshot *snapshot);
+
+/**
+ * Returns the default options: states, values and bounds are
+ * included, as well as hidden nodes.
+ */
+struct accesskit_tree_dump_options accesskit_tree_dump_options_default(void);
+
+/**
+ * Renders the tree as indented text, one node per line with its role
+ * and name followed by the property groups selected in `options`.
+ * Children are indented by two spaces. The output only depends on the
+ * content of the tree, so it can be compared against golden files.
+ *
+ * Caller must call `accesskit_string_free` with the return value.
+ */
+char *accesskit_tree_dump(const struct accesskit_tree_snapshot *snapshot,
+                          struct accesskit_tree_dump_options options);
+
 struct accesskit_affine accesskit_affine_identity(void);
 
 struct accesskit_affine accesskit_affine_flip_y(void);
@@ -2478,40 +3422,701 @@ bool accesskit_rect_contains(const struct accesskit_rect *rect,
 struct accesskit_rect accesskit_rect_union(const struct accesskit_rect *rect,
                                            struct accesskit_rect other);
 
-struct accesskit_rect accesskit_rect_union_pt(const struct accesskit_rect *rect,
-                                              struct accesskit_point pt);
+struct accesskit_rect accesskit_rect_union_pt(const struct accesskit_rect *rect,
+                                              struct accesskit_point pt);
+
+struct accesskit_rect accesskit_rect_intersect(
+    const struct accesskit_rect *rect, struct accesskit_rect other);
+
+struct accesskit_rect accesskit_rect_translate(
+    struct accesskit_rect rect, struct accesskit_vec2 translation);
+
+struct accesskit_vec2 accesskit_size_to_vec2(struct accesskit_size size);
+
+struct accesskit_size accesskit_size_scale(struct accesskit_size size,
+                                           double scalar);
+
+struct accesskit_size accesskit_size_add(struct accesskit_size a,
+                                         struct accesskit_size b);
+
+struct accesskit_size accesskit_size_sub(struct accesskit_size a,
+                                         struct accesskit_size b);
+
+struct accesskit_point accesskit_vec2_to_point(struct accesskit_vec2 vec2);
+
+struct accesskit_size accesskit_vec2_to_size(struct accesskit_vec2 vec2);
+
+struct accesskit_vec2 accesskit_vec2_add(struct accesskit_vec2 a,
+                                         struct accesskit_vec2 b);
+
+struct accesskit_vec2 accesskit_vec2_sub(struct accesskit_vec2 a,
+                                         struct accesskit_vec2 b);
+
+struct accesskit_vec2 accesskit_vec2_scale(struct accesskit_vec2 vec,
+                                           double scalar);
+
+struct accesskit_vec2 accesskit_vec2_neg(struct accesskit_vec2 vec);
+
+/**
+ * Renders the tree as a static HTML document that browser-based
+ * accessibility tools can inspect. Each node becomes a `div` whose
+ * `id` is `n` followed by the node ID, and relations refer to nodes
+ * by these IDs.
+ *
+ * Roles with an ARIA equivalent are emitted as the `role` attribute.
+ * Other roles are approximated by the closest ARIA role, if any, and
+ * kept in a `data-accesskit-role` attribute holding the role name
+ * in snake case, such as `default_button`.
+ *
+ * Properties map to the corresponding ARIA attributes: `label` to
+ * `aria-label`, `description` to `aria-description`, `tooltip` to
+ * `title`, `toggled` to `aria-checked` (or `aria-pressed` for roles
+ * that can't be checked), and `invalid`, `live`, `has_popup`,
+ * `aria_current`, `auto_complete`, `orientation` and `sort_direction`
+ * to the attribute of the same meaning, with the lowercase variant
+ * name as value. Row and column indices become 1-based. Boolean
+ * states are emitted when set, as `"true"`. The string value becomes
+ * the text content of labels and text inputs, `aria-valuetext` for
+ * range roles such as sliders, and `data-accesskit-value` otherwise.
+ * Focusable nodes get `tabindex="0"`, and the focused node `autofocus`.
+ *
+ * Nodes with bounds are absolute-positioned boxes, relative to their
+ * nearest positioned ancestor, with transforms applied.
+ *
+ * Caller must call `accesskit_string_free` with the return value.
+ */
+char *accesskit_tree_to_html(const struct accesskit_tree_snapshot *snapshot);
+
+/**
+ * Builds a full tree update from an HTML fragment or document, such
+ * as the output of `accesskit_tree_to_html`.
+ *
+ * Each element becomes a node, with IDs assigned from 0 in document
+ * order. The role comes from the `data-accesskit-role` attribute,
+ * the `role` attribute or the tag name, in that order. ARIA attributes
+ * are mapped back to properties as described in
+ * `accesskit_tree_to_html`, and references to other elements through
+ * `aria-labelledby`, `aria-controls` and the like are resolved using
+ * their `id` attribute. The text content of labels and text inputs
+ * becomes their value; elsewhere, each run of text becomes a child
+ * node with the `ACCESSKIT_ROLE_LABEL` role. Elements with the
+ * `tabindex` attribute are focusable, and the element with the
+ * `autofocus` attribute is focused; the root is focused otherwise.
+ * The content of `head`, `script` and `style` elements is ignored.
+ *
+ * Never returns null. Check `accesskit_html_import_error` to find out
+ * whether parsing succeeded.
+ */
+struct accesskit_html_import *accesskit_tree_update_from_html(const char *html,
+                                                              size_t length);
+
+void accesskit_html_import_free(struct accesskit_html_import *import);
+
+/**
+ * Returns null if parsing succeeded, otherwise a description
+ * of the error, including its line and column.
+ *
+ * Caller must call `accesskit_string_free` with the return value.
+ */
+char *accesskit_html_import_error(const struct accesskit_html_import *import);
+
+/**
+ * Returns the 1-based line of the parse error, or 0 if parsing succeeded.
+ */
+size_t accesskit_html_import_error_line(
+    const struct accesskit_html_import *import);
+
+/**
+ * Returns the 1-based column of the parse error, counted in characters,
+ * or 0 if parsing succeeded.
+ */
+size_t accesskit_html_import_error_column(
+    const struct accesskit_html_import *import);
+
+/**
+ * Returns null if parsing failed or if the tree update was already taken.
+ * Ownership of the returned value is transferred to the caller.
+ */
+struct accesskit_tree_update *accesskit_html_import_take_tree_update(
+    struct accesskit_html_import *import);
+
+/**
+ * Returns the ID of the node created for the element with the given
+ * `id` attribute.
+ */
+struct accesskit_opt_node_id accesskit_html_import_node_id(
+    const struct accesskit_html_import *import, const char *id);
+
+/**
+ * Returns the ID of the node created for the element with the given
+ * `id` attribute.
+ */
+struct accesskit_opt_node_id accesskit_html_import_node_id_with_length(
+    const struct accesskit_html_import *import, const char *id, size_t length);
+
+struct accesskit_imm_context *accesskit_imm_context_new(void);
+
+void accesskit_imm_context_free(struct accesskit_imm_context *ctx);
+
+/**
+ * Begins a node, as a child of the innermost node that hasn't ended.
+ * Returns the new node so that its properties can be set, except for
+ * its children which are filled in from the nodes declared until the
+ * matching call to `accesskit_imm_end_node`.
+ *
+ * The returned node is owned by the context, and is only valid until
+ * the next call to `accesskit_imm_begin_node` or `accesskit_imm_end_node`.
+ *
+ * Returns null if `key` is already used by a sibling in this frame, if
+ * this is a second top-level node, or if the parent was rejected. The
+ * rejected node, along with the nodes declared inside it, is left out
+ * of the frame, but must still be ended.
+ */
+struct accesskit_node *accesskit_imm_begin_node(
+    struct accesskit_imm_context *ctx, const char *key, accesskit_role role);
+
+/**
+ * Ends the innermost node that hasn't ended. Returns false if there is
+ * no such node.
+ */
+bool accesskit_imm_end_node(struct accesskit_imm_context *ctx);
+
+/**
+ * Returns the ID of the innermost node that hasn't ended, for use in
+ * relations between nodes and to recognize the targets of action
+ * requests. Has no value if there is no such node.
+ */
+struct accesskit_opt_node_id accesskit_imm_current_id(
+    const struct accesskit_imm_context *ctx);
+
+/**
+ * Gives the focus to the innermost node that hasn't ended. Without a
+ * call to this function during a frame, the root has the focus.
+ * Returns false, leaving the focus unchanged, if there is no such
+ * node or it was rejected.
+ */
+bool accesskit_imm_set_focus(struct accesskit_imm_context *ctx);
+
+/**
+ * Ends the frame and returns an update holding the nodes that differ
+ * from the previous frame. Nodes that are no longer declared are
+ * dropped by adapters since their parent, which changed, no longer
+ * lists them. The first frame, and any frame whose root has another
+ * key, produces a full update.
+ *
+ * The returned update should be given to an adapter, or else the next
+ * updates will miss what changed in this one; use
+ * `accesskit_imm_full_update` to bring an adapter up to date.
+ *
+ * Returns null if no node was declared. Also returns null if a node
+ * hasn't ended, in which case the frame goes on.
+ */
+struct accesskit_tree_update *accesskit_imm_end_frame(
+    struct accesskit_imm_context *ctx);
+
+/**
+ * Returns an update holding the whole tree as of the last frame, or
+ * null if no frame has ended yet.
+ */
+struct accesskit_tree_update *accesskit_imm_full_update(
+    const struct accesskit_imm_context *ctx);
+
+/**
+ * Returns a stable identifier for the rule, such as `control-name`.
+ * The returned string is static and must not be freed.
+ */
+const char *accesskit_lint_rule_id(accesskit_lint_rule rule);
+
+/**
+ * Creates a linter with all rules enabled.
+ */
+struct accesskit_linter *accesskit_linter_new(void);
+
+void accesskit_linter_free(struct accesskit_linter *linter);
+
+void accesskit_linter_set_rule_enabled(struct accesskit_linter *linter,
+                                       accesskit_lint_rule rule, bool enabled);
+
+bool accesskit_linter_is_rule_enabled(const struct accesskit_linter *linter,
+                                      accesskit_lint_rule rule);
+
+/**
+ * Runs the enabled rules on every node of the tree.
+ */
+struct accesskit_lint_findings *accesskit_lint(
+    const struct accesskit_linter *linter,
+    const struct accesskit_tree_snapshot *snapshot);
+
+/**
+ * Runs the enabled rules on the tree described by a full tree update,
+ * such as the one returned from an activation handler.
+ *
+ * Returns null if the tree update doesn't have its `tree` field set.
+ */
+struct accesskit_lint_findings *accesskit_lint_tree_update(
+    const struct accesskit_linter *linter,
+    const struct accesskit_tree_update *update);
+
+void accesskit_lint_findings_free(struct accesskit_lint_findings *findings);
+
+size_t accesskit_lint_findings_len(
+    const struct accesskit_lint_findings *findings);
+
+/**
+ * `index` must be lower than the value returned by
+ * `accesskit_lint_findings_len`.
+ */
+struct accesskit_lint_finding accesskit_lint_findings_get(
+    const struct accesskit_lint_findings *findings, size_t index);
+
+/**
+ * Renders the findings one per line, for instance
+ * `error #4 [image-name]: image has no name`.
+ *
+ * Caller must call `accesskit_string_free` with the return value.
+ */
+char *accesskit_lint_findings_to_string(
+    const struct accesskit_lint_findings *findings);
+
+/**
+ * Creates a pool that keeps at most `max_updates` released updates.
+ */
+struct accesskit_tree_update_pool *accesskit_tree_update_pool_new(
+    size_t max_updates);
+
+void accesskit_tree_update_pool_free(struct accesskit_tree_update_pool *pool);
+
+/**
+ * Returns an empty update for the root tree, reusing one previously
+ * released to the pool if there is any, so that pushing no more nodes
+ * than it held before doesn't allocate memory for the list of nodes.
+ *
+ * The returned update must be freed, released to the pool or given
+ * to a function taking ownership of it.
+ */
+struct accesskit_tree_update *accesskit_tree_update_pool_acquire(
+    struct accesskit_tree_update_pool *pool, accesskit_node_id focus);
+
+/**
+ * Clears `update` and keeps it for a later call to
+ * `accesskit_tree_update_pool_acquire`, or frees it if the pool is full.
+ * Takes ownership of `update`.
+ */
+void accesskit_tree_update_pool_release(struct accesskit_tree_update_pool *pool,
+                                        struct accesskit_tree_update *update);
+
+/**
+ * Same as `accesskit_tree_update_merge`, except that `src` is released
+ * to the pool instead of being freed.
+ */
+bool accesskit_tree_update_pool_merge(struct accesskit_tree_update_pool *pool,
+                                      struct accesskit_tree_update *dst,
+                                      struct accesskit_tree_update *src);
+
+/**
+ * Same as `accesskit_tree_update_queue_push`, except that what is left
+ * of `update` is released to the pool instead of being freed.
+ */
+bool accesskit_tree_update_pool_push_to_queue(
+    struct accesskit_tree_update_pool *pool,
+    struct accesskit_tree_update_queue *queue,
+    struct accesskit_tree_update *update);
+
+/**
+ * Same as `accesskit_tree_update_queue_clear`, except that the pending
+ * update is released to the pool instead of being freed.
+ */
+void accesskit_tree_update_pool_clear_queue(
+    struct accesskit_tree_update_pool *pool,
+    struct accesskit_tree_update_queue *queue);
+
+/**
+ * Same as `accesskit_tree_snapshot_update`, except that `update` is
+ * released to the pool instead of being freed.
+ */
+void accesskit_tree_update_pool_update_snapshot(
+    struct accesskit_tree_update_pool *pool,
+    struct accesskit_tree_snapshot *snapshot,
+    struct accesskit_tree_update *update);
+
+struct accesskit_node_id_registry *accesskit_node_id_registry_new(void);
+
+void accesskit_node_id_registry_free(
+    struct accesskit_node_id_registry *registry);
+
+/**
+ * Returns the ID of the object at `path`, associating it with `object`.
+ * The ID is a hash of the path, unless it is taken by another path or
+ * object, in which case the next free ID is used and the collision is
+ * counted. Calling this function again with the same path returns the
+ * same ID, and replaces the object, until the ID is recycled.
+ */
+accesskit_node_id accesskit_node_id_registry_id_for_path(
+    struct accesskit_node_id_registry *registry, const char *path,
+    void *object);
+
+/**
+ * Returns the ID of `object`, allocating one if this pointer and
+ * generation weren't seen before. `generation` must change whenever
+ * a new object is allocated at the address of a destroyed one.
+ */
+accesskit_node_id accesskit_node_id_registry_id_for_object(
+    struct accesskit_node_id_registry *registry, void *object,
+    uint64_t generation);
+
+/**
+ * Returns the object associated with `id`, for instance the target
+ * of an action request, or null if the ID is unknown or was released.
+ */
+void *accesskit_node_id_registry_object(
+    const struct accesskit_node_id_registry *registry, accesskit_node_id id);
+
+/**
+ * Forgets the object associated with `id`, to be called when it is
+ * destroyed. The ID is recycled right away if it isn't in the tree,
+ * or else once a noted update has removed it. Returns false if the ID
+ * is unknown or was already released.
+ */
+bool accesskit_node_id_registry_release(
+    struct accesskit_node_id_registry *registry, accesskit_node_id id);
+
+/**
+ * Records which nodes are in the tree after `update` is applied. Call
+ * this with every update, including initial trees, right before giving
+ * it to an adapter.
+ */
+void accesskit_node_id_registry_note_update(
+    struct accesskit_node_id_registry *registry,
+    const struct accesskit_tree_update *update);
+
+/**
+ * Returns the number of paths whose hash was already taken when they
+ * were registered.
+ */
+size_t accesskit_node_id_registry_collision_count(
+    const struct accesskit_node_id_registry *registry);
+
+/**
+ * Creates an empty retained tree. The focus is initially on the root.
+ * Takes ownership of `tree`.
+ */
+struct accesskit_retained_tree *accesskit_retained_tree_new(
+    struct accesskit_tree *tree);
+
+void accesskit_retained_tree_free(struct accesskit_retained_tree *tree);
+
+/**
+ * Takes ownership of `tree`.
+ */
+void accesskit_retained_tree_set_tree(
+    struct accesskit_retained_tree *retained_tree, struct accesskit_tree *tree);
+
+void accesskit_retained_tree_set_focus(struct accesskit_retained_tree *tree,
+                                       accesskit_node_id focus);
+
+/**
+ * Adds a node or replaces the node with the same ID.
+ * Takes ownership of `node`.
+ */
+void accesskit_retained_tree_set_node(struct accesskit_retained_tree *tree,
+                                      accesskit_node_id id,
+                                      struct accesskit_node *node);
+
+/**
+ * Adds a node and appends it to the children of `parent`. Takes
+ * ownership of `node`.
+ *
+ * Returns false, freeing `node`, if there is no node with the ID
+ * of `parent`.
+ */
+bool accesskit_retained_tree_append_child(struct accesskit_retained_tree *tree,
+                                          accesskit_node_id parent,
+                                          accesskit_node_id id,
+                                          struct accesskit_node *node);
+
+/**
+ * Returns null if there is no node with this ID.
+ * The returned node is owned by the retained tree.
+ */
+const struct accesskit_node *accesskit_retained_tree_node(
+    const struct accesskit_retained_tree *tree, accesskit_node_id id);
+
+/**
+ * Returns the node with this ID so that it can be modified in place,
+ * and marks it as changed. Returns null if there is no such node.
+ * The returned node is owned by the retained tree.
+ */
+struct accesskit_node *accesskit_retained_tree_node_mut(
+    struct accesskit_retained_tree *tree, accesskit_node_id id);
+
+/**
+ * Removes a node and takes it out of the children of its parent.
+ * Its descendants are no longer part of the tree, but are kept until
+ * removed, so that they can be attached somewhere else. Returns false
+ * if there is no node with this ID.
+ */
+bool accesskit_retained_tree_remove_node(struct accesskit_retained_tree *tree,
+                                         accesskit_node_id id);
+
+/**
+ * Returns whether nodes were modified since the last update was
+ * produced. The next update may still be empty if the modifications
+ * cancelled each other out.
+ */
+bool accesskit_retained_tree_has_changes(
+    const struct accesskit_retained_tree *tree);
+
+/**
+ * Produces the smallest update bringing a platform adapter from the
+ * state of the previous update to the current one: the nodes that were
+ * modified and differ from what was last submitted, including parents
+ * whose list of children changed, and the nodes that became reachable
+ * from the root. Nodes that aren't reachable from the root are left
+ * out. The first update includes the whole tree.
+ *
+ * Every child listed by a reachable node must be in the tree, and so
+ * must the focus.
+ */
+struct accesskit_tree_update *accesskit_retained_tree_take_update(
+    struct accesskit_retained_tree *tree);
+
+/**
+ * Produces an update holding the whole tree, to give to an adapter that
+ * has just been activated. Later updates are computed from this state.
+ */
+struct accesskit_tree_update *accesskit_retained_tree_take_full_update(
+    struct accesskit_retained_tree *tree);
+
+/**
+ * A tree update factory calling `accesskit_retained_tree_take_update`
+ * on the retained tree passed as userdata, to be given to the
+ * `update_if_active` function of an adapter.
+ */
+struct accesskit_tree_update *accesskit_retained_tree_update_factory(
+    accesskit_tree_update_factory_userdata userdata);
+
+/**
+ * Builds a snapshot from a full tree update, such as the one returned
+ * from an activation handler. Takes ownership of `update`.
+ *
+ * Returns null if the tree update doesn't have its `tree` field set.
+ */
+struct accesskit_tree_snapshot *accesskit_tree_snapshot_new(
+    struct accesskit_tree_update *update);
+
+void accesskit_tree_snapshot_free(struct accesskit_tree_snapshot *snapshot);
+
+struct accesskit_tree_snapshot *accesskit_tree_snapshot_clone(
+    const struct accesskit_tree_snapshot *snapshot);
+
+/**
+ * Applies an incremental tree update. Takes ownership of `update`.
+ */
+void accesskit_tree_snapshot_update(struct accesskit_tree_snapshot *snapshot,
+                                    struct accesskit_tree_update *update);
+
+accesskit_node_id accesskit_tree_snapshot_root(
+    const struct accesskit_tree_snapshot *snapshot);
+
+accesskit_node_id accesskit_tree_snapshot_focus(
+    const struct accesskit_tree_snapshot *snapshot);
+
+/**
+ * Returns the number of nodes reachable from the root.
+ */
+size_t accesskit_tree_snapshot_len(
+    const struct accesskit_tree_snapshot *snapshot);
+
+/**
+ * Returns null if there is no node with this ID in the tree.
+ * The returned node is owned by the snapshot.
+ */
+const struct accesskit_node *accesskit_tree_snapshot_node(
+    const struct accesskit_tree_snapshot *snapshot, accesskit_node_id id);
+
+struct accesskit_opt_node_id accesskit_tree_snapshot_parent(
+    const struct accesskit_tree_snapshot *snapshot, accesskit_node_id id);
+
+#if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
+     defined(__NetBSD__) || defined(__OpenBSD__))
+/**
+ * Calls the handler, then frees the task. Takes ownership of `task`.
+ */
+void accesskit_handler_task_run(struct accesskit_handler_task *task);
+#endif
+
+#if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
+     defined(__NetBSD__) || defined(__OpenBSD__))
+/**
+ * Frees the task without calling the handler, for instance when the
+ * application is shutting down.
+ */
+void accesskit_handler_task_free(struct accesskit_handler_task *task);
+#endif
+
+/**
+ * Creates a stand-in for a platform adapter that lets tests inspect
+ * the tree and perform actions, using the same handlers as a real
+ * adapter. The activation handler is called immediately.
+ */
+struct accesskit_test_driver *accesskit_test_driver_new(
+    accesskit_activation_handler_callback activation_handler,
+    void *activation_handler_userdata,
+    accesskit_action_handler_callback action_handler,
+    void *action_handler_userdata);
+
+void accesskit_test_driver_free(struct accesskit_test_driver *driver);
+
+/**
+ * Calls the activation handler again if it previously returned null.
+ * Returns whether the driver now has a tree.
+ */
+bool accesskit_test_driver_activate(struct accesskit_test_driver *driver);
+
+void accesskit_test_driver_update_if_active(
+    struct accesskit_test_driver *driver,
+    accesskit_tree_update_factory update_factory,
+    void *update_factory_userdata);
+
+/**
+ * Returns null if the driver doesn't have a tree yet.
+ * The returned snapshot is owned by the driver and is only valid
+ * until the next update.
+ */
+const struct accesskit_tree_snapshot *accesskit_test_driver_snapshot(
+    const struct accesskit_test_driver *driver);
+
+/**
+ * Returns null if `selector` is valid, otherwise a description
+ * of the first syntax error.
+ *
+ * Caller must call `accesskit_string_free` with the return value.
+ */
+char *accesskit_selector_error(const char *selector);
+
+/**
+ * Returns null if `selector` is valid, otherwise a description
+ * of the first syntax error.
+ *
+ * Caller must call `accesskit_string_free` with the return value.
+ */
+char *accesskit_selector_error_with_length(const char *selector, size_t length);
+
+/**
+ * Finds the nodes matching `selector`, in document order.
+ *
+ * A selector is a list of compound selectors separated by `>`, which
+ * matches children, or by whitespace, which matches descendants,
+ * as in `window > list > list_item`. A compound selector is made of
+ * an optional role name, attribute test or `*`, followed by any number
+ * of bracketed attribute tests and `:nth(n)` filters, which keep
+ * the n-th match counting from 1. Filters apply from left to right.
+ *
+ * Attribute tests take the form `attribute=value` or `attribute` alone,
+ * and values may be quoted. The supported attributes are `role`, `id`,
+ * `author_id`, `name` (the computed name), `label`, `value`,
+ * `description`, `placeholder`, `tooltip`, `class_name`, `toggled`
+ * (or `checked`) and the boolean states `expanded`, `selected`,
+ * `disabled`, `hidden`, `required`, `read_only`, `focused`
+ * and `focusable`. For example: `role=button[label="OK"]`,
+ * `author_id=save` or `list_item:nth(3)`.
+ *
+ * Returns null if the selector is invalid; see `accesskit_selector_error`.
+ * Caller must call `accesskit_node_id_list_free` with the return value.
+ */
+struct accesskit_node_id_list *accesskit_test_driver_query(
+    const struct accesskit_test_driver *driver, const char *selector);
 
-struct accesskit_rect accesskit_rect_intersect(
-    const struct accesskit_rect *rect, struct accesskit_rect other);
+/**
+ * Returns null if the selector is invalid; see `accesskit_selector_error`.
+ * Caller must call `accesskit_node_id_list_free` with the return value.
+ */
+struct accesskit_node_id_list *accesskit_test_driver_query_with_length(
+    const struct accesskit_test_driver *driver, const char *selector,
+    size_t length);
 
-struct accesskit_rect accesskit_rect_translate(
-    struct accesskit_rect rect, struct accesskit_vec2 translation);
+/**
+ * Returns the first node matching `selector`, if any.
+ */
+struct accesskit_opt_node_id accesskit_test_driver_query_one(
+    const struct accesskit_test_driver *driver, const char *selector);
 
-struct accesskit_vec2 accesskit_size_to_vec2(struct accesskit_size size);
+/**
+ * Returns the first node matching `selector`, if any.
+ */
+struct accesskit_opt_node_id accesskit_test_driver_query_one_with_length(
+    const struct accesskit_test_driver *driver, const char *selector,
+    size_t length);
 
-struct accesskit_size accesskit_size_scale(struct accesskit_size size,
-                                           double scalar);
+/**
+ * Sends an action request to the action handler, as an assistive
+ * technology would. Returns false without calling the handler
+ * if `target` isn't in the tree.
+ */
+bool accesskit_test_driver_do_action(struct accesskit_test_driver *driver,
+                                     accesskit_node_id target,
+                                     accesskit_action action);
 
-struct accesskit_size accesskit_size_add(struct accesskit_size a,
-                                         struct accesskit_size b);
+/**
+ * Like `accesskit_test_driver_do_action`, with a string value attached
+ * to the request, for instance for `ACCESSKIT_ACTION_SET_VALUE`.
+ *
+ * Caller is responsible for freeing the memory pointed by `value`.
+ */
+bool accesskit_test_driver_do_action_with_value(
+    struct accesskit_test_driver *driver, accesskit_node_id target,
+    accesskit_action action, const char *value);
 
-struct accesskit_size accesskit_size_sub(struct accesskit_size a,
-                                         struct accesskit_size b);
+/**
+ * Like `accesskit_test_driver_do_action`, with a numeric value attached
+ * to the request.
+ */
+bool accesskit_test_driver_do_action_with_numeric_value(
+    struct accesskit_test_driver *driver, accesskit_node_id target,
+    accesskit_action action, double value);
 
-struct accesskit_point accesskit_vec2_to_point(struct accesskit_vec2 vec2);
+/**
+ * Requests `ACCESSKIT_ACTION_CUSTOM_ACTION` with the given custom
+ * action ID.
+ */
+bool accesskit_test_driver_do_custom_action(
+    struct accesskit_test_driver *driver, accesskit_node_id target, int32_t id);
 
-struct accesskit_size accesskit_vec2_to_size(struct accesskit_vec2 vec2);
+void accesskit_tree_update_diagnostics_free(
+    struct accesskit_tree_update_diagnostics *diagnostics);
 
-struct accesskit_vec2 accesskit_vec2_add(struct accesskit_vec2 a,
-                                         struct accesskit_vec2 b);
+size_t accesskit_tree_update_diagnostics_len(
+    const struct accesskit_tree_update_diagnostics *diagnostics);
 
-struct accesskit_vec2 accesskit_vec2_sub(struct accesskit_vec2 a,
-                                         struct accesskit_vec2 b);
+/**
+ * `index` must be lower than the value returned by
+ * `accesskit_tree_update_diagnostics_len`.
+ */
+struct accesskit_tree_update_diagnostic accesskit_tree_update_diagnostics_get(
+    const struct accesskit_tree_update_diagnostics *diagnostics, size_t index);
 
-struct accesskit_vec2 accesskit_vec2_scale(struct accesskit_vec2 vec,
-                                           double scalar);
+/**
+ * Renders the diagnostics one per line, for instance
+ * `[missing-child] #1 lists child #7, which is not in the update`.
+ *
+ * Caller must call `accesskit_string_free` with the return value.
+ */
+char *accesskit_tree_update_diagnostics_to_string(
+    const struct accesskit_tree_update_diagnostics *diagnostics);
 
-struct accesskit_vec2 accesskit_vec2_neg(struct accesskit_vec2 vec);
+/**
+ * Checks that `update` can be applied to `previous_snapshot`, which
+ * may be null if `update` describes an initial tree. The checks cover
+ * children that were never pushed, a root or focus that isn't in the
+ * resulting tree, cycles, nodes listed under two parents, and pushed
+ * nodes that can't be reached from the root. `previous_snapshot` is
+ * ignored if its tree ID differs from the one of `update`.
+ *
+ * Returns null if the update is valid.
+ */
+struct accesskit_tree_update_diagnostics *accesskit_tree_update_validate(
+    const struct accesskit_tree_update *update,
+    const struct accesskit_tree_snapshot *previous_snapshot);
 
 #if defined(__ANDROID__)
 struct accesskit_android_platform_action *
@@ -2540,6 +4145,17 @@ struct accesskit_android_adapter *accesskit_android_adapter_new(void);
 void accesskit_android_adapter_free(struct accesskit_android_adapter *adapter);
 #endif
 
+#if defined(__ANDROID__)
+/**
+ * Validates every tree update given to the adapter and reports the
+ * invalid ones to `handler` instead of applying them. Pass a null
+ * `handler` to turn validation off.
+ */
+void accesskit_android_adapter_set_validation_handler(
+    struct accesskit_android_adapter *adapter,
+    accesskit_validation_handler_callback handler, void *userdata);
+#endif
+
 #if defined(__ANDROID__)
 /**
  * You must call `accesskit_android_queued_events_raise` on the returned
@@ -2552,6 +4168,23 @@ accesskit_android_adapter_update_if_active(
     void *update_factory_userdata);
 #endif
 
+#if defined(__ANDROID__)
+/**
+ * Gives the adapter the initial tree that its activation handler didn't
+ * return, which must be a full tree update. Passing one to
+ * `accesskit_android_adapter_update_if_active` works too. Takes ownership
+ * of `update`, which is dropped if the adapter isn't waiting for its
+ * initial tree.
+ *
+ * You must call `accesskit_android_queued_events_raise` on the returned
+ * pointer. It can be null if the adapter wasn't waiting for its initial tree.
+ */
+struct accesskit_android_queued_events *
+accesskit_android_adapter_provide_initial_tree(
+    struct accesskit_android_adapter *adapter,
+    struct accesskit_tree_update *update);
+#endif
+
 #if defined(__ANDROID__)
 jobject accesskit_android_adapter_create_accessibility_node_info(
     struct accesskit_android_adapter *adapter,
@@ -2608,6 +4241,17 @@ void accesskit_android_injecting_adapter_free(
     struct accesskit_android_injecting_adapter *adapter);
 #endif
 
+#if defined(__ANDROID__)
+/**
+ * Validates every tree update given to the adapter and reports the
+ * invalid ones to `handler` instead of applying them. Pass a null
+ * `handler` to turn validation off.
+ */
+void accesskit_android_injecting_adapter_set_validation_handler(
+    struct accesskit_android_injecting_adapter *adapter,
+    accesskit_validation_handler_callback handler, void *userdata);
+#endif
+
 #if defined(__ANDROID__)
 void accesskit_android_injecting_adapter_update_if_active(
     struct accesskit_android_injecting_adapter *adapter,
@@ -2615,6 +4259,19 @@ void accesskit_android_injecting_adapter_update_if_active(
     void *update_factory_userdata);
 #endif
 
+#if defined(__ANDROID__)
+/**
+ * Gives the adapter the initial tree that its activation handler didn't
+ * return, which must be a full tree update. Passing one to
+ * `accesskit_android_injecting_adapter_update_if_active` works too. Takes
+ * ownership of `update`, which is dropped, returning false, if the adapter
+ * isn't waiting for its initial tree.
+ */
+bool accesskit_android_injecting_adapter_provide_initial_tree(
+    struct accesskit_android_injecting_adapter *adapter,
+    struct accesskit_tree_update *update);
+#endif
+
 #if (defined(ACCESSKIT_IOS) || defined(ACCESSKIT_TVOS) || \
      defined(ACCESSKIT_VISIONOS) || defined(ACCESSKIT_WATCHOS))
 /**
@@ -2648,6 +4305,18 @@ struct accesskit_ios_adapter *accesskit_ios_adapter_new(
 void accesskit_ios_adapter_free(struct accesskit_ios_adapter *adapter);
 #endif
 
+#if (defined(ACCESSKIT_IOS) || defined(ACCESSKIT_TVOS) || \
+     defined(ACCESSKIT_VISIONOS) || defined(ACCESSKIT_WATCHOS))
+/**
+ * Validates every tree update given to the adapter and reports the
+ * invalid ones to `handler` instead of applying them. Pass a null
+ * `handler` to turn validation off.
+ */
+void accesskit_ios_adapter_set_validation_handler(
+    struct accesskit_ios_adapter *adapter,
+    accesskit_validation_handler_callback handler, void *userdata);
+#endif
+
 #if (defined(ACCESSKIT_IOS) || defined(ACCESSKIT_TVOS) || \
      defined(ACCESSKIT_VISIONOS) || defined(ACCESSKIT_WATCHOS))
 /**
@@ -2660,6 +4329,23 @@ struct accesskit_ios_queued_events *accesskit_ios_adapter_update_if_active(
     void *update_factory_userdata);
 #endif
 
+#if (defined(ACCESSKIT_IOS) || defined(ACCESSKIT_TVOS) || \
+     defined(ACCESSKIT_VISIONOS) || defined(ACCESSKIT_WATCHOS))
+/**
+ * Gives the adapter the initial tree that its activation handler didn't
+ * return, which must be a full tree update. Passing one to
+ * `accesskit_ios_adapter_update_if_active` works too. Takes ownership of
+ * `update`, which is dropped if the adapter isn't waiting for its initial
+ * tree.
+ *
+ * You must call `accesskit_ios_queued_events_raise` on the returned pointer. It
+ * can be null if the adapter wasn't waiting for its initial tree.
+ */
+struct accesskit_ios_queued_events *accesskit_ios_adapter_provide_initial_tree(
+    struct accesskit_ios_adapter *adapter,
+    struct accesskit_tree_update *update);
+#endif
+
 #if (defined(ACCESSKIT_IOS) || defined(ACCESSKIT_TVOS) || \
      defined(ACCESSKIT_VISIONOS) || defined(ACCESSKIT_WATCHOS))
 /**
@@ -2762,6 +4448,18 @@ void accesskit_ios_subclassing_adapter_free(
     struct accesskit_ios_subclassing_adapter *adapter);
 #endif
 
+#if (defined(ACCESSKIT_IOS) || defined(ACCESSKIT_TVOS) || \
+     defined(ACCESSKIT_VISIONOS) || defined(ACCESSKIT_WATCHOS))
+/**
+ * Validates every tree update given to the adapter and reports the
+ * invalid ones to `handler` instead of applying them. Pass a null
+ * `handler` to turn validation off.
+ */
+void accesskit_ios_subclassing_adapter_set_validation_handler(
+    struct accesskit_ios_subclassing_adapter *adapter,
+    accesskit_validation_handler_callback handler, void *userdata);
+#endif
+
 #if (defined(ACCESSKIT_IOS) || defined(ACCESSKIT_TVOS) || \
      defined(ACCESSKIT_VISIONOS) || defined(ACCESSKIT_WATCHOS))
 /**
@@ -2775,6 +4473,24 @@ accesskit_ios_subclassing_adapter_update_if_active(
     void *update_factory_userdata);
 #endif
 
+#if (defined(ACCESSKIT_IOS) || defined(ACCESSKIT_TVOS) || \
+     defined(ACCESSKIT_VISIONOS) || defined(ACCESSKIT_WATCHOS))
+/**
+ * Gives the adapter the initial tree that its activation handler didn't
+ * return, which must be a full tree update. Passing one to
+ * `accesskit_ios_subclassing_adapter_update_if_active` works too. Takes
+ * ownership of `update`, which is dropped if the adapter isn't waiting for
+ * its initial tree.
+ *
+ * You must call `accesskit_ios_queued_events_raise` on the returned pointer. It
+ * can be null if the adapter wasn't waiting for its initial tree.
+ */
+struct accesskit_ios_queued_events *
+accesskit_ios_subclassing_adapter_provide_initial_tree(
+    struct accesskit_ios_subclassing_adapter *adapter,
+    struct accesskit_tree_update *update);
+#endif
+
 #if defined(ACCESSKIT_MACOS)
 /**
  * Memory is also freed when calling this function.
@@ -2799,6 +4515,17 @@ struct accesskit_macos_adapter *accesskit_macos_adapter_new(
 void accesskit_macos_adapter_free(struct accesskit_macos_adapter *adapter);
 #endif
 
+#if defined(ACCESSKIT_MACOS)
+/**
+ * Validates every tree update given to the adapter and reports the
+ * invalid ones to `handler` instead of applying them. Pass a null
+ * `handler` to turn validation off.
+ */
+void accesskit_macos_adapter_set_validation_handler(
+    struct accesskit_macos_adapter *adapter,
+    accesskit_validation_handler_callback handler, void *userdata);
+#endif
+
 #if defined(ACCESSKIT_MACOS)
 /**
  * You must call `accesskit_macos_queued_events_raise` on the returned pointer.
@@ -2810,6 +4537,23 @@ struct accesskit_macos_queued_events *accesskit_macos_adapter_update_if_active(
     void *update_factory_userdata);
 #endif
 
+#if defined(ACCESSKIT_MACOS)
+/**
+ * Gives the adapter the initial tree that its activation handler didn't
+ * return, which must be a full tree update. Passing one to
+ * `accesskit_macos_adapter_update_if_active` works too. Takes ownership of
+ * `update`, which is dropped if the adapter isn't waiting for its initial
+ * tree.
+ *
+ * You must call `accesskit_macos_queued_events_raise` on the returned pointer.
+ * It can be null if the adapter wasn't waiting for its initial tree.
+ */
+struct accesskit_macos_queued_events *
+accesskit_macos_adapter_provide_initial_tree(
+    struct accesskit_macos_adapter *adapter,
+    struct accesskit_tree_update *update);
+#endif
+
 #if defined(ACCESSKIT_MACOS)
 /**
  * Update the tree state based on whether the window is focused.
@@ -2901,6 +4645,17 @@ void accesskit_macos_subclassing_adapter_free(
     struct accesskit_macos_subclassing_adapter *adapter);
 #endif
 
+#if defined(ACCESSKIT_MACOS)
+/**
+ * Validates every tree update given to the adapter and reports the
+ * invalid ones to `handler` instead of applying them. Pass a null
+ * `handler` to turn validation off.
+ */
+void accesskit_macos_subclassing_adapter_set_validation_handler(
+    struct accesskit_macos_subclassing_adapter *adapter,
+    accesskit_validation_handler_callback handler, void *userdata);
+#endif
+
 #if defined(ACCESSKIT_MACOS)
 /**
  * You must call `accesskit_macos_queued_events_raise` on the returned pointer.
@@ -2913,6 +4668,23 @@ accesskit_macos_subclassing_adapter_update_if_active(
     void *update_factory_userdata);
 #endif
 
+#if defined(ACCESSKIT_MACOS)
+/**
+ * Gives the adapter the initial tree that its activation handler didn't
+ * return, which must be a full tree update. Passing one to
+ * `accesskit_macos_subclassing_adapter_update_if_active` works too. Takes
+ * ownership of `update`, which is dropped if the adapter isn't waiting for
+ * its initial tree.
+ *
+ * You must call `accesskit_macos_queued_events_raise` on the returned pointer.
+ * It can be null if the adapter wasn't waiting for its initial tree.
+ */
+struct accesskit_macos_queued_events *
+accesskit_macos_subclassing_adapter_provide_initial_tree(
+    struct accesskit_macos_subclassing_adapter *adapter,
+    struct accesskit_tree_update *update);
+#endif
+
 #if defined(ACCESSKIT_MACOS)
 /**
  * Update the tree state based on whether the window is focused.
@@ -2982,11 +4754,87 @@ struct accesskit_unix_adapter *accesskit_unix_adapter_new(
     void *deactivation_handler_userdata);
 #endif
 
+#if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
+     defined(__NetBSD__) || defined(__OpenBSD__))
+/**
+ * Same as `accesskit_unix_adapter_new`, except that each handler comes
+ * with an optional callback freeing its userdata. It is called once,
+ * possibly from another thread, when the library drops the handler,
+ * which may be after `accesskit_unix_adapter_free` returns.
+ */
+struct accesskit_unix_adapter *accesskit_unix_adapter_new_with_destroy_notify(
+    accesskit_activation_handler_callback activation_handler,
+    void *activation_handler_userdata,
+    accesskit_destroy_notify_callback activation_handler_destroy_notify,
+    accesskit_action_handler_callback action_handler,
+    void *action_handler_userdata,
+    accesskit_destroy_notify_callback action_handler_destroy_notify,
+    accesskit_deactivation_handler_callback deactivation_handler,
+    void *deactivation_handler_userdata,
+    accesskit_destroy_notify_callback deactivation_handler_destroy_notify);
+#endif
+
+#if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
+     defined(__NetBSD__) || defined(__OpenBSD__))
+/**
+ * Creates an adapter that gives every call to its handlers to
+ * `dispatcher`, which must run them on the thread that owns the
+ * adapter. The tree returned by the activation handler is given to the
+ * adapter once the call completes, if the adapter still exists. If
+ * `dispatcher` is null, this is the same as
+ * `accesskit_unix_adapter_new_with_destroy_notify`.
+ *
+ * The destroy notify callbacks are the same as in
+ * `accesskit_unix_adapter_new_with_destroy_notify`, and may be null.
+ * Since tasks hold the handlers, a handler may also be dropped when
+ * its pending tasks are run or freed.
+ */
+struct accesskit_unix_adapter *accesskit_unix_adapter_new_with_dispatcher(
+    accesskit_activation_handler_callback activation_handler,
+    void *activation_handler_userdata,
+    accesskit_destroy_notify_callback activation_handler_destroy_notify,
+    accesskit_action_handler_callback action_handler,
+    void *action_handler_userdata,
+    accesskit_destroy_notify_callback action_handler_destroy_notify,
+    accesskit_deactivation_handler_callback deactivation_handler,
+    void *deactivation_handler_userdata,
+    accesskit_destroy_notify_callback deactivation_handler_destroy_notify,
+    accesskit_handler_dispatcher_callback dispatcher,
+    void *dispatcher_userdata);
+#endif
+
+#if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
+     defined(__NetBSD__) || defined(__OpenBSD__))
+/**
+ * Creates an adapter whose handlers push events to a queue instead of
+ * calling back into the application, so that they can be handled on
+ * the thread owning the adapter. Take them with
+ * `accesskit_unix_adapter_poll_event`.
+ *
+ * Returns null if the file descriptors signaling pending events
+ * couldn't be created.
+ */
+struct accesskit_unix_adapter *accesskit_unix_adapter_new_with_event_queue(
+    void);
+#endif
+
 #if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
      defined(__NetBSD__) || defined(__OpenBSD__))
 void accesskit_unix_adapter_free(struct accesskit_unix_adapter *adapter);
 #endif
 
+#if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
+     defined(__NetBSD__) || defined(__OpenBSD__))
+/**
+ * Validates every tree update given to the adapter and reports the
+ * invalid ones to `handler` instead of applying them. Pass a null
+ * `handler` to turn validation off.
+ */
+void accesskit_unix_adapter_set_validation_handler(
+    struct accesskit_unix_adapter *adapter,
+    accesskit_validation_handler_callback handler, void *userdata);
+#endif
+
 #if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
      defined(__NetBSD__) || defined(__OpenBSD__))
 /**
@@ -3011,6 +4859,20 @@ void accesskit_unix_adapter_update_if_active(
     void *update_factory_userdata);
 #endif
 
+#if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
+     defined(__NetBSD__) || defined(__OpenBSD__))
+/**
+ * Gives the adapter the initial tree that its activation handler didn't
+ * return, which must be a full tree update. Passing one to
+ * `accesskit_unix_adapter_update_if_active` works too. Takes ownership of
+ * `update`, which is dropped, returning false, if the adapter isn't
+ * waiting for its initial tree.
+ */
+bool accesskit_unix_adapter_provide_initial_tree(
+    struct accesskit_unix_adapter *adapter,
+    struct accesskit_tree_update *update);
+#endif
+
 #if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
      defined(__NetBSD__) || defined(__OpenBSD__))
 /**
@@ -3020,6 +4882,31 @@ void accesskit_unix_adapter_update_window_focus_state(
     struct accesskit_unix_adapter *adapter, bool is_focused);
 #endif
 
+#if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
+     defined(__NetBSD__) || defined(__OpenBSD__))
+/**
+ * Removes the oldest event from the queue of an adapter created with
+ * `accesskit_unix_adapter_new_with_event_queue` and stores it in
+ * `event`. Returns false, leaving `event` untouched, if the queue is
+ * empty or the adapter has no queue.
+ */
+bool accesskit_unix_adapter_poll_event(struct accesskit_unix_adapter *adapter,
+                                       struct accesskit_adapter_event *event);
+#endif
+
+#if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
+     defined(__NetBSD__) || defined(__OpenBSD__))
+/**
+ * Returns a file descriptor that is readable while the event queue of
+ * the adapter isn't empty, to be watched by the event loop of the
+ * application, or -1 if the adapter has no queue. Only wait for it to
+ * become readable, and call `accesskit_unix_adapter_poll_event` until
+ * it returns false; the descriptor is owned by the adapter.
+ */
+int accesskit_unix_adapter_event_fd(
+    const struct accesskit_unix_adapter *adapter);
+#endif
+
 #if (defined(__linux__) || defined(__DragonFly__) || defined(__FreeBSD__) || \
      defined(__NetBSD__) || defined(__OpenBSD__))
 /**
@@ -3044,10 +4931,37 @@ struct accesskit_windows_adapter *accesskit_windows_adapter_new(
     void *action_handler_userdata);
 #endif
 
+#if defined(_WIN32)
+/**
+ * Same as `accesskit_windows_adapter_new`, except that the action
+ * handler comes with an optional callback freeing its userdata. Since
+ * the action handler may be called on other threads, it is called
+ * once, possibly from another thread, when the library drops the
+ * handler, which may be after `accesskit_windows_adapter_free` returns.
+ */
+struct accesskit_windows_adapter *
+accesskit_windows_adapter_new_with_destroy_notify(
+    HWND hwnd, bool is_window_focused,
+    accesskit_action_handler_callback action_handler,
+    void *action_handler_userdata,
+    accesskit_destroy_notify_callback action_handler_destroy_notify);
+#endif
+
 #if defined(_WIN32)
 void accesskit_windows_adapter_free(struct accesskit_windows_adapter *adapter);
 #endif
 
+#if defined(_WIN32)
+/**
+ * Validates every tree update given to the adapter and reports the
+ * invalid ones to `handler` instead of applying them. Pass a null
+ * `handler` to turn validation off.
+ */
+void accesskit_windows_adapter_set_validation_handler(
+    struct accesskit_windows_adapter *adapter,
+    accesskit_validation_handler_callback handler, void *userdata);
+#endif
+
 #if defined(_WIN32)
 /**
  * You must call `accesskit_windows_queued_events_raise` on the returned
@@ -3060,6 +4974,23 @@ accesskit_windows_adapter_update_if_active(
     void *update_factory_userdata);
 #endif
 
+#if defined(_WIN32)
+/**
+ * Gives the adapter the initial tree that its activation handler didn't
+ * return, which must be a full tree update. Passing one to
+ * `accesskit_windows_adapter_update_if_active` works too. Takes ownership
+ * of `update`, which is dropped if the adapter isn't waiting for its
+ * initial tree.
+ *
+ * You must call `accesskit_windows_queued_events_raise` on the returned
+ * pointer. It can be null if the adapter wasn't waiting for its initial tree.
+ */
+struct accesskit_windows_queued_events *
+accesskit_windows_adapter_provide_initial_tree(
+    struct accesskit_windows_adapter *adapter,
+    struct accesskit_tree_update *update);
+#endif
+
 #if defined(_WIN32)
 /**
  * Update the tree state based on whether the window is focused.
@@ -3109,11 +5040,44 @@ accesskit_windows_subclassing_adapter_new(
     void *action_handler_userdata);
 #endif
 
+#if defined(_WIN32)
+/**
+ * Same as `accesskit_windows_subclassing_adapter_new`, except that each
+ * handler comes with an optional callback freeing its userdata. It is
+ * called once, possibly from another thread, when the library drops
+ * the handler, which may be after
+ * `accesskit_windows_subclassing_adapter_free` returns.
+ *
+ * # Panics
+ *
+ * Panics if the window is already visible.
+ */
+struct accesskit_windows_subclassing_adapter *
+accesskit_windows_subclassing_adapter_new_with_destroy_notify(
+    HWND hwnd, accesskit_activation_handler_callback activation_handler,
+    void *activation_handler_userdata,
+    accesskit_destroy_notify_callback activation_handler_destroy_notify,
+    accesskit_action_handler_callback action_handler,
+    void *action_handler_userdata,
+    accesskit_destroy_notify_callback action_handler_destroy_notify);
+#endif
+
 #if defined(_WIN32)
 void accesskit_windows_subclassing_adapter_free(
     struct accesskit_windows_subclassing_adapter *adapter);
 #endif
 
+#if defined(_WIN32)
+/**
+ * Validates every tree update given to the adapter and reports the
+ * invalid ones to `handler` instead of applying them. Pass a null
+ * `handler` to turn validation off.
+ */
+void accesskit_windows_subclassing_adapter_set_validation_handler(
+    struct accesskit_windows_subclassing_adapter *adapter,
+    accesskit_validation_handler_callback handler, void *userdata);
+#endif
+
 #if defined(_WIN32)
 /**
  * You must call `accesskit_windows_queued_events_raise` on the returned
@@ -3126,6 +5090,23 @@ accesskit_windows_subclassing_adapter_update_if_active(
     void *update_factory_userdata);
 #endif
 
+#if defined(_WIN32)
+/**
+ * Gives the adapter the initial tree that its activation handler didn't
+ * return, which must be a full tree update. Passing one to
+ * `accesskit_windows_subclassing_adapter_update_if_active` works too.
+ * Takes ownership of `update`, which is dropped if the adapter isn't
+ * waiting for its initial tree.
+ *
+ * You must call `accesskit_windows_queued_events_raise` on the returned
+ * pointer. It can be null if the adapter wasn't waiting for its initial tree.
+ */
+struct accesskit_windows_queued_events *
+accesskit_windows_subclassing_adapter_provide_initial_tree(
+    struct accesskit_windows_subclassing_adapter *adapter,
+    struct accesskit_tree_update *update);
+#endif
+
 #ifdef __cplusplus
 }  // extern "C"
 #endif  // __cplusplus
@@ -4,12 +4,13 @@
 // the LICENSE-MIT file), at your option.
 
 use accesskit_android::*;
-use std::os::raw::c_void;
+use std::{os::raw::c_void, ptr};
 
 use crate::{
-    box_from_ptr, mut_from_ptr, ref_from_ptr, tree_update_factory, tree_update_factory_userdata,
-    ActionHandlerCallback, ActivationHandlerCallback, BoxCastPtr, CastPtr, FfiActionHandler,
-    FfiActivationHandler,
+    box_from_ptr, mut_from_ptr, ref_from_ptr, tree_update, tree_update_factory,
+    tree_update_factory_userdata, ActionHandlerCallback, ActivationHandlerCallback, BoxCastPtr,
+    CastPtr, FfiActionHandler, FfiActivationHandler, UpdateValidator, ValidatingAdapter,
+    ValidationHandlerCallback,
 };
 
 pub struct android_platform_action {
@@ -71,7 +72,7 @@ pub struct android_adapter {
 }
 
 impl CastPtr for android_adapter {
-    type RustType = Adapter;
+    type RustType = ValidatingAdapter<Adapter>;
 }
 
 impl BoxCastPtr for android_adapter {}
@@ -80,7 +81,7 @@ impl android_adapter {
     #[no_mangle]
     pub extern "C" fn accesskit_android_adapter_new() -> *mut android_adapter {
         let adapter = Adapter::default();
-        BoxCastPtr::to_mut_ptr(adapter)
+        BoxCastPtr::to_mut_ptr(ValidatingAdapter::new(adapter, UpdateValidator::default()))
     }
 
     #[no_mangle]
@@ -88,6 +89,19 @@ impl android_adapter {
         drop(box_from_ptr(adapter));
     }
 
+    /// Validates every tree update given to the adapter and reports the
+    /// invalid ones to `handler` instead of applying them. Pass a null
+    /// `handler` to turn validation off.
+    #[no_mangle]
+    pub extern "C" fn accesskit_android_adapter_set_validation_handler(
+        adapter: *mut android_adapter,
+        handler: ValidationHandlerCallback,
+        userdata: *mut c_void,
+    ) {
+        let adapter = mut_from_ptr(adapter);
+        adapter.validator.set_handler(handler, userdata);
+    }
+
     /// You must call `accesskit_android_queued_events_raise` on the returned pointer. It can be null if the adapter is not active.
     #[no_mangle]
     pub extern "C" fn accesskit_android_adapter_update_if_active(
@@ -95,11 +109,36 @@ impl android_adapter {
         update_factory: tree_update_factory,
         update_factory_userdata: *mut c_void,
     ) -> *mut android_queued_events {
-        let update_factory = update_factory.unwrap();
+        let Some(update_factory) = update_factory else {
+            return ptr::null_mut();
+        };
         let update_factory_userdata = tree_update_factory_userdata(update_factory_userdata);
         let adapter = mut_from_ptr(adapter);
-        let events =
-            adapter.update_if_active(|| *box_from_ptr(update_factory(update_factory_userdata)));
+        let validator = adapter.validator.clone();
+        let events = adapter.update_if_active(|| {
+            validator.check_update(*box_from_ptr(update_factory(update_factory_userdata)))
+        });
+        BoxCastPtr::to_nullable_mut_ptr(events)
+    }
+
+    /// Gives the adapter the initial tree that its activation handler didn't
+    /// return, which must be a full tree update. Passing one to
+    /// `accesskit_android_adapter_update_if_active` works too. Takes ownership
+    /// of `update`, which is dropped if the adapter isn't waiting for its
+    /// initial tree.
+    ///
+    /// You must call `accesskit_android_queued_events_raise` on the returned pointer. It can be null if the adapter wasn't waiting for its initial tree.
+    #[no_mangle]
+    pub extern "C" fn accesskit_android_adapter_provide_initial_tree(
+        adapter: *mut android_adapter,
+        update: *mut tree_update,
+    ) -> *mut android_queued_events {
+        let update = *box_from_ptr(update);
+        let adapter = mut_from_ptr(adapter);
+        let Some(update) = adapter.validator.provide_initial_tree(update) else {
+            return ptr::null_mut();
+        };
+        let events = adapter.update_if_active(|| update);
         BoxCastPtr::to_nullable_mut_ptr(events)
     }
 
@@ -114,7 +153,12 @@ impl android_adapter {
     ) -> jni::sys::jobject {
         let adapter = mut_from_ptr(adapter);
         let mut activation_handler =
-            FfiActivationHandler::new(activation_handler, activation_handler_userdata);
+            adapter
+                .validator
+                .activation_handler(FfiActivationHandler::new(
+                    activation_handler,
+                    activation_handler_userdata,
+                ));
         let mut env = unsafe { jni::JNIEnv::from_raw(env).unwrap() };
         let host = unsafe { jni::objects::JObject::from_raw(host) };
         adapter
@@ -138,7 +182,12 @@ impl android_adapter {
     ) -> jni::sys::jobject {
         let adapter = mut_from_ptr(adapter);
         let mut activation_handler =
-            FfiActivationHandler::new(activation_handler, activation_handler_userdata);
+            adapter
+                .validator
+                .activation_handler(FfiActivationHandler::new(
+                    activation_handler,
+                    activation_handler_userdata,
+                ));
         let mut env = unsafe { jni::JNIEnv::from_raw(env).unwrap() };
         let host = unsafe { jni::objects::JObject::from_raw(host) };
         adapter
@@ -174,7 +223,12 @@ impl android_adapter {
     ) -> *mut android_queued_events {
         let adapter = mut_from_ptr(adapter);
         let mut activation_handler =
-            FfiActivationHandler::new(activation_handler, activation_handler_userdata);
+            adapter
+                .validator
+                .activation_handler(FfiActivationHandler::new(
+                    activation_handler,
+                    activation_handler_userdata,
+                ));
         let events = adapter.on_hover_event(&mut activation_handler, action, x, y);
         BoxCastPtr::to_nullable_mut_ptr(events)
     }
@@ -185,7 +239,7 @@ pub struct android_injecting_adapter {
 }
 
 impl CastPtr for android_injecting_adapter {
-    type RustType = InjectingAdapter;
+    type RustType = ValidatingAdapter<InjectingAdapter>;
 }
 
 impl BoxCastPtr for android_injecting_adapter {}
@@ -202,11 +256,14 @@ impl android_injecting_adapter {
     ) -> *mut android_injecting_adapter {
         let mut env = unsafe { jni::JNIEnv::from_raw(env).unwrap() };
         let host = unsafe { jni::objects::JObject::from_raw(host) };
-        let activation_handler =
-            FfiActivationHandler::new(activation_handler, activation_handler_userdata);
+        let validator = UpdateValidator::default();
+        let activation_handler = validator.activation_handler(FfiActivationHandler::new(
+            activation_handler,
+            activation_handler_userdata,
+        ));
         let action_handler = FfiActionHandler::new(action_handler, action_handler_userdata);
         let adapter = InjectingAdapter::new(&mut env, &host, activation_handler, action_handler);
-        BoxCastPtr::to_mut_ptr(adapter)
+        BoxCastPtr::to_mut_ptr(ValidatingAdapter::new(adapter, validator))
     }
 
     #[no_mangle]
@@ -216,15 +273,52 @@ impl android_injecting_adapter {
         drop(box_from_ptr(adapter));
     }
 
+    /// Validates every tree update given to the adapter and reports the
+    /// invalid ones to `handler` instead of applying them. Pass a null
+    /// `handler` to turn validation off.
+    #[no_mangle]
+    pub extern "C" fn accesskit_android_injecting_adapter_set_validation_handler(
+        adapter: *mut android_injecting_adapter,
+        handler: ValidationHandlerCallback,
+        userdata: *mut c_void,
+    ) {
+        let adapter = mut_from_ptr(adapter);
+        adapter.validator.set_handler(handler, userdata);
+    }
+
     #[no_mangle]
     pub extern "C" fn accesskit_android_injecting_adapter_update_if_active(
         adapter: *mut android_injecting_adapter,
         update_factory: tree_update_factory,
         update_factory_userdata: *mut c_void,
     ) {
-        let update_factory = update_factory.unwrap();
+        let Some(update_factory) = update_factory else {
+            return;
+        };
         let update_factory_userdata = tree_update_factory_userdata(update_factory_userdata);
         let adapter = mut_from_ptr(adapter);
-        adapter.update_if_active(|| *box_from_ptr(update_factory(update_factory_userdata)));
+        let validator = adapter.validator.clone();
+        adapter.update_if_active(|| {
+            validator.check_update(*box_from_ptr(update_factory(update_factory_userdata)))
+        });
+    }
+
+    /// Gives the adapter the initial tree that its activation handler didn't
+    /// return, which must be a full tree update. Passing one to
+    /// `accesskit_android_injecting_adapter_update_if_active` works too. Takes
+    /// ownership of `update`, which is dropped, returning false, if the adapter
+    /// isn't waiting for its initial tree.
+    #[no_mangle]
+    pub extern "C" fn accesskit_android_injecting_adapter_provide_initial_tree(
+        adapter: *mut android_injecting_adapter,
+        update: *mut tree_update,
+    ) -> bool {
+        let update = *box_from_ptr(update);
+        let adapter = mut_from_ptr(adapter);
+        let Some(update) = adapter.validator.provide_initial_tree(update) else {
+            return false;
+        };
+        adapter.update_if_active(|| update);
+        true
     }
 }
@@ -0,0 +1,295 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::Role;
+
+use crate::role_name;
+
+/// How a role is expressed in HTML.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AriaRole {
+    /// The ARIA role has the same meaning, and maps back to this role.
+    Exact(&'static str),
+    /// The closest ARIA role, which maps back to another role; the exact
+    /// role is kept in a `data-accesskit-role` attribute.
+    Approximate(&'static str),
+    /// Only the `data-accesskit-role` attribute is emitted.
+    NoEquivalent,
+}
+
+use AriaRole::*;
+
+/// The mapping between AccessKit roles and ARIA roles, covering every role.
+pub(crate) const ROLES: &[(Role, AriaRole)] = &[
+    (Role::Unknown, NoEquivalent),
+    (Role::TextRun, NoEquivalent),
+    (Role::Cell, Exact("cell")),
+    (Role::Label, NoEquivalent),
+    (Role::Image, Exact("img")),
+    (Role::Link, Exact("link")),
+    (Role::Row, Exact("row")),
+    (Role::ListItem, Exact("listitem")),
+    (Role::ListMarker, NoEquivalent),
+    (Role::TreeItem, Exact("treeitem")),
+    (Role::ListBoxOption, Exact("option")),
+    (Role::MenuItem, Exact("menuitem")),
+    (Role::MenuListOption, Approximate("option")),
+    (Role::Paragraph, Exact("paragraph")),
+    (Role::GenericContainer, Exact("generic")),
+    (Role::CheckBox, Exact("checkbox")),
+    (Role::RadioButton, Exact("radio")),
+    (Role::TextInput, Exact("textbox")),
+    (Role::Button, Exact("button")),
+    (Role::DefaultButton, Approximate("button")),
+    (Role::Pane, Approximate("group")),
+    (Role::RowHeader, Exact("rowheader")),
+    (Role::ColumnHeader, Exact("columnheader")),
+    (Role::RowGroup, Exact("rowgroup")),
+    (Role::List, Exact("list")),
+    (Role::Table, Exact("table")),
+    (Role::LayoutTableCell, Approximate("presentation")),
+    (Role::LayoutTableRow, Approximate("presentation")),
+    (Role::LayoutTable, Approximate("presentation")),
+    (Role::Switch, Exact("switch")),
+    (Role::Menu, Exact("menu")),
+    (Role::MultilineTextInput, Approximate("textbox")),
+    (Role::SearchInput, Exact("searchbox")),
+    (Role::DateInput, Approximate("textbox")),
+    (Role::DateTimeInput, Approximate("textbox")),
+    (Role::WeekInput, Approximate("textbox")),
+    (Role::MonthInput, Approximate("textbox")),
+    (Role::TimeInput, Approximate("textbox")),
+    (Role::EmailInput, Approximate("textbox")),
+    (Role::NumberInput, Approximate("spinbutton")),
+    (Role::PasswordInput, Approximate("textbox")),
+    (Role::PhoneNumberInput, Approximate("textbox")),
+    (Role::UrlInput, Approximate("textbox")),
+    (Role::Abbr, NoEquivalent),
+    (Role::Alert, Exact("alert")),
+    (Role::AlertDialog, Exact("alertdialog")),
+    (Role::Application, Exact("application")),
+    (Role::Article, Exact("article")),
+    (Role::Audio, NoEquivalent),
+    (Role::Banner, Exact("banner")),
+    (Role::Blockquote, Exact("blockquote")),
+    (Role::Canvas, NoEquivalent),
+    (Role::Caption, Exact("caption")),
+    (Role::Caret, NoEquivalent),
+    (Role::Code, Exact("code")),
+    (Role::ColorWell, NoEquivalent),
+    (Role::ComboBox, Exact("combobox")),
+    (Role::EditableComboBox, Approximate("combobox")),
+    (Role::Complementary, Exact("complementary")),
+    (Role::Comment, Exact("comment")),
+    (Role::ContentDeletion, Exact("deletion")),
+    (Role::ContentInsertion, Exact("insertion")),
+    (Role::ContentInfo, Exact("contentinfo")),
+    (Role::Definition, Exact("definition")),
+    (Role::DescriptionList, Approximate("list")),
+    (Role::Details, Approximate("group")),
+    (Role::Dialog, Exact("dialog")),
+    (Role::DisclosureTriangle, Approximate("button")),
+    (Role::Document, Exact("document")),
+    (Role::EmbeddedObject, NoEquivalent),
+    (Role::Emphasis, Exact("emphasis")),
+    (Role::Feed, Exact("feed")),
+    (Role::FigureCaption, Approximate("caption")),
+    (Role::Figure, Exact("figure")),
+    (Role::Footer, Approximate("contentinfo")),
+    (Role::Form, Exact("form")),
+    (Role::Grid, Exact("grid")),
+    (Role::GridCell, Exact("gridcell")),
+    (Role::Group, Exact("group")),
+    (Role::Header, Approximate("banner")),
+    (Role::Heading, Exact("heading")),
+    (Role::Iframe, NoEquivalent),
+    (Role::IframePresentational, NoEquivalent),
+    (Role::ImeCandidate, NoEquivalent),
+    (Role::Keyboard, NoEquivalent),
+    (Role::Legend, NoEquivalent),
+    (Role::LineBreak, NoEquivalent),
+    (Role::ListBox, Exact("listbox")),
+    (Role::Log, Exact("log")),
+    (Role::Main, Exact("main")),
+    (Role::Mark, Exact("mark")),
+    (Role::Marquee, Exact("marquee")),
+    (Role::Math, Exact("math")),
+    (Role::MenuBar, Exact("menubar")),
+    (Role::MenuItemCheckBox, Exact("menuitemcheckbox")),
+    (Role::MenuItemRadio, Exact("menuitemradio")),
+    (Role::MenuListPopup, Approximate("listbox")),
+    (Role::Meter, Exact("meter")),
+    (Role::Navigation, Exact("navigation")),
+    (Role::Note, Exact("note")),
+    (Role::PluginObject, NoEquivalent),
+    (Role::ProgressIndicator, Exact("progressbar")),
+    (Role::RadioGroup, Exact("radiogroup")),
+    (Role::Region, Exact("region")),
+    (Role::RootWebArea, Approximate("document")),
+    (Role::Ruby, NoEquivalent),
+    (Role::RubyAnnotation, NoEquivalent),
+    (Role::ScrollBar, Exact("scrollbar")),
+    (Role::ScrollView, NoEquivalent),
+    (Role::Search, Exact("search")),
+    (Role::Section, Approximate("generic")),
+    (Role::SectionFooter, NoEquivalent),
+    (Role::SectionHeader, NoEquivalent),
+    (Role::Slider, Exact("slider")),
+    (Role::SpinButton, Exact("spinbutton")),
+    (Role::Splitter, Exact("separator")),
+    (Role::Status, Exact("status")),
+    (Role::Strong, Exact("strong")),
+    (Role::Suggestion, Exact("suggestion")),
+    (Role::SvgRoot, Approximate("graphics-document")),
+    (Role::Tab, Exact("tab")),
+    (Role::TabList, Exact("tablist")),
+    (Role::TabPanel, Exact("tabpanel")),
+    (Role::Term, Exact("term")),
+    (Role::Time, Exact("time")),
+    (Role::Timer, Exact("timer")),
+    (Role::TitleBar, Approximate("banner")),
+    (Role::Toolbar, Exact("toolbar")),
+    (Role::Tooltip, Exact("tooltip")),
+    (Role::Tree, Exact("tree")),
+    (Role::TreeGrid, Exact("treegrid")),
+    (Role::Video, NoEquivalent),
+    (Role::WebView, NoEquivalent),
+    (Role::Window, NoEquivalent),
+    (Role::PdfActionableHighlight, NoEquivalent),
+    (Role::PdfRoot, NoEquivalent),
+    (Role::GraphicsDocument, Exact("graphics-document")),
+    (Role::GraphicsObject, Exact("graphics-object")),
+    (Role::GraphicsSymbol, Exact("graphics-symbol")),
+    (Role::DocAbstract, Exact("doc-abstract")),
+    (Role::DocAcknowledgements, Exact("doc-acknowledgments")),
+    (Role::DocAfterword, Exact("doc-afterword")),
+    (Role::DocAppendix, Exact("doc-appendix")),
+    (Role::DocBackLink, Exact("doc-backlink")),
+    (Role::DocBiblioEntry, Exact("doc-biblioentry")),
+    (Role::DocBibliography, Exact("doc-bibliography")),
+    (Role::DocBiblioRef, Exact("doc-biblioref")),
+    (Role::DocChapter, Exact("doc-chapter")),
+    (Role::DocColophon, Exact("doc-colophon")),
+    (Role::DocConclusion, Exact("doc-conclusion")),
+    (Role::DocCover, Exact("doc-cover")),
+    (Role::DocCredit, Exact("doc-credit")),
+    (Role::DocCredits, Exact("doc-credits")),
+    (Role::DocDedication, Exact("doc-dedication")),
+    (Role::DocEndnote, Exact("doc-endnote")),
+    (Role::DocEndnotes, Exact("doc-endnotes")),
+    (Role::DocEpigraph, Exact("doc-epigraph")),
+    (Role::DocEpilogue, Exact("doc-epilogue")),
+    (Role::DocErrata, Exact("doc-errata")),
+    (Role::DocExample, Exact("doc-example")),
+    (Role::DocFootnote, Exact("doc-footnote")),
+    (Role::DocForeword, Exact("doc-foreword")),
+    (Role::DocGlossary, Exact("doc-glossary")),
+    (Role::DocGlossRef, Exact("doc-glossref")),
+    (Role::DocIndex, Exact("doc-index")),
+    (Role::DocIntroduction, Exact("doc-introduction")),
+    (Role::DocNoteRef, Exact("doc-noteref")),
+    (Role::DocNotice, Exact("doc-notice")),
+    (Role::DocPageBreak, Exact("doc-pagebreak")),
+    (Role::DocPageFooter, Exact("doc-pagefooter")),
+    (Role::DocPageHeader, Exact("doc-pageheader")),
+    (Role::DocPageList, Exact("doc-pagelist")),
+    (Role::DocPart, Exact("doc-part")),
+    (Role::DocPreface, Exact("doc-preface")),
+    (Role::DocPrologue, Exact("doc-prologue")),
+    (Role::DocPullquote, Exact("doc-pullquote")),
+    (Role::DocQna, Exact("doc-qna")),
+    (Role::DocSubtitle, Exact("doc-subtitle")),
+    (Role::DocTip, Exact("doc-tip")),
+    (Role::DocToc, Exact("doc-toc")),
+    (Role::ListGrid, Approximate("grid")),
+    (Role::Terminal, Approximate("log")),
+];
+
+pub(crate) fn aria_role(role: Role) -> AriaRole {
+    ROLES
+        .iter()
+        .find(|(other, _)| *other == role)
+        .map_or(NoEquivalent, |(_, aria)| *aria)
+}
+
+/// The role exactly matching an ARIA role, or else the first role it
+/// approximates. `none` and `presentation` are generic containers.
+pub(crate) fn role_from_aria(aria: &str) -> Option<Role> {
+    if aria == "none" || aria == "presentation" {
+        return Some(Role::GenericContainer);
+    }
+    ROLES
+        .iter()
+        .find(|(_, other)| matches!(other, Exact(name) if *name == aria))
+        .or_else(|| {
+            ROLES
+                .iter()
+                .find(|(_, other)| matches!(other, Approximate(name) if *name == aria))
+        })
+        .map(|(role, _)| *role)
+}
+
+/// The role with the given name in snake case, as in `default_button`.
+pub(crate) fn role_from_name(name: &str) -> Option<Role> {
+    ROLES
+        .iter()
+        .map(|(role, _)| *role)
+        .find(|role| role_name(*role) == name)
+}
+
+/// Whether the toggled state is expressed with `aria-checked` rather than
+/// `aria-pressed`.
+pub(crate) fn uses_aria_checked(role: Role) -> bool {
+    matches!(
+        role,
+        Role::CheckBox
+            | Role::RadioButton
+            | Role::Switch
+            | Role::MenuItemCheckBox
+            | Role::MenuItemRadio
+            | Role::ListBoxOption
+            | Role::TreeItem
+    )
+}
+
+/// Roles whose string value is exposed as `aria-valuetext`, rather than
+/// as text content.
+pub(crate) fn is_range_role(role: Role) -> bool {
+    matches!(
+        role,
+        Role::Slider
+            | Role::SpinButton
+            | Role::NumberInput
+            | Role::ScrollBar
+            | Role::ProgressIndicator
+            | Role::Meter
+            | Role::Splitter
+    )
+}
+
+/// Roles whose string value is their text content, such as labels
+/// and text inputs.
+pub(crate) fn is_text_role(role: Role) -> bool {
+    matches!(
+        role,
+        Role::Label
+            | Role::TextRun
+            | Role::ListMarker
+            | Role::TextInput
+            | Role::MultilineTextInput
+            | Role::SearchInput
+            | Role::DateInput
+            | Role::DateTimeInput
+            | Role::WeekInput
+            | Role::MonthInput
+            | Role::TimeInput
+            | Role::EmailInput
+            | Role::PasswordInput
+            | Role::PhoneNumberInput
+            | Role::UrlInput
+            | Role::ComboBox
+            | Role::EditableComboBox
+    )
+}
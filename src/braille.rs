@@ -0,0 +1,414 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{Node, NodeId, Role, Toggled};
+use std::{
+    ffi::{c_char, CString},
+    ptr,
+};
+
+use crate::{
+    box_from_ptr, mut_from_ptr, ref_from_ptr, role_name, tree_snapshot, BoxCastPtr, CastPtr,
+    TreeSnapshot,
+};
+
+/// The characters of the six-dot Braille ASCII set, indexed by dot pattern.
+const BRAILLE_ASCII: &[u8; 64] =
+    b" A1B'K2L@CIF/MSP\"E3H9O6R^DJG>NTQ,*5<-U8V.%[$+X!&;:4\\0Z7(_?W]#Y)=";
+
+const DOT_7: u32 = 0x40;
+const BLANK_CELL: u32 = 0x2800;
+const UNKNOWN_CELL: char = '\u{28ff}';
+
+/// Returns the characters as a C string, with the replacement character
+/// standing for the null characters that labels may contain.
+fn text_from_chars(chars: &[char]) -> *mut c_char {
+    let text: String = chars
+        .iter()
+        .map(|c| {
+            if *c == '\0' {
+                char::REPLACEMENT_CHARACTER
+            } else {
+                *c
+            }
+        })
+        .collect();
+    CString::new(text).unwrap().into_raw()
+}
+
+/// Converts a character to a Unicode braille pattern using
+/// North American Braille Computer Code.
+fn braille_cell(c: char) -> char {
+    let pattern = |c: u8| {
+        BRAILLE_ASCII
+            .iter()
+            .position(|&b| b == c)
+            .map(|dots| dots as u32)
+    };
+    let dots = match c {
+        ' '..='?' => pattern(c as u8),
+        '@'..='_' => pattern(c as u8).map(|dots| dots | DOT_7),
+        '`'..='~' => pattern(c as u8 - 0x20),
+        _ => None,
+    };
+    dots.and_then(|dots| char::from_u32(BLANK_CELL + dots))
+        .unwrap_or(UNKNOWN_CELL)
+}
+
+fn role_abbreviation(node: &Node) -> Option<String> {
+    let abbreviation = match node.role() {
+        Role::Unknown
+        | Role::GenericContainer
+        | Role::Label
+        | Role::TextRun
+        | Role::Paragraph
+        | Role::Pane
+        | Role::Section
+        | Role::Cell
+        | Role::LayoutTable
+        | Role::LayoutTableRow
+        | Role::LayoutTableCell => return None,
+        Role::Heading => {
+            return Some(match node.level() {
+                Some(level) => format!("h{}", level),
+                None => "hdng".into(),
+            })
+        }
+        Role::Button | Role::DefaultButton => "btn",
+        Role::CheckBox | Role::MenuItemCheckBox => "chk",
+        Role::RadioButton | Role::MenuItemRadio => "rbtn",
+        Role::Switch => "swtch",
+        Role::Link => "lnk",
+        Role::Image => "gra",
+        Role::List | Role::ListBox => "lst",
+        Role::ListItem | Role::ListBoxOption | Role::MenuListOption => "lstitm",
+        Role::Menu | Role::MenuListPopup => "mnu",
+        Role::MenuBar => "mnubar",
+        Role::MenuItem => "mnuitm",
+        Role::ComboBox | Role::EditableComboBox => "cbo",
+        Role::TextInput
+        | Role::MultilineTextInput
+        | Role::SearchInput
+        | Role::EmailInput
+        | Role::NumberInput
+        | Role::PasswordInput
+        | Role::PhoneNumberInput
+        | Role::UrlInput => "edt",
+        Role::Slider => "sldr",
+        Role::SpinButton => "spnbtn",
+        Role::ProgressIndicator => "prgbar",
+        Role::ScrollBar => "scrlbar",
+        Role::Tab => "tab",
+        Role::TabList => "tabctl",
+        Role::TabPanel => "tabpnl",
+        Role::Tree => "tv",
+        Role::TreeItem => "tvitm",
+        Role::Table | Role::Grid | Role::TreeGrid => "tbl",
+        Role::Row => "row",
+        Role::ColumnHeader => "colhdr",
+        Role::RowHeader => "rowhdr",
+        Role::Dialog | Role::AlertDialog => "dlg",
+        Role::Alert => "alrt",
+        Role::Toolbar => "tlbar",
+        Role::Tooltip => "tltip",
+        Role::Group | Role::RadioGroup => "grp",
+        Role::Navigation => "navi",
+        Role::Main => "main",
+        Role::Search => "srch",
+        Role::Form => "frm",
+        Role::Document | Role::RootWebArea => "doc",
+        Role::Window => "wnd",
+        role => return Some(role_name(role).replace('_', " ")),
+    };
+    Some(abbreviation.into())
+}
+
+fn state_abbreviations(node: &Node) -> Vec<&'static str> {
+    let mut states = Vec::new();
+    let checkable = matches!(
+        node.role(),
+        Role::CheckBox
+            | Role::MenuItemCheckBox
+            | Role::RadioButton
+            | Role::MenuItemRadio
+            | Role::Switch
+    );
+    match (node.toggled(), checkable) {
+        (Some(Toggled::True), true) => states.push("(x)"),
+        (Some(Toggled::False), true) | (None, true) => states.push("( )"),
+        (Some(Toggled::Mixed), true) => states.push("(-)"),
+        (Some(Toggled::True), false) => states.push("prsd"),
+        (Some(Toggled::Mixed), false) => states.push("half prsd"),
+        _ => (),
+    }
+    match node.is_expanded() {
+        Some(true) => states.push("-"),
+        Some(false) => states.push("+"),
+        None => (),
+    }
+    if node.is_selected() == Some(true) {
+        states.push("sel");
+    }
+    if node.is_disabled() {
+        states.push("unav");
+    }
+    if node.is_read_only() && !matches!(node.role(), Role::Label | Role::TextRun) {
+        states.push("ro");
+    }
+    if node.is_required() {
+        states.push("req");
+    }
+    if node.invalid().is_some() {
+        states.push("invld");
+    }
+    if node.is_visited() {
+        states.push("vstd");
+    }
+    if node.is_busy() {
+        states.push("bsy");
+    }
+    states
+}
+
+fn value_text(node: &Node) -> Option<String> {
+    if let Some(value) = node.value() {
+        return (!value.is_empty()).then(|| value.to_string());
+    }
+    node.numeric_value().map(|value| value.to_string())
+}
+
+/// Whether an ancestor of the focused node is worth showing as context
+/// even if it doesn't have a name.
+fn is_context_role(role: Role) -> bool {
+    matches!(
+        role,
+        Role::Dialog
+            | Role::AlertDialog
+            | Role::List
+            | Role::ListBox
+            | Role::Menu
+            | Role::MenuBar
+            | Role::MenuListPopup
+            | Role::Tree
+            | Role::TreeGrid
+            | Role::Table
+            | Role::Grid
+            | Role::TabList
+            | Role::Toolbar
+            | Role::RadioGroup
+    )
+}
+
+fn push_segment(segments: &mut Vec<String>, text: Option<String>) {
+    if let Some(text) = text {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !text.is_empty() {
+            segments.push(text);
+        }
+    }
+}
+
+fn node_segments(snapshot: &TreeSnapshot, id: NodeId, node: &Node, segments: &mut Vec<String>) {
+    let name = node
+        .braille_label()
+        .map(str::to_string)
+        .or_else(|| snapshot.name(id));
+    push_segment(segments, name);
+    let role = node
+        .braille_role_description()
+        .or_else(|| node.role_description())
+        .map(str::to_string)
+        .or_else(|| role_abbreviation(node));
+    push_segment(segments, role);
+}
+
+/// Renders the focused node, followed by the ancestors that give it context,
+/// from the innermost to the outermost.
+fn render_line(snapshot: &TreeSnapshot) -> String {
+    let focus = snapshot.focus_id();
+    let Some(node) = snapshot.node(focus) else {
+        return String::new();
+    };
+    let mut segments = Vec::new();
+    node_segments(snapshot, focus, node, &mut segments);
+    for state in state_abbreviations(node) {
+        segments.push(state.into());
+    }
+    push_segment(&mut segments, value_text(node));
+    if let (Some(position), Some(size)) = (node.position_in_set(), node.size_of_set()) {
+        segments.push(format!("{}/{}", position, size));
+    }
+    for id in snapshot.ancestor_ids(focus) {
+        let ancestor = snapshot.node(id).unwrap();
+        let has_name = ancestor.braille_label().is_some() || snapshot.name(id).is_some();
+        if has_name || is_context_role(ancestor.role()) {
+            node_segments(snapshot, id, ancestor, &mut segments);
+        }
+    }
+    segments.join(" ")
+}
+
+pub(crate) struct BrailleSimulator {
+    cell_count: usize,
+    line: Vec<char>,
+    focus: Option<NodeId>,
+    offset: usize,
+}
+
+impl BrailleSimulator {
+    fn render(&mut self, snapshot: &TreeSnapshot) {
+        self.line = render_line(snapshot).chars().collect();
+        let focus = Some(snapshot.focus_id());
+        if focus != self.focus {
+            self.focus = focus;
+            self.offset = 0;
+        } else if self.offset >= self.line.len() {
+            self.offset = self.line.len().saturating_sub(1) / self.cell_count * self.cell_count;
+        }
+    }
+
+    fn window(&self) -> &[char] {
+        let end = (self.offset + self.cell_count).min(self.line.len());
+        &self.line[self.offset.min(end)..end]
+    }
+
+    fn pan_left(&mut self) -> bool {
+        if self.offset == 0 {
+            return false;
+        }
+        self.offset = self.offset.saturating_sub(self.cell_count);
+        true
+    }
+
+    fn pan_right(&mut self) -> bool {
+        if self.offset + self.cell_count >= self.line.len() {
+            return false;
+        }
+        self.offset += self.cell_count;
+        true
+    }
+}
+
+pub struct braille_simulator {
+    _private: [u8; 0],
+}
+
+impl CastPtr for braille_simulator {
+    type RustType = BrailleSimulator;
+}
+
+impl BoxCastPtr for braille_simulator {}
+
+impl braille_simulator {
+    /// Simulates a braille display with `cell_count` cells. Returns null if
+    /// `cell_count` is zero.
+    #[no_mangle]
+    pub extern "C" fn accesskit_braille_simulator_new(cell_count: usize) -> *mut braille_simulator {
+        if cell_count == 0 {
+            return ptr::null_mut();
+        }
+        let simulator = BrailleSimulator {
+            cell_count,
+            line: Vec::new(),
+            focus: None,
+            offset: 0,
+        };
+        BoxCastPtr::to_mut_ptr(simulator)
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_braille_simulator_free(simulator: *mut braille_simulator) {
+        drop(box_from_ptr(simulator));
+    }
+
+    /// Renders the focused node of `snapshot` and its context into the
+    /// braille line. The node's braille label and braille role description
+    /// are used when present, otherwise its name and an abbreviation
+    /// of its role are used.
+    ///
+    /// The display is panned back to the start of the line when the focus
+    /// has moved since the last call.
+    #[no_mangle]
+    pub extern "C" fn accesskit_braille_simulator_render(
+        simulator: *mut braille_simulator,
+        snapshot: *const tree_snapshot,
+    ) {
+        let simulator = mut_from_ptr(simulator);
+        let snapshot = ref_from_ptr(snapshot);
+        simulator.render(snapshot);
+    }
+
+    /// Returns false if the display is already showing the start of the line.
+    #[no_mangle]
+    pub extern "C" fn accesskit_braille_simulator_pan_left(
+        simulator: *mut braille_simulator,
+    ) -> bool {
+        let simulator = mut_from_ptr(simulator);
+        simulator.pan_left()
+    }
+
+    /// Returns false if the display is already showing the end of the line.
+    #[no_mangle]
+    pub extern "C" fn accesskit_braille_simulator_pan_right(
+        simulator: *mut braille_simulator,
+    ) -> bool {
+        let simulator = mut_from_ptr(simulator);
+        simulator.pan_right()
+    }
+
+    /// Returns the index in characters of the first character on the display.
+    #[no_mangle]
+    pub extern "C" fn accesskit_braille_simulator_offset(
+        simulator: *const braille_simulator,
+    ) -> usize {
+        let simulator = ref_from_ptr(simulator);
+        simulator.offset
+    }
+
+    /// Returns the whole braille line as text.
+    ///
+    /// Caller must call `accesskit_string_free` with the return value.
+    #[no_mangle]
+    pub extern "C" fn accesskit_braille_simulator_line(
+        simulator: *const braille_simulator,
+    ) -> *mut c_char {
+        let simulator = ref_from_ptr(simulator);
+        text_from_chars(&simulator.line)
+    }
+
+    /// Returns the part of the line currently shown on the display as text.
+    ///
+    /// Caller must call `accesskit_string_free` with the return value.
+    #[no_mangle]
+    pub extern "C" fn accesskit_braille_simulator_text(
+        simulator: *const braille_simulator,
+    ) -> *mut c_char {
+        let simulator = ref_from_ptr(simulator);
+        text_from_chars(simulator.window())
+    }
+
+    /// Returns the content of every cell of the display as Unicode braille
+    /// patterns encoded in UTF-8, using North American Braille Computer Code.
+    /// Unused cells are blank; characters that can't be represented
+    /// have all eight dots raised.
+    ///
+    /// Caller must call `accesskit_string_free` with the return value.
+    #[no_mangle]
+    pub extern "C" fn accesskit_braille_simulator_cells(
+        simulator: *const braille_simulator,
+    ) -> *mut c_char {
+        let simulator = ref_from_ptr(simulator);
+        let window = simulator.window();
+        let cells: String = window
+            .iter()
+            .map(|c| braille_cell(*c))
+            .chain(std::iter::repeat_n(
+                char::from_u32(BLANK_CELL).unwrap(),
+                simulator.cell_count - window.len(),
+            ))
+            .collect();
+        CString::new(cells).unwrap().into_raw()
+    }
+}
@@ -0,0 +1,175 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{Node, NodeId, Role, TreeId, TreeUpdate};
+use std::{collections::HashSet, ptr};
+
+use crate::{
+    box_from_ptr, mut_from_ptr, node, node_id, ref_from_ptr, tree, tree_update, BoxCastPtr, CastPtr,
+};
+
+pub(crate) struct TreeBuilder {
+    update: TreeUpdate,
+    /// Indices in `update.nodes` of the nodes that are open, innermost last.
+    open: Vec<usize>,
+    ids: HashSet<NodeId>,
+    next_id: u64,
+}
+
+impl TreeBuilder {
+    fn open_node(&mut self, id: NodeId, role: Role) -> Option<&mut Node> {
+        if !self.ids.insert(id) {
+            return None;
+        }
+        if let Some(&parent) = self.open.last() {
+            self.update.nodes[parent].1.push_child(id);
+        }
+        self.open.push(self.update.nodes.len());
+        self.update.nodes.push((id, Node::new(role)));
+        Some(&mut self.update.nodes.last_mut().unwrap().1)
+    }
+
+    fn next_id(&mut self) -> NodeId {
+        while self.ids.contains(&NodeId(self.next_id)) {
+            self.next_id += 1;
+        }
+        let id = NodeId(self.next_id);
+        self.next_id += 1;
+        id
+    }
+}
+
+/// Builds a tree update by opening and closing nodes, the way a toolkit
+/// walks its widget hierarchy: nodes opened while another one is open
+/// become its children, in order, and no node can be added twice.
+pub struct tree_builder {
+    _private: [u8; 0],
+}
+
+impl CastPtr for tree_builder {
+    type RustType = TreeBuilder;
+}
+
+impl BoxCastPtr for tree_builder {}
+
+impl tree_builder {
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_builder_new(focus: node_id) -> *mut tree_builder {
+        BoxCastPtr::to_mut_ptr(TreeBuilder {
+            update: TreeUpdate {
+                nodes: vec![],
+                tree: None,
+                tree_id: TreeId::ROOT,
+                focus: focus.into(),
+            },
+            open: Vec::new(),
+            ids: HashSet::new(),
+            next_id: 0,
+        })
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_builder_free(builder: *mut tree_builder) {
+        drop(box_from_ptr(builder));
+    }
+
+    /// Takes ownership of `tree`.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_builder_set_tree(builder: *mut tree_builder, tree: *mut tree) {
+        let builder = mut_from_ptr(builder);
+        builder.update.tree = Some(*box_from_ptr(tree));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_builder_set_focus(builder: *mut tree_builder, focus: node_id) {
+        let builder = mut_from_ptr(builder);
+        builder.update.focus = focus.into();
+    }
+
+    /// Returns the lowest ID, counting up from the one previously returned,
+    /// that hasn't been given to a node of this builder. IDs are only known
+    /// to be unique within the builder, so this is meant for updates that
+    /// hold the whole tree.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_builder_next_id(builder: *mut tree_builder) -> node_id {
+        let builder = mut_from_ptr(builder);
+        builder.next_id().into()
+    }
+
+    /// Opens a node, which is appended to the children of the node that
+    /// is currently open, if any. Nodes opened with nothing else open are
+    /// the top-level nodes of the update, the first of which is usually
+    /// the root or a node that was already in the tree. Returns the new
+    /// node so that its properties can be set; its children are filled in
+    /// by the builder.
+    ///
+    /// Returns null, and opens nothing, if a node with this ID was already
+    /// added to the builder.
+    ///
+    /// The returned node is owned by the builder, and is only valid until
+    /// the next call to `accesskit_tree_builder_open_node`.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_builder_open_node(
+        builder: *mut tree_builder,
+        id: node_id,
+        role: Role,
+    ) -> *mut node {
+        let builder = mut_from_ptr(builder);
+        match builder.open_node(id.into(), role) {
+            Some(node) => node as *mut _ as *mut node,
+            None => ptr::null_mut(),
+        }
+    }
+
+    /// Appends `id` to the children of the node that is currently open,
+    /// without adding a node for it, for a child that is already in the
+    /// tree and hasn't changed. Returns false if no node is open, or if
+    /// `id` was already added to the builder.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_builder_push_existing_child(
+        builder: *mut tree_builder,
+        id: node_id,
+    ) -> bool {
+        let builder = mut_from_ptr(builder);
+        let id = id.into();
+        match builder.open.last() {
+            Some(&parent) if builder.ids.insert(id) => {
+                builder.update.nodes[parent].1.push_child(id);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Closes the node that is currently open. Returns false if no node
+    /// is open.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_builder_close_node(builder: *mut tree_builder) -> bool {
+        let builder = mut_from_ptr(builder);
+        builder.open.pop().is_some()
+    }
+
+    /// Returns the number of nodes that are open.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_builder_depth(builder: *const tree_builder) -> usize {
+        let builder = ref_from_ptr(builder);
+        builder.open.len()
+    }
+
+    /// Returns the tree update built so far, with nodes in the order they
+    /// were opened. Takes ownership of `builder`.
+    ///
+    /// Returns null, freeing `builder`, if a node is still open.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_builder_finish(
+        builder: *mut tree_builder,
+    ) -> *mut tree_update {
+        let builder = box_from_ptr(builder);
+        if !builder.open.is_empty() {
+            return ptr::null_mut();
+        }
+        BoxCastPtr::to_mut_ptr(builder.update)
+    }
+}
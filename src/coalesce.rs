@@ -0,0 +1,298 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{NodeId, TreeId, TreeUpdate};
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    box_from_ptr, mut_from_ptr, node_id, ref_from_ptr, tree_update, tree_update_factory_userdata,
+    BoxCastPtr, CastPtr,
+};
+
+fn listed_children(update: &TreeUpdate) -> HashSet<NodeId> {
+    update
+        .nodes
+        .iter()
+        .flat_map(|(_, node)| node.children().iter().copied())
+        .collect()
+}
+
+/// Applies `src` on top of `dst`, so that applying the result is the same
+/// as applying both in order. `src` is left empty, with its storage intact.
+/// Returns false, leaving both updates unchanged, if they are for different
+/// trees.
+///
+/// A node that one of the updates listed as a child, but that no node of
+/// the result lists anymore, was removed by `src` and is dropped from the
+/// result along with its own unlisted descendants, unless it is the new
+/// root. Nodes whose parent isn't part of either update are kept: they
+/// must already be in the tree, and the updates alone can't tell whether
+/// their parent still lists them.
+pub(crate) fn merge_from(dst: &mut TreeUpdate, src: &mut TreeUpdate) -> bool {
+    if dst.tree_id != src.tree_id {
+        return false;
+    }
+    let mut listed_before = listed_children(dst);
+    listed_before.extend(listed_children(src));
+    let mut positions: HashMap<NodeId, usize> = dst
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(index, (id, _))| (*id, index))
+        .collect();
+    for (id, node) in src.nodes.drain(..) {
+        match positions.get(&id) {
+            Some(&index) => dst.nodes[index].1 = node,
+            None => {
+                positions.insert(id, dst.nodes.len());
+                dst.nodes.push((id, node));
+            }
+        }
+    }
+    if let Some(tree) = src.tree.take() {
+        dst.tree = Some(tree);
+    }
+    dst.focus = src.focus;
+    drop_unlisted(dst, &listed_before);
+    true
+}
+
+fn drop_unlisted(update: &mut TreeUpdate, listed_before: &HashSet<NodeId>) {
+    let root = update.tree.as_ref().map(|tree| tree.root);
+    loop {
+        let listed = listed_children(update);
+        let len = update.nodes.len();
+        update.nodes.retain(|(id, _)| {
+            Some(*id) == root || listed.contains(id) || !listed_before.contains(id)
+        });
+        if update.nodes.len() == len {
+            break;
+        }
+    }
+}
+
+pub(crate) fn merge(dst: &mut TreeUpdate, mut src: TreeUpdate) -> bool {
+    merge_from(dst, &mut src)
+}
+
+impl tree_update {
+    /// Merges `src` into `dst`, so that applying `dst` has the same effect
+    /// as applying the original `dst` followed by `src`. Nodes of `src`
+    /// replace the nodes of `dst` with the same ID, and the others are
+    /// appended. `dst` gets the focus of `src`, and its `tree` field if set.
+    /// Nodes that `src` removes from a parent listed in either update are
+    /// dropped, so that a node added by `dst` and removed by `src` is not
+    /// sent at all. A node whose parent is in neither update is kept, since
+    /// that requires knowing the current tree. Takes ownership of `src`.
+    ///
+    /// Returns false, leaving `dst` unchanged and freeing `src`, if the two
+    /// updates have different tree IDs.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_merge(
+        dst: *mut tree_update,
+        src: *mut tree_update,
+    ) -> bool {
+        let dst = mut_from_ptr(dst);
+        let src = box_from_ptr(src);
+        merge(dst, *src)
+    }
+}
+
+pub(crate) struct TreeUpdateQueue {
+    pending: Option<TreeUpdate>,
+    /// The tree and focus of the last update taken out of the queue.
+    tree_id: TreeId,
+    focus: NodeId,
+}
+
+impl TreeUpdateQueue {
+    /// Merges `update` into the pending update, or makes it the pending
+    /// update if there is none. `release` is given what is left of `update`
+    /// once merged, or all of it if it is for another tree.
+    pub(crate) fn push(
+        &mut self,
+        mut update: TreeUpdate,
+        release: impl FnOnce(TreeUpdate),
+    ) -> bool {
+        match &mut self.pending {
+            Some(pending) => {
+                let merged = merge_from(pending, &mut update);
+                release(update);
+                merged
+            }
+            None => {
+                self.pending = Some(update);
+                true
+            }
+        }
+    }
+
+    /// Drops the pending update, giving it to `release`.
+    pub(crate) fn clear(&mut self, release: impl FnOnce(TreeUpdate)) {
+        if let Some(update) = self.pending.take() {
+            release(update);
+        }
+    }
+
+    fn take(&mut self) -> Option<TreeUpdate> {
+        let update = self.pending.take()?;
+        self.tree_id = update.tree_id;
+        self.focus = update.focus;
+        Some(update)
+    }
+}
+
+/// Tree updates waiting to be given to an adapter, squashed into one.
+pub struct tree_update_queue {
+    _private: [u8; 0],
+}
+
+impl CastPtr for tree_update_queue {
+    type RustType = TreeUpdateQueue;
+}
+
+impl BoxCastPtr for tree_update_queue {}
+
+impl tree_update_queue {
+    /// Creates an empty queue for the root tree. `focus` is the focus
+    /// given to the adapter until an update is taken out of the queue.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_queue_new(focus: node_id) -> *mut tree_update_queue {
+        BoxCastPtr::to_mut_ptr(TreeUpdateQueue {
+            pending: None,
+            tree_id: TreeId::ROOT,
+            focus: focus.into(),
+        })
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_queue_free(queue: *mut tree_update_queue) {
+        drop(box_from_ptr(queue));
+    }
+
+    /// Merges `update` into the pending update, as with
+    /// `accesskit_tree_update_merge`, including its handling of removed
+    /// nodes. Takes ownership of `update`.
+    ///
+    /// Returns false, freeing `update`, if it is for another tree than the
+    /// pending update.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_queue_push(
+        queue: *mut tree_update_queue,
+        update: *mut tree_update,
+    ) -> bool {
+        let queue = mut_from_ptr(queue);
+        queue.push(*box_from_ptr(update), drop)
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_queue_is_empty(
+        queue: *const tree_update_queue,
+    ) -> bool {
+        let queue = ref_from_ptr(queue);
+        queue.pending.is_none()
+    }
+
+    /// Drops the pending update.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_queue_clear(queue: *mut tree_update_queue) {
+        let queue = mut_from_ptr(queue);
+        queue.clear(drop);
+    }
+
+    /// Removes the pending update from the queue and returns it, or null
+    /// if the queue is empty.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_queue_take(
+        queue: *mut tree_update_queue,
+    ) -> *mut tree_update {
+        let queue = mut_from_ptr(queue);
+        BoxCastPtr::to_nullable_mut_ptr(queue.take())
+    }
+
+    /// A tree update factory taking the pending update out of the queue
+    /// passed as userdata, to be given to the `update_if_active` function
+    /// of an adapter when the queue isn't empty. The factory isn't called
+    /// if the adapter is inactive, in which case the update stays queued;
+    /// call `accesskit_tree_update_queue_clear` if it is no longer needed.
+    ///
+    /// If the queue is empty, returns an update without nodes, keeping the
+    /// tree and focus of the last update taken out of the queue, or the
+    /// focus the queue was created with if none was taken yet.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_queue_factory(
+        userdata: tree_update_factory_userdata,
+    ) -> *mut tree_update {
+        let queue = mut_from_ptr(userdata.0 as *mut tree_update_queue);
+        let update = queue.take().unwrap_or_else(|| TreeUpdate {
+            nodes: Vec::new(),
+            tree: None,
+            tree_id: queue.tree_id,
+            focus: queue.focus,
+        });
+        BoxCastPtr::to_mut_ptr(update)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use accesskit::{Node, Role, Tree, Uuid};
+
+    use super::*;
+
+    fn update(nodes: Vec<(u64, Vec<u64>)>) -> TreeUpdate {
+        let nodes = nodes
+            .into_iter()
+            .map(|(id, children)| {
+                let mut node = Node::new(Role::GenericContainer);
+                node.set_children(children.into_iter().map(NodeId).collect::<Vec<_>>());
+                (NodeId(id), node)
+            })
+            .collect();
+        TreeUpdate {
+            nodes,
+            tree: None,
+            tree_id: TreeId::ROOT,
+            focus: NodeId(0),
+        }
+    }
+
+    fn ids(update: &TreeUpdate) -> Vec<u64> {
+        update.nodes.iter().map(|(id, _)| id.0).collect()
+    }
+
+    #[test]
+    fn add_then_remove() {
+        let mut dst = update(vec![(0, vec![1]), (1, vec![2]), (2, vec![])]);
+        assert!(merge(&mut dst, update(vec![(0, vec![])])));
+        assert_eq!(ids(&dst), [0]);
+        assert!(dst.nodes[0].1.children().is_empty());
+    }
+
+    #[test]
+    fn keeps_nodes_with_parent_outside_updates() {
+        let mut dst = update(vec![(1, vec![]), (3, vec![])]);
+        assert!(merge(&mut dst, update(vec![(0, vec![1])])));
+        assert_eq!(ids(&dst), [1, 3, 0]);
+    }
+
+    #[test]
+    fn keeps_moved_nodes_and_new_root() {
+        let mut dst = update(vec![(0, vec![1]), (1, vec![]), (2, vec![])]);
+        let mut src = update(vec![(0, vec![]), (2, vec![1])]);
+        src.tree = Some(Tree::new(NodeId(2)));
+        assert!(merge(&mut dst, src));
+        assert_eq!(ids(&dst), [0, 1, 2]);
+    }
+
+    #[test]
+    fn rejects_other_tree() {
+        let mut dst = update(vec![(0, vec![])]);
+        let mut src = update(vec![(1, vec![])]);
+        src.tree_id = TreeId(Uuid::from_u128(1));
+        assert!(!merge(&mut dst, src));
+        assert_eq!(ids(&dst), [0]);
+    }
+}
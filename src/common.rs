@@ -9,6 +9,7 @@ use std::{
     mem,
     os::raw::{c_char, c_void},
     ptr, slice,
+    sync::Arc,
 };
 
 use crate::{
@@ -228,6 +229,37 @@ pub static ACCESSKIT_TREE_ID_ROOT: tree_id = tree_id { bytes: [0; 16] };
 
 slice_struct! { node_ids, NodeId, node_id }
 
+/// An array of node IDs owned by the caller.
+#[repr(C)]
+pub struct node_id_list {
+    pub length: usize,
+    pub values: *mut node_id,
+}
+
+impl CastPtr for node_id_list {
+    type RustType = node_id_list;
+}
+
+impl BoxCastPtr for node_id_list {}
+
+impl node_id_list {
+    #[no_mangle]
+    pub extern "C" fn accesskit_node_id_list_free(value: *mut node_id_list) {
+        let array = box_from_ptr(value);
+        drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(array.values, array.length)) });
+    }
+}
+
+impl From<Vec<NodeId>> for node_id_list {
+    fn from(values: Vec<NodeId>) -> Self {
+        let ffi_values: Box<[node_id]> = values.into_iter().map(Into::into).collect();
+        Self {
+            length: ffi_values.len(),
+            values: Box::into_raw(ffi_values) as *mut node_id,
+        }
+    }
+}
+
 macro_rules! node_id_vec_property_methods {
     ($(($c_getter:ident, $getter:ident, $c_setter:ident, $setter:ident, $c_pusher:ident, $pusher:ident, $c_clearer:ident, $clearer:ident)),+) => {
         $(vec_property_methods! {
@@ -881,6 +913,12 @@ impl tree {
         drop(box_from_ptr(tree));
     }
 
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_get_root(tree: *const tree) -> node_id {
+        let tree = ref_from_ptr(tree);
+        tree.root.into()
+    }
+
     /// Caller must call `accesskit_string_free` with the return value.
     #[no_mangle]
     pub extern "C" fn accesskit_tree_get_toolkit_name(tree: *const tree) -> *mut c_char {
@@ -1007,6 +1045,18 @@ impl tree_update {
         drop(box_from_ptr(update));
     }
 
+    /// Removes all nodes and unsets the `tree` field, keeping the memory
+    /// allocated for the list of nodes so that the update can be refilled
+    /// without reallocating it. The tree ID is reset to the root tree.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_clear(update: *mut tree_update, focus: node_id) {
+        let update = mut_from_ptr(update);
+        update.nodes.clear();
+        update.tree = None;
+        update.tree_id = TreeId::ROOT;
+        update.focus = focus.into();
+    }
+
     /// Appends the provided node to the tree update's list of nodes.
     /// Takes ownership of `node`.
     #[no_mangle]
@@ -1020,6 +1070,130 @@ impl tree_update {
         update.nodes.push((id.into(), *node));
     }
 
+    /// Appends a node with the provided role to the tree update's list of
+    /// nodes, and returns it so that its properties can be set. The node
+    /// is built in place, without the separate allocation made by
+    /// `accesskit_node_new`. It is owned by the tree update, and the
+    /// pointer is only valid until nodes are next added to or removed from
+    /// the update, since the list may then be moved to a larger buffer.
+    /// Use `accesskit_tree_update_with_capacity_and_focus` to allocate the
+    /// list once when the number of nodes is known.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_push_new_node(
+        update: *mut tree_update,
+        id: node_id,
+        role: Role,
+    ) -> *mut node {
+        let update = mut_from_ptr(update);
+        update.nodes.push((id.into(), Node::new(role)));
+        &mut update.nodes.last_mut().unwrap().1 as *mut _ as *mut node
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_node_count(update: *const tree_update) -> usize {
+        let update = ref_from_ptr(update);
+        update.nodes.len()
+    }
+
+    /// `index` must be lower than the value returned by
+    /// `accesskit_tree_update_node_count`.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_node_id_at(
+        update: *const tree_update,
+        index: usize,
+    ) -> node_id {
+        let update = ref_from_ptr(update);
+        update.nodes[index].0.into()
+    }
+
+    /// `index` must be lower than the value returned by
+    /// `accesskit_tree_update_node_count`.
+    /// The returned node is owned by the tree update.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_node_at(
+        update: *const tree_update,
+        index: usize,
+    ) -> *const node {
+        let update = ref_from_ptr(update);
+        &update.nodes[index].1 as *const _ as *const node
+    }
+
+    /// Returns null if no node with this ID was pushed. If there are several,
+    /// returns the last one, which is the one platform adapters use.
+    /// The returned node is owned by the tree update.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_find_node(
+        update: *const tree_update,
+        id: node_id,
+    ) -> *const node {
+        let update = ref_from_ptr(update);
+        let id = NodeId::from(id);
+        match update.nodes.iter().rfind(|(other, _)| *other == id) {
+            Some((_, node)) => node as *const _ as *const node,
+            None => ptr::null(),
+        }
+    }
+
+    /// Same as `accesskit_tree_update_find_node`, but the node can be
+    /// modified in place.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_find_node_mut(
+        update: *mut tree_update,
+        id: node_id,
+    ) -> *mut node {
+        let update = mut_from_ptr(update);
+        let id = NodeId::from(id);
+        match update.nodes.iter_mut().rfind(|(other, _)| *other == id) {
+            Some((_, node)) => node as *mut _ as *mut node,
+            None => ptr::null_mut(),
+        }
+    }
+
+    /// Replaces the node that `accesskit_tree_update_find_node` would return,
+    /// keeping its position in the list. Takes ownership of `node`, which is
+    /// freed if no node with this ID was pushed; returns false in that case.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_replace_node(
+        update: *mut tree_update,
+        id: node_id,
+        node: *mut node,
+    ) -> bool {
+        let update = mut_from_ptr(update);
+        let node = box_from_ptr(node);
+        let id = NodeId::from(id);
+        match update.nodes.iter_mut().rfind(|(other, _)| *other == id) {
+            Some(entry) => {
+                entry.1 = *node;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes every node pushed with this ID. Returns false if there was none.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_remove_node(
+        update: *mut tree_update,
+        id: node_id,
+    ) -> bool {
+        let update = mut_from_ptr(update);
+        let id = NodeId::from(id);
+        let len = update.nodes.len();
+        update.nodes.retain(|(other, _)| *other != id);
+        update.nodes.len() != len
+    }
+
+    /// Returns null if the tree update doesn't have its `tree` field set.
+    /// The returned tree is owned by the tree update.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_get_tree(update: *const tree_update) -> *const tree {
+        let update = ref_from_ptr(update);
+        match update.tree.as_ref() {
+            Some(tree) => tree as *const _ as *const tree,
+            None => ptr::null(),
+        }
+    }
+
     #[no_mangle]
     pub extern "C" fn accesskit_tree_update_set_tree(update: *mut tree_update, tree: *mut tree) {
         let update = mut_from_ptr(update);
@@ -1032,6 +1206,12 @@ impl tree_update {
         update.tree = None;
     }
 
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_get_focus(update: *const tree_update) -> node_id {
+        let update = ref_from_ptr(update);
+        update.focus.into()
+    }
+
     #[no_mangle]
     pub extern "C" fn accesskit_tree_update_set_focus(update: *mut tree_update, focus: node_id) {
         let update = mut_from_ptr(update);
@@ -1108,6 +1288,10 @@ pub struct action_request {
     pub data: opt_action_data,
 }
 
+impl CastPtr for action_request {
+    type RustType = action_request;
+}
+
 impl From<ActionRequest> for action_request {
     fn from(request: ActionRequest) -> action_request {
         Self {
@@ -1124,31 +1308,80 @@ pub extern "C" fn accesskit_action_request_free(request: *mut action_request) {
     drop(unsafe { Box::from_raw(request) });
 }
 
-type ActivationHandlerCallbackUnwrapped = extern "C" fn(userdata: *mut c_void) -> *mut tree_update;
-pub type ActivationHandlerCallback =
-    Option<extern "C" fn(userdata: *mut c_void) -> *mut tree_update>;
+/// Called once the library no longer references the userdata given along
+/// with a handler, possibly from another thread, so that it can be freed.
+pub type DestroyNotifyCallback = Option<extern "C" fn(userdata: *mut c_void)>;
+
+/// The userdata of a handler, shared by its copies, whose destroy notify
+/// callback is called when the last copy is dropped.
+struct HandlerUserdataInner {
+    userdata: *mut c_void,
+    destroy_notify: DestroyNotifyCallback,
+}
+
+unsafe impl Send for HandlerUserdataInner {}
+unsafe impl Sync for HandlerUserdataInner {}
+
+impl Drop for HandlerUserdataInner {
+    fn drop(&mut self) {
+        if let Some(destroy_notify) = self.destroy_notify {
+            destroy_notify(self.userdata);
+        }
+    }
+}
 
-struct FfiActivationHandlerUserdata(*mut c_void);
+#[derive(Clone)]
+struct HandlerUserdata(Arc<HandlerUserdataInner>);
 
-unsafe impl Send for FfiActivationHandlerUserdata {}
+impl HandlerUserdata {
+    fn new(userdata: *mut c_void, destroy_notify: DestroyNotifyCallback) -> Self {
+        Self(Arc::new(HandlerUserdataInner {
+            userdata,
+            destroy_notify,
+        }))
+    }
 
+    fn get(&self) -> *mut c_void {
+        self.0.userdata
+    }
+}
+
+/// Returns the initial tree of the adapter, or null if it isn't ready yet.
+/// The adapter then keeps activating until the tree is given to its
+/// `provide_initial_tree` function, or a full tree update to its
+/// `update_if_active` function. A null callback always returns null.
+pub type ActivationHandlerCallback =
+    Option<extern "C" fn(userdata: *mut c_void) -> *mut tree_update>;
+
+#[derive(Clone)]
 pub(crate) struct FfiActivationHandler {
-    callback: ActivationHandlerCallbackUnwrapped,
-    userdata: FfiActivationHandlerUserdata,
+    callback: ActivationHandlerCallback,
+    userdata: HandlerUserdata,
 }
 
 impl FfiActivationHandler {
     pub(crate) fn new(callback: ActivationHandlerCallback, userdata: *mut c_void) -> Self {
+        Self::with_destroy_notify(callback, userdata, None)
+    }
+
+    pub(crate) fn with_destroy_notify(
+        callback: ActivationHandlerCallback,
+        userdata: *mut c_void,
+        destroy_notify: DestroyNotifyCallback,
+    ) -> Self {
         Self {
-            callback: callback.unwrap(),
-            userdata: FfiActivationHandlerUserdata(userdata),
+            callback,
+            userdata: HandlerUserdata::new(userdata, destroy_notify),
         }
     }
 }
 
 impl ActivationHandler for FfiActivationHandler {
     fn request_initial_tree(&mut self) -> Option<TreeUpdate> {
-        let result = (self.callback)(self.userdata.0);
+        let result = match self.callback {
+            Some(callback) => callback(self.userdata.get()),
+            None => ptr::null_mut(),
+        };
         if result.is_null() {
             None
         } else {
@@ -1157,64 +1390,78 @@ impl ActivationHandler for FfiActivationHandler {
     }
 }
 
-type ActionHandlerCallbackUnwrapped =
-    extern "C" fn(request: *mut action_request, userdata: *mut c_void);
-
 /// Ownership of `request` is transferred to the callback. `request` must
 /// be freed using `accesskit_action_request_free`.
+///
+/// If null, action requests are ignored.
 pub type ActionHandlerCallback =
     Option<extern "C" fn(request: *mut action_request, userdata: *mut c_void)>;
 
-struct FfiActionHandlerUserdata(*mut c_void);
-
-unsafe impl Send for FfiActionHandlerUserdata {}
-
+#[derive(Clone)]
 pub(crate) struct FfiActionHandler {
-    callback: ActionHandlerCallbackUnwrapped,
-    userdata: FfiActionHandlerUserdata,
+    callback: ActionHandlerCallback,
+    userdata: HandlerUserdata,
 }
 
 impl FfiActionHandler {
     pub(crate) fn new(callback: ActionHandlerCallback, userdata: *mut c_void) -> Self {
+        Self::with_destroy_notify(callback, userdata, None)
+    }
+
+    pub(crate) fn with_destroy_notify(
+        callback: ActionHandlerCallback,
+        userdata: *mut c_void,
+        destroy_notify: DestroyNotifyCallback,
+    ) -> Self {
         Self {
-            callback: callback.unwrap(),
-            userdata: FfiActionHandlerUserdata(userdata),
+            callback,
+            userdata: HandlerUserdata::new(userdata, destroy_notify),
         }
     }
 }
 
 impl ActionHandler for FfiActionHandler {
     fn do_action(&mut self, request: ActionRequest) {
-        let request = Box::new(action_request::from(request));
-        (self.callback)(Box::into_raw(request), self.userdata.0);
+        if let Some(callback) = self.callback {
+            let request = Box::new(action_request::from(request));
+            callback(Box::into_raw(request), self.userdata.get());
+        }
     }
 }
 
-type DeactivationHandlerCallbackUnwrapped = extern "C" fn(userdata: *mut c_void);
+/// If null, deactivation goes unnoticed.
 pub type DeactivationHandlerCallback = Option<extern "C" fn(userdata: *mut c_void)>;
 
-struct FfiDeactivationHandlerUserdata(*mut c_void);
-
-unsafe impl Send for FfiDeactivationHandlerUserdata {}
-
+#[derive(Clone)]
 pub(crate) struct FfiDeactivationHandler {
-    callback: DeactivationHandlerCallbackUnwrapped,
-    userdata: FfiDeactivationHandlerUserdata,
+    callback: DeactivationHandlerCallback,
+    userdata: HandlerUserdata,
 }
 
 impl FfiDeactivationHandler {
     #[allow(dead_code)]
     pub(crate) fn new(callback: DeactivationHandlerCallback, userdata: *mut c_void) -> Self {
+        Self::with_destroy_notify(callback, userdata, None)
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn with_destroy_notify(
+        callback: DeactivationHandlerCallback,
+        userdata: *mut c_void,
+        destroy_notify: DestroyNotifyCallback,
+    ) -> Self {
         Self {
-            callback: callback.unwrap(),
-            userdata: FfiDeactivationHandlerUserdata(userdata),
+            callback,
+            userdata: HandlerUserdata::new(userdata, destroy_notify),
         }
     }
 }
 
 impl DeactivationHandler for FfiDeactivationHandler {
     fn deactivate_accessibility(&mut self) {
-        (self.callback)(self.userdata.0);
+        if let Some(callback) = self.callback {
+            callback(self.userdata.get());
+        }
     }
 }
 
@@ -1224,5 +1471,7 @@ pub struct tree_update_factory_userdata(pub *mut c_void);
 unsafe impl Send for tree_update_factory_userdata {}
 
 /// This function can't return a null pointer. Ownership of the returned value will be transferred to the caller.
+///
+/// Passing a null factory to an `update_if_active` function leaves the tree unchanged.
 pub type tree_update_factory =
     Option<extern "C" fn(tree_update_factory_userdata) -> *mut tree_update>;
@@ -0,0 +1,314 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{Color, Node, NodeId, Role};
+use std::{
+    ffi::{c_char, CString},
+    fmt::Write,
+    iter,
+};
+
+use crate::{
+    box_from_ptr, format_number, node_id, ref_from_ptr, role_name, tree_snapshot, BoxCastPtr,
+    CastPtr, TreeSnapshot,
+};
+
+const WHITE: Color = Color {
+    red: 255,
+    green: 255,
+    blue: 255,
+    alpha: 255,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum contrast_level {
+    /// WCAG level AA: 4.5:1 for normal text, 3:1 for large text.
+    Aa,
+    /// WCAG level AAA: 7:1 for normal text, 4.5:1 for large text.
+    Aaa,
+}
+
+impl contrast_level {
+    fn required_ratio(self, large_text: bool) -> f64 {
+        match (self, large_text) {
+            (Self::Aa, false) => 4.5,
+            (Self::Aa, true) => 3.0,
+            (Self::Aaa, false) => 7.0,
+            (Self::Aaa, true) => 4.5,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Aa => "AA",
+            Self::Aaa => "AAA",
+        }
+    }
+}
+
+fn channel_luminance(value: u8) -> f64 {
+    let value = f64::from(value) / 255.0;
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn relative_luminance(color: Color) -> f64 {
+    0.2126 * channel_luminance(color.red)
+        + 0.7152 * channel_luminance(color.green)
+        + 0.0722 * channel_luminance(color.blue)
+}
+
+fn contrast_ratio(first: Color, second: Color) -> f64 {
+    let first = relative_luminance(first);
+    let second = relative_luminance(second);
+    (first.max(second) + 0.05) / (first.min(second) + 0.05)
+}
+
+/// Paints `color` over the opaque `base`.
+fn blend(color: Color, base: Color) -> Color {
+    let alpha = u32::from(color.alpha);
+    let mix = |top: u8, bottom: u8| {
+        ((u32::from(top) * alpha + u32::from(bottom) * (255 - alpha) + 127) / 255) as u8
+    };
+    Color {
+        red: mix(color.red, base.red),
+        green: mix(color.green, base.green),
+        blue: mix(color.blue, base.blue),
+        alpha: 255,
+    }
+}
+
+fn format_color(color: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.red, color.green, color.blue)
+}
+
+/// Text is large from 24px, or from 18.66px (14pt) when bold.
+fn is_large_text(font_size: Option<f32>, font_weight: Option<f32>) -> bool {
+    match font_size {
+        Some(size) => size >= 24.0 || (size >= 18.66 && font_weight.is_some_and(|w| w >= 700.0)),
+        None => false,
+    }
+}
+
+pub(crate) struct ContrastFinding {
+    node: NodeId,
+    role: Role,
+    foreground: Color,
+    background: Color,
+    ratio: f64,
+    large_text: bool,
+    level: contrast_level,
+}
+
+impl ContrastFinding {
+    fn required_ratio(&self) -> f64 {
+        self.level.required_ratio(self.large_text)
+    }
+}
+
+/// Resolves the colors and font of a node the way they are inherited:
+/// each property comes from the nearest node that sets it, and translucent
+/// backgrounds are painted over the ones further up the tree. Returns
+/// `None` if no foreground or no background color is set on the node or
+/// its ancestors.
+fn resolve_style(
+    snapshot: &TreeSnapshot,
+    id: NodeId,
+    node: &Node,
+) -> Option<(Color, Color, Option<f32>, Option<f32>)> {
+    let chain: Vec<&Node> = iter::once(node)
+        .chain(
+            snapshot
+                .ancestor_ids(id)
+                .map(|ancestor| snapshot.node(ancestor).unwrap()),
+        )
+        .collect();
+    let foreground = chain.iter().find_map(|node| node.foreground_color())?;
+    let backgrounds: Vec<Color> = chain
+        .iter()
+        .filter_map(|node| node.background_color())
+        .collect();
+    if backgrounds.is_empty() {
+        return None;
+    }
+    // A stack of translucent backgrounds with nothing opaque below it is
+    // assumed to be drawn on a white canvas.
+    let background = backgrounds
+        .iter()
+        .rev()
+        .fold(WHITE, |base, &color| blend(color, base));
+    let font_size = chain.iter().find_map(|node| node.font_size());
+    let font_weight = chain.iter().find_map(|node| node.font_weight());
+    Some((
+        blend(foreground, background),
+        background,
+        font_size,
+        font_weight,
+    ))
+}
+
+/// Checks the text runs and labels that aren't hidden, in document order.
+pub(crate) fn audit(snapshot: &TreeSnapshot, level: contrast_level) -> Vec<ContrastFinding> {
+    let mut findings = Vec::new();
+    snapshot.visit(|id, node, _| {
+        let role = node.role();
+        if !matches!(role, Role::TextRun | Role::Label)
+            || node.is_hidden()
+            || snapshot
+                .ancestor_ids(id)
+                .any(|ancestor| snapshot.node(ancestor).unwrap().is_hidden())
+        {
+            return;
+        }
+        let Some((foreground, background, font_size, font_weight)) =
+            resolve_style(snapshot, id, node)
+        else {
+            return;
+        };
+        let ratio = contrast_ratio(foreground, background);
+        let large_text = is_large_text(font_size, font_weight);
+        let failed = [contrast_level::Aa, contrast_level::Aaa]
+            .into_iter()
+            .filter(|&other| other <= level)
+            .find(|other| ratio < other.required_ratio(large_text));
+        if let Some(failed) = failed {
+            findings.push(ContrastFinding {
+                node: id,
+                role,
+                foreground,
+                background,
+                ratio,
+                large_text,
+                level: failed,
+            });
+        }
+    });
+    findings
+}
+
+fn render(findings: &[ContrastFinding]) -> String {
+    let mut output = String::new();
+    for finding in findings {
+        writeln!(
+            output,
+            "#{} {}: {} on {} is {}:1, {} requires {}:1 for {} text",
+            finding.node.0,
+            role_name(finding.role),
+            format_color(finding.foreground),
+            format_color(finding.background),
+            format_number((finding.ratio * 100.0).floor() / 100.0),
+            finding.level.name(),
+            format_number(finding.required_ratio()),
+            if finding.large_text {
+                "large"
+            } else {
+                "normal"
+            }
+        )
+        .unwrap();
+    }
+    output
+}
+
+/// A text run or label whose contrast is too low.
+///
+/// `foreground` and `background` are the effective colors, after
+/// inheritance and alpha blending. `level` is the lowest level the text
+/// fails to meet, and `required_ratio` the ratio that level requires.
+#[repr(C)]
+pub struct contrast_finding {
+    pub node: node_id,
+    pub foreground: Color,
+    pub background: Color,
+    pub ratio: f64,
+    pub required_ratio: f64,
+    pub large_text: bool,
+    pub level: contrast_level,
+}
+
+pub struct contrast_findings {
+    _private: [u8; 0],
+}
+
+impl CastPtr for contrast_findings {
+    type RustType = Vec<ContrastFinding>;
+}
+
+impl BoxCastPtr for contrast_findings {}
+
+impl contrast_findings {
+    /// Computes the WCAG contrast ratio of every text run and label that
+    /// isn't hidden, and lists the ones that don't meet `level`.
+    ///
+    /// Foreground color, background color, font size and font weight are
+    /// inherited from the nearest ancestor that sets them. Translucent
+    /// colors are blended over the background beneath them, ending with
+    /// white if no background is opaque. Nodes without an effective
+    /// foreground or background color are skipped. Text is large if its
+    /// font size is at least 24px, or at least 18.66px with a font weight
+    /// of 700 or more; text without a font size is considered normal.
+    #[no_mangle]
+    pub extern "C" fn accesskit_contrast_audit(
+        snapshot: *const tree_snapshot,
+        level: contrast_level,
+    ) -> *mut contrast_findings {
+        let snapshot = ref_from_ptr(snapshot);
+        BoxCastPtr::to_mut_ptr(audit(snapshot, level))
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_contrast_findings_free(findings: *mut contrast_findings) {
+        drop(box_from_ptr(findings));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_contrast_findings_len(findings: *const contrast_findings) -> usize {
+        let findings = ref_from_ptr(findings);
+        findings.len()
+    }
+
+    /// `index` must be lower than the value returned by
+    /// `accesskit_contrast_findings_len`.
+    #[no_mangle]
+    pub extern "C" fn accesskit_contrast_findings_get(
+        findings: *const contrast_findings,
+        index: usize,
+    ) -> contrast_finding {
+        let findings = ref_from_ptr(findings);
+        let finding = &findings[index];
+        contrast_finding {
+            node: finding.node.into(),
+            foreground: finding.foreground,
+            background: finding.background,
+            ratio: finding.ratio,
+            required_ratio: finding.required_ratio(),
+            large_text: finding.large_text,
+            level: finding.level,
+        }
+    }
+
+    /// Renders the findings one per line, for instance
+    /// `#4 label: #777777 on #ffffff is 4.47:1, AA requires 4.5:1 for normal text`.
+    ///
+    /// Caller must call `accesskit_string_free` with the return value.
+    #[no_mangle]
+    pub extern "C" fn accesskit_contrast_findings_to_string(
+        findings: *const contrast_findings,
+    ) -> *mut c_char {
+        let findings = ref_from_ptr(findings);
+        CString::new(render(findings)).unwrap().into_raw()
+    }
+}
+
+/// Returns the WCAG contrast ratio between two colors, from 1 to 21.
+/// The alpha channel of both colors is ignored.
+#[no_mangle]
+pub extern "C" fn accesskit_contrast_ratio(first: Color, second: Color) -> f64 {
+    contrast_ratio(first, second)
+}
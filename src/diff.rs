@@ -0,0 +1,321 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::NodeId;
+use std::{
+    ffi::{c_char, CString},
+    fmt::Write,
+    ptr,
+};
+
+use crate::{
+    box_from_ptr, dump_node, format_ids, node_id, node_properties, opt_node_id, quote,
+    ref_from_ptr, role_name, tree_dump_options, tree_snapshot, tree_update, BoxCastPtr, CastPtr,
+    Property, TreeSnapshot,
+};
+
+const SUMMARY: tree_dump_options = tree_dump_options {
+    ids: false,
+    states: false,
+    values: false,
+    text: false,
+    bounds: false,
+    relations: false,
+    actions: false,
+    hidden: true,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum tree_change_kind {
+    /// The node is only present in the new tree. `new_value` holds
+    /// its role and name.
+    Added,
+    /// The node is only present in the old tree. `old_value` holds
+    /// its role and name.
+    Removed,
+    /// The node has a different parent in each tree.
+    Reparented,
+    /// `property` has a different value in each tree. A null value
+    /// means the property isn't set.
+    PropertyChanged,
+}
+
+pub(crate) struct TreeChange {
+    kind: tree_change_kind,
+    node: NodeId,
+    old_parent: Option<NodeId>,
+    new_parent: Option<NodeId>,
+    property: Option<CString>,
+    old_value: Option<CString>,
+    new_value: Option<CString>,
+}
+
+fn to_c_string(value: String) -> CString {
+    CString::new(value).unwrap()
+}
+
+fn format_parent(parent: Option<NodeId>) -> String {
+    match parent {
+        Some(parent) => format!("#{}", parent.0),
+        None => "(root)".into(),
+    }
+}
+
+fn format_value(value: &Option<CString>) -> String {
+    match value {
+        Some(value) => value.to_string_lossy().into_owned(),
+        None => "(none)".into(),
+    }
+}
+
+impl TreeChange {
+    fn render(&self, output: &mut String) {
+        let id = self.node.0;
+        match self.kind {
+            tree_change_kind::Added => writeln!(
+                output,
+                "added #{} under {}: {}",
+                id,
+                format_parent(self.new_parent),
+                format_value(&self.new_value)
+            ),
+            tree_change_kind::Removed => writeln!(
+                output,
+                "removed #{} from {}: {}",
+                id,
+                format_parent(self.old_parent),
+                format_value(&self.old_value)
+            ),
+            tree_change_kind::Reparented => writeln!(
+                output,
+                "moved #{} from {} to {}",
+                id,
+                format_parent(self.old_parent),
+                format_parent(self.new_parent)
+            ),
+            tree_change_kind::PropertyChanged => writeln!(
+                output,
+                "changed #{} {}: {} -> {}",
+                id,
+                format_value(&self.property),
+                format_value(&self.old_value),
+                format_value(&self.new_value)
+            ),
+        }
+        .unwrap();
+    }
+}
+
+/// The properties compared between two versions of a node, including
+/// the ones a tree dump always shows.
+fn compared_properties(snapshot: &TreeSnapshot, id: NodeId) -> Vec<(&'static str, String)> {
+    let node = snapshot.node(id).unwrap();
+    let mut properties = vec![("role", role_name(node.role()).into())];
+    if let Some(name) = snapshot.name(id) {
+        properties.push(("name", quote(&name)));
+    }
+    let children: Vec<NodeId> = snapshot.child_ids(id).collect();
+    if !children.is_empty() {
+        properties.push(("children", format_ids(&children)));
+    }
+    properties.extend(
+        node_properties(snapshot, id, node)
+            .into_iter()
+            .map(|Property { name, value, .. }| (name, value)),
+    );
+    properties
+}
+
+fn find_property<'a>(properties: &'a [(&str, String)], name: &str) -> Option<&'a String> {
+    properties
+        .iter()
+        .find(|(other, _)| *other == name)
+        .map(|(_, value)| value)
+}
+
+/// Compares two snapshots. Removed nodes come first, in the document order
+/// of the old tree, followed by the other changes in the document order
+/// of the new tree.
+pub(crate) fn diff(old: &TreeSnapshot, new: &TreeSnapshot) -> Vec<TreeChange> {
+    let mut changes = Vec::new();
+    old.visit(|id, node, _| {
+        if new.node(id).is_none() {
+            changes.push(TreeChange {
+                kind: tree_change_kind::Removed,
+                node: id,
+                old_parent: old.parent_id(id),
+                new_parent: None,
+                property: None,
+                old_value: Some(to_c_string(dump_node(old, id, node, &SUMMARY))),
+                new_value: None,
+            });
+        }
+    });
+    new.visit(|id, node, _| {
+        if old.node(id).is_none() {
+            changes.push(TreeChange {
+                kind: tree_change_kind::Added,
+                node: id,
+                old_parent: None,
+                new_parent: new.parent_id(id),
+                property: None,
+                old_value: None,
+                new_value: Some(to_c_string(dump_node(new, id, node, &SUMMARY))),
+            });
+            return;
+        }
+        let old_parent = old.parent_id(id);
+        let new_parent = new.parent_id(id);
+        if old_parent != new_parent {
+            changes.push(TreeChange {
+                kind: tree_change_kind::Reparented,
+                node: id,
+                old_parent,
+                new_parent,
+                property: None,
+                old_value: None,
+                new_value: None,
+            });
+        }
+        let old_properties = compared_properties(old, id);
+        let new_properties = compared_properties(new, id);
+        let mut push_change =
+            |name: &str, old_value: Option<&String>, new_value: Option<&String>| {
+                changes.push(TreeChange {
+                    kind: tree_change_kind::PropertyChanged,
+                    node: id,
+                    old_parent,
+                    new_parent,
+                    property: Some(to_c_string(name.into())),
+                    old_value: old_value.cloned().map(to_c_string),
+                    new_value: new_value.cloned().map(to_c_string),
+                });
+            };
+        for (name, value) in &new_properties {
+            let old_value = find_property(&old_properties, name);
+            if old_value != Some(value) {
+                push_change(name, old_value, Some(value));
+            }
+        }
+        for (name, value) in &old_properties {
+            if find_property(&new_properties, name).is_none() {
+                push_change(name, Some(value), None);
+            }
+        }
+    });
+    changes
+}
+
+fn render(changes: &[TreeChange]) -> String {
+    let mut output = String::new();
+    for change in changes {
+        change.render(&mut output);
+    }
+    output
+}
+
+/// A change between two versions of a tree.
+///
+/// The strings are owned by the `accesskit_tree_diff` this was retrieved from.
+#[repr(C)]
+pub struct tree_change {
+    pub kind: tree_change_kind,
+    pub node: node_id,
+    pub old_parent: opt_node_id,
+    pub new_parent: opt_node_id,
+    pub property: *const c_char,
+    pub old_value: *const c_char,
+    pub new_value: *const c_char,
+}
+
+fn c_str_ptr(value: &Option<CString>) -> *const c_char {
+    value.as_ref().map_or(ptr::null(), |value| value.as_ptr())
+}
+
+impl From<&TreeChange> for tree_change {
+    fn from(change: &TreeChange) -> Self {
+        Self {
+            kind: change.kind,
+            node: change.node.into(),
+            old_parent: change.old_parent.into(),
+            new_parent: change.new_parent.into(),
+            property: c_str_ptr(&change.property),
+            old_value: c_str_ptr(&change.old_value),
+            new_value: c_str_ptr(&change.new_value),
+        }
+    }
+}
+
+pub struct tree_diff {
+    _private: [u8; 0],
+}
+
+impl CastPtr for tree_diff {
+    type RustType = Vec<TreeChange>;
+}
+
+impl BoxCastPtr for tree_diff {}
+
+impl tree_diff {
+    /// Lists the differences between two snapshots: nodes that were added,
+    /// removed or moved to another parent, and properties whose value
+    /// changed. Properties are compared by their rendering in
+    /// `accesskit_tree_dump`, with all property groups included, along with
+    /// the node's role, name and children. A change of focus shows up as
+    /// a change of the `focused` state.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_diff(
+        old_snapshot: *const tree_snapshot,
+        new_snapshot: *const tree_snapshot,
+    ) -> *mut tree_diff {
+        let old_snapshot = ref_from_ptr(old_snapshot);
+        let new_snapshot = ref_from_ptr(new_snapshot);
+        BoxCastPtr::to_mut_ptr(diff(old_snapshot, new_snapshot))
+    }
+
+    /// Lists what applying `update` to `snapshot` would change, without
+    /// modifying `snapshot`. Takes ownership of `update`.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_diff_update(
+        snapshot: *const tree_snapshot,
+        update: *mut tree_update,
+    ) -> *mut tree_diff {
+        let snapshot = ref_from_ptr(snapshot);
+        let update = box_from_ptr(update);
+        let mut updated = snapshot.clone();
+        updated.apply(*update);
+        BoxCastPtr::to_mut_ptr(diff(snapshot, &updated))
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_diff_free(diff: *mut tree_diff) {
+        drop(box_from_ptr(diff));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_diff_len(diff: *const tree_diff) -> usize {
+        let diff = ref_from_ptr(diff);
+        diff.len()
+    }
+
+    /// `index` must be lower than the value returned by
+    /// `accesskit_tree_diff_len`.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_diff_get(diff: *const tree_diff, index: usize) -> tree_change {
+        let diff = ref_from_ptr(diff);
+        (&diff[index]).into()
+    }
+
+    /// Renders the changes one per line, for instance
+    /// `changed #3 toggled: true -> false`.
+    ///
+    /// Caller must call `accesskit_string_free` with the return value.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_diff_to_string(diff: *const tree_diff) -> *mut c_char {
+        let diff = ref_from_ptr(diff);
+        CString::new(render(diff)).unwrap().into_raw()
+    }
+}
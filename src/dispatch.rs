@@ -0,0 +1,226 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{Action, NodeId};
+use std::{collections::HashMap, ffi::c_void, sync::Mutex};
+
+use crate::{
+    accesskit_action_request_free, action_request, box_from_ptr, node_id, ref_from_ptr, BoxCastPtr,
+    CastPtr,
+};
+
+type DispatchedActionHandlerCallbackUnwrapped =
+    extern "C" fn(request: *const action_request, userdata: *mut c_void) -> bool;
+
+/// Called by an action dispatcher with a request routed to it. Returns
+/// whether the request was handled; if not, the dispatcher tries the next
+/// handler that matches the request. `request` is owned by the dispatcher
+/// and is only valid during the call.
+pub type DispatchedActionHandlerCallback =
+    Option<extern "C" fn(request: *const action_request, userdata: *mut c_void) -> bool>;
+
+type UnhandledActionCallbackUnwrapped =
+    extern "C" fn(request: *const action_request, userdata: *mut c_void);
+
+/// Called by an action dispatcher with a request that no handler took.
+/// `request` is owned by the dispatcher and is only valid during the call.
+pub type UnhandledActionCallback =
+    Option<extern "C" fn(request: *const action_request, userdata: *mut c_void)>;
+
+#[derive(Clone, Copy)]
+struct Handler<C> {
+    callback: C,
+    userdata: *mut c_void,
+}
+
+impl<C> Handler<C> {
+    fn new(callback: Option<C>, userdata: *mut c_void) -> Option<Self> {
+        callback.map(|callback| Self { callback, userdata })
+    }
+}
+
+type DispatchedHandler = Handler<DispatchedActionHandlerCallbackUnwrapped>;
+
+#[derive(Default)]
+pub(crate) struct ActionDispatcher {
+    action_handlers: HashMap<(NodeId, Action), DispatchedHandler>,
+    node_handlers: HashMap<NodeId, DispatchedHandler>,
+    fallback: Option<DispatchedHandler>,
+    unhandled: Option<Handler<UnhandledActionCallbackUnwrapped>>,
+}
+
+/// The handlers that can match a request, most specific first.
+#[derive(Clone, Copy)]
+enum HandlerKind {
+    Action,
+    Node,
+    Fallback,
+}
+
+impl ActionDispatcher {
+    fn handler(
+        &self,
+        kind: HandlerKind,
+        node: NodeId,
+        action: Action,
+    ) -> Option<DispatchedHandler> {
+        match kind {
+            HandlerKind::Action => self.action_handlers.get(&(node, action)).copied(),
+            HandlerKind::Node => self.node_handlers.get(&node).copied(),
+            HandlerKind::Fallback => self.fallback,
+        }
+    }
+}
+
+/// Routes action requests to handlers registered for a node and an action,
+/// for a whole node, or for any request.
+///
+/// The handlers are called on the thread the request is dispatched from,
+/// without the dispatcher being locked, so they can register or remove
+/// handlers. A handler removed while a request is being dispatched isn't
+/// called for it afterwards.
+pub struct action_dispatcher {
+    _private: [u8; 0],
+}
+
+impl CastPtr for action_dispatcher {
+    type RustType = Mutex<ActionDispatcher>;
+}
+
+impl BoxCastPtr for action_dispatcher {}
+
+impl action_dispatcher {
+    #[no_mangle]
+    pub extern "C" fn accesskit_action_dispatcher_new() -> *mut action_dispatcher {
+        BoxCastPtr::to_mut_ptr(Mutex::new(ActionDispatcher::default()))
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_action_dispatcher_free(dispatcher: *mut action_dispatcher) {
+        drop(box_from_ptr(dispatcher));
+    }
+
+    /// Sets the handler for `action` on `node`, replacing any previous one.
+    /// Passing a null callback removes the handler.
+    #[no_mangle]
+    pub extern "C" fn accesskit_action_dispatcher_set_handler(
+        dispatcher: *const action_dispatcher,
+        node: node_id,
+        action: Action,
+        callback: DispatchedActionHandlerCallback,
+        userdata: *mut c_void,
+    ) {
+        let mut dispatcher = ref_from_ptr(dispatcher).lock().unwrap();
+        let key = (node.into(), action);
+        match Handler::new(callback, userdata) {
+            Some(handler) => dispatcher.action_handlers.insert(key, handler),
+            None => dispatcher.action_handlers.remove(&key),
+        };
+    }
+
+    /// Sets the handler for the actions on `node` that have no handler of
+    /// their own, replacing any previous one. Passing a null callback
+    /// removes the handler.
+    #[no_mangle]
+    pub extern "C" fn accesskit_action_dispatcher_set_node_handler(
+        dispatcher: *const action_dispatcher,
+        node: node_id,
+        callback: DispatchedActionHandlerCallback,
+        userdata: *mut c_void,
+    ) {
+        let mut dispatcher = ref_from_ptr(dispatcher).lock().unwrap();
+        let node = node.into();
+        match Handler::new(callback, userdata) {
+            Some(handler) => dispatcher.node_handlers.insert(node, handler),
+            None => dispatcher.node_handlers.remove(&node),
+        };
+    }
+
+    /// Sets the handler tried last for every request. Passing a null
+    /// callback removes it.
+    #[no_mangle]
+    pub extern "C" fn accesskit_action_dispatcher_set_fallback(
+        dispatcher: *const action_dispatcher,
+        callback: DispatchedActionHandlerCallback,
+        userdata: *mut c_void,
+    ) {
+        let mut dispatcher = ref_from_ptr(dispatcher).lock().unwrap();
+        dispatcher.fallback = Handler::new(callback, userdata);
+    }
+
+    /// Sets the callback told about requests that no handler took. Passing
+    /// a null callback removes it.
+    #[no_mangle]
+    pub extern "C" fn accesskit_action_dispatcher_set_unhandled_callback(
+        dispatcher: *const action_dispatcher,
+        callback: UnhandledActionCallback,
+        userdata: *mut c_void,
+    ) {
+        let mut dispatcher = ref_from_ptr(dispatcher).lock().unwrap();
+        dispatcher.unhandled = Handler::new(callback, userdata);
+    }
+
+    /// Removes all the handlers registered for `node`, to be called when
+    /// it is removed from the tree.
+    #[no_mangle]
+    pub extern "C" fn accesskit_action_dispatcher_remove_node(
+        dispatcher: *const action_dispatcher,
+        node: node_id,
+    ) {
+        let mut dispatcher = ref_from_ptr(dispatcher).lock().unwrap();
+        let node = node.into();
+        dispatcher.node_handlers.remove(&node);
+        dispatcher
+            .action_handlers
+            .retain(|(other, _), _| *other != node);
+    }
+
+    /// Calls the handlers matching `request`, from the one registered for
+    /// its target node and action, then the one for its target node, to the
+    /// fallback, until one of them handles it. Returns false, after calling
+    /// the unhandled callback, if none did. Takes ownership of `request`,
+    /// which is freed before returning.
+    #[no_mangle]
+    pub extern "C" fn accesskit_action_dispatcher_dispatch(
+        dispatcher: *const action_dispatcher,
+        request: *mut action_request,
+    ) -> bool {
+        let dispatcher = ref_from_ptr(dispatcher);
+        let (node, action) = {
+            let request = ref_from_ptr(request);
+            (request.target_node.into(), request.action)
+        };
+        // Each handler is looked up right before being called, since the
+        // previous ones may have removed it.
+        let handled = [
+            HandlerKind::Action,
+            HandlerKind::Node,
+            HandlerKind::Fallback,
+        ]
+        .into_iter()
+        .any(|kind| {
+            let handler = dispatcher.lock().unwrap().handler(kind, node, action);
+            handler.is_some_and(|handler| (handler.callback)(request, handler.userdata))
+        });
+        if !handled {
+            let unhandled = dispatcher.lock().unwrap().unhandled;
+            if let Some(unhandled) = unhandled {
+                (unhandled.callback)(request, unhandled.userdata);
+            }
+        }
+        accesskit_action_request_free(request);
+        handled
+    }
+
+    /// An action handler calling `accesskit_action_dispatcher_dispatch` on
+    /// the dispatcher passed as userdata, to be given to an adapter.
+    #[no_mangle]
+    pub extern "C" fn accesskit_action_dispatcher_action_handler(
+        request: *mut action_request,
+        userdata: *mut c_void,
+    ) {
+        Self::accesskit_action_dispatcher_dispatch(userdata as *const action_dispatcher, request);
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::NodeId;
+use std::{
+    collections::BTreeSet,
+    ffi::{c_char, CString},
+    fmt::Write,
+};
+
+use crate::{node_relations, ref_from_ptr, role_name, tree_snapshot, TreeSnapshot};
+
+fn relation_color(relation: &str) -> &'static str {
+    match relation {
+        "labelled_by" => "blue",
+        "described_by" => "darkgreen",
+        "controls" => "crimson",
+        "details" => "purple",
+        "flow_to" => "orange",
+        "owns" => "brown",
+        "radio_group" => "deeppink",
+        "active_descendant" => "darkcyan",
+        "error_message" => "firebrick",
+        "popup_for" => "goldenrod",
+        "member_of" => "slateblue",
+        _ => "gray",
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub(crate) fn to_dot(snapshot: &TreeSnapshot) -> String {
+    let mut output = String::from("digraph accesskit {\n");
+    output.push_str("  node [shape=box, fontname=\"sans-serif\"];\n");
+    output.push_str("  edge [fontname=\"sans-serif\", fontsize=10];\n");
+    let mut edges = String::new();
+    let mut missing = BTreeSet::new();
+    snapshot.visit(|id, node, _| {
+        let mut label = format!("#{} {}", id.0, role_name(node.role()));
+        if let Some(name) = snapshot.name(id) {
+            write!(label, "\n{}", name).unwrap();
+        }
+        let mut attributes = format!("label=\"{}\"", escape(&label));
+        if snapshot.focus_id() == id {
+            attributes.push_str(", penwidth=3");
+        }
+        if node.is_hidden() {
+            attributes.push_str(", style=dotted");
+        }
+        writeln!(output, "  n{} [{}];", id.0, attributes).unwrap();
+        for child in snapshot.child_ids(id) {
+            writeln!(edges, "  n{} -> n{};", id.0, child.0).unwrap();
+        }
+        for (relation, targets) in node_relations(node) {
+            for target in targets {
+                if snapshot.node(target).is_none() {
+                    missing.insert(target);
+                }
+                writeln!(
+                    edges,
+                    "  n{} -> n{} [style=dashed, constraint=false, color={color}, fontcolor={color}, label=\"{}\"];",
+                    id.0,
+                    target.0,
+                    relation,
+                    color = relation_color(relation)
+                )
+                .unwrap();
+            }
+        }
+    });
+    for NodeId(id) in missing {
+        writeln!(
+            output,
+            "  n{} [label=\"#{} (missing)\", style=dashed, color=red];",
+            id, id
+        )
+        .unwrap();
+    }
+    output.push_str(&edges);
+    output.push_str("}\n");
+    output
+}
+
+impl tree_snapshot {
+    /// Renders the tree in the Graphviz DOT language. Each node is labelled
+    /// with its ID, role and name; the focused node has a thicker border
+    /// and hidden nodes a dotted one. Parent-child links are solid edges,
+    /// and relations such as `labelled_by` or `controls` are dashed edges
+    /// labelled with the relation name and colored by relation type.
+    /// Relations pointing to nodes that aren't in the tree lead to a red
+    /// placeholder node.
+    ///
+    /// Caller must call `accesskit_string_free` with the return value.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_to_dot(snapshot: *const tree_snapshot) -> *mut c_char {
+        let snapshot = ref_from_ptr(snapshot);
+        CString::new(to_dot(snapshot)).unwrap().into_raw()
+    }
+}
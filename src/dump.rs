@@ -0,0 +1,377 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{Action, Node, NodeId, Toggled};
+use std::{
+    ffi::{c_char, CString},
+    fmt::Write,
+};
+
+use crate::{ref_from_ptr, role_name, tree_snapshot, variant_name, TreeSnapshot, VariantName};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PropertyGroup {
+    States,
+    Values,
+    Text,
+    Bounds,
+    Relations,
+    Actions,
+}
+
+/// A property of a node rendered as text. States that are simply set
+/// have the value `true`; string values are quoted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Property {
+    pub(crate) group: PropertyGroup,
+    pub(crate) name: &'static str,
+    pub(crate) value: String,
+}
+
+pub(crate) fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => write!(quoted, "\\u{{{:x}}}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Formats whole numbers without a fractional part, and other numbers
+/// with the shortest representation that round-trips.
+pub(crate) fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+pub(crate) fn format_ids(ids: &[NodeId]) -> String {
+    ids.iter()
+        .map(|id| format!("#{}", id.0))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// The relations from a node to other nodes that are set, other than
+/// its children.
+pub(crate) fn node_relations(node: &Node) -> Vec<(&'static str, Vec<NodeId>)> {
+    let mut relations = Vec::new();
+    for (name, ids) in [
+        ("labelled_by", node.labelled_by()),
+        ("described_by", node.described_by()),
+        ("controls", node.controls()),
+        ("details", node.details()),
+        ("flow_to", node.flow_to()),
+        ("owns", node.owns()),
+        ("radio_group", node.radio_group()),
+    ] {
+        if !ids.is_empty() {
+            relations.push((name, ids.to_vec()));
+        }
+    }
+    for (name, id) in [
+        ("active_descendant", node.active_descendant()),
+        ("error_message", node.error_message()),
+        ("in_page_link_target", node.in_page_link_target()),
+        ("member_of", node.member_of()),
+        ("popup_for", node.popup_for()),
+        ("next_on_line", node.next_on_line()),
+        ("previous_on_line", node.previous_on_line()),
+    ] {
+        if let Some(id) = id {
+            relations.push((name, vec![id]));
+        }
+    }
+    relations
+}
+
+/// Lists the properties of a node in a fixed order, independently of
+/// the order in which they were set.
+pub(crate) fn node_properties(snapshot: &TreeSnapshot, id: NodeId, node: &Node) -> Vec<Property> {
+    let mut properties = Vec::new();
+    let mut push = |group, name, value: String| {
+        properties.push(Property { group, name, value });
+    };
+
+    use PropertyGroup::*;
+    match node.toggled() {
+        Some(Toggled::True) => push(States, "toggled", "true".into()),
+        Some(Toggled::False) => push(States, "toggled", "false".into()),
+        Some(Toggled::Mixed) => push(States, "toggled", "mixed".into()),
+        None => {}
+    }
+    if let Some(expanded) = node.is_expanded() {
+        push(States, "expanded", expanded.to_string());
+    }
+    if let Some(selected) = node.is_selected() {
+        push(States, "selected", selected.to_string());
+    }
+    if let Some(current) = node.aria_current() {
+        push(States, "current", variant_name(current).into());
+    }
+    if let Some(invalid) = node.invalid() {
+        push(States, "invalid", variant_name(invalid).into());
+    }
+    if let Some(has_popup) = node.has_popup() {
+        push(States, "has_popup", variant_name(has_popup).into());
+    }
+    if let Some(auto_complete) = node.auto_complete() {
+        push(States, "auto_complete", variant_name(auto_complete).into());
+    }
+    if let Some(orientation) = node.orientation() {
+        push(States, "orientation", variant_name(orientation).into());
+    }
+    if let Some(sort_direction) = node.sort_direction() {
+        push(
+            States,
+            "sort_direction",
+            variant_name(sort_direction).into(),
+        );
+    }
+    if let Some(live) = node.live() {
+        push(States, "live", variant_name(live).into());
+    }
+    for (name, set) in [
+        ("focused", snapshot.focus_id() == id),
+        ("focusable", node.supports_action(Action::Focus)),
+        ("disabled", node.is_disabled()),
+        ("hidden", node.is_hidden()),
+        ("required", node.is_required()),
+        ("read_only", node.is_read_only()),
+        ("multiselectable", node.is_multiselectable()),
+        ("modal", node.is_modal()),
+        ("busy", node.is_busy()),
+        ("visited", node.is_visited()),
+    ] {
+        if set {
+            push(States, name, "true".into());
+        }
+    }
+
+    if let Some(value) = node.value() {
+        push(Values, "value", quote(value));
+    }
+    for (name, value) in [
+        ("numeric_value", node.numeric_value()),
+        ("min_numeric_value", node.min_numeric_value()),
+        ("max_numeric_value", node.max_numeric_value()),
+        ("numeric_value_step", node.numeric_value_step()),
+    ] {
+        if let Some(value) = value {
+            push(Values, name, format_number(value));
+        }
+    }
+    for (name, value) in [
+        ("level", node.level()),
+        ("position_in_set", node.position_in_set()),
+        ("size_of_set", node.size_of_set()),
+        ("row_index", node.row_index()),
+        ("column_index", node.column_index()),
+        ("row_count", node.row_count()),
+        ("column_count", node.column_count()),
+    ] {
+        if let Some(value) = value {
+            push(Values, name, value.to_string());
+        }
+    }
+
+    for (name, value) in [
+        ("description", node.description()),
+        ("placeholder", node.placeholder()),
+        ("tooltip", node.tooltip()),
+        ("role_description", node.role_description()),
+        ("state_description", node.state_description()),
+        ("keyboard_shortcut", node.keyboard_shortcut()),
+        ("access_key", node.access_key()),
+        ("language", node.language()),
+        ("url", node.url()),
+        ("author_id", node.author_id()),
+        ("class_name", node.class_name()),
+    ] {
+        if let Some(value) = value {
+            push(Text, name, quote(value));
+        }
+    }
+
+    if let Some(bounds) = node.bounds() {
+        push(
+            Bounds,
+            "bounds",
+            format!(
+                "({}, {})-({}, {})",
+                format_number(bounds.x0),
+                format_number(bounds.y0),
+                format_number(bounds.x1),
+                format_number(bounds.y1)
+            ),
+        );
+    }
+    if let Some(transform) = node.transform() {
+        let coeffs: Vec<String> = transform
+            .as_coeffs()
+            .iter()
+            .map(|c| format_number(*c))
+            .collect();
+        push(Bounds, "transform", format!("[{}]", coeffs.join(", ")));
+    }
+
+    for (name, ids) in node_relations(node) {
+        push(Relations, name, format_ids(&ids));
+    }
+
+    let actions: Vec<&str> = Action::ALL
+        .iter()
+        .filter(|action| node.supports_action(**action))
+        .map(|action| variant_name(*action))
+        .collect();
+    if !actions.is_empty() {
+        push(Actions, "actions", actions.join(","));
+    }
+
+    properties
+}
+
+/// Which parts of each node to include in a tree dump.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct tree_dump_options {
+    /// Prefix each line with the node ID, as in `#3`.
+    pub ids: bool,
+    /// Toggled, expanded, selected, focused, disabled and other states.
+    pub states: bool,
+    /// The value, numeric value and range, level, and position in set.
+    pub values: bool,
+    /// Description, placeholder, tooltip and other secondary strings.
+    pub text: bool,
+    /// Bounding boxes and transforms.
+    pub bounds: bool,
+    /// Relations to other nodes, such as `labelled_by` or `controls`.
+    pub relations: bool,
+    /// The list of supported actions.
+    pub actions: bool,
+    /// Include hidden nodes and their descendants.
+    pub hidden: bool,
+}
+
+impl Default for tree_dump_options {
+    fn default() -> Self {
+        Self {
+            ids: false,
+            states: true,
+            values: true,
+            text: false,
+            bounds: true,
+            relations: false,
+            actions: false,
+            hidden: true,
+        }
+    }
+}
+
+impl tree_dump_options {
+    fn includes(&self, group: PropertyGroup) -> bool {
+        match group {
+            PropertyGroup::States => self.states,
+            PropertyGroup::Values => self.values,
+            PropertyGroup::Text => self.text,
+            PropertyGroup::Bounds => self.bounds,
+            PropertyGroup::Relations => self.relations,
+            PropertyGroup::Actions => self.actions,
+        }
+    }
+
+    /// Returns the default options: states, values and bounds are
+    /// included, as well as hidden nodes.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_dump_options_default() -> tree_dump_options {
+        Self::default()
+    }
+}
+
+/// Renders one node on a single line, without indentation.
+pub(crate) fn dump_node(
+    snapshot: &TreeSnapshot,
+    id: NodeId,
+    node: &Node,
+    options: &tree_dump_options,
+) -> String {
+    let mut line = String::new();
+    if options.ids {
+        write!(line, "#{} ", id.0).unwrap();
+    }
+    line.push_str(role_name(node.role()));
+    if let Some(name) = snapshot.name(id) {
+        write!(line, " {}", quote(&name)).unwrap();
+    }
+    let properties: Vec<Property> = node_properties(snapshot, id, node)
+        .into_iter()
+        .filter(|property| options.includes(property.group))
+        .collect();
+    let states: Vec<String> = properties
+        .iter()
+        .filter(|property| property.group == PropertyGroup::States)
+        .map(|property| match property.value.as_str() {
+            "true" => property.name.to_string(),
+            value => format!("{}={}", property.name, value),
+        })
+        .collect();
+    if !states.is_empty() {
+        write!(line, " [{}]", states.join(", ")).unwrap();
+    }
+    for property in &properties {
+        if property.group != PropertyGroup::States {
+            write!(line, " {}={}", property.name, property.value).unwrap();
+        }
+    }
+    line
+}
+
+pub(crate) fn dump(snapshot: &TreeSnapshot, options: &tree_dump_options) -> String {
+    let mut output = String::new();
+    let mut hidden_depth = None;
+    snapshot.visit(|id, node, depth| {
+        if hidden_depth.is_some_and(|hidden_depth| depth > hidden_depth) {
+            return;
+        }
+        hidden_depth = None;
+        if !options.hidden && node.is_hidden() {
+            hidden_depth = Some(depth);
+            return;
+        }
+        for _ in 0..depth {
+            output.push_str("  ");
+        }
+        output.push_str(&dump_node(snapshot, id, node, options));
+        output.push('\n');
+    });
+    output
+}
+
+impl tree_snapshot {
+    /// Renders the tree as indented text, one node per line with its role
+    /// and name followed by the property groups selected in `options`.
+    /// Children are indented by two spaces. The output only depends on the
+    /// content of the tree, so it can be compared against golden files.
+    ///
+    /// Caller must call `accesskit_string_free` with the return value.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_dump(
+        snapshot: *const tree_snapshot,
+        options: tree_dump_options,
+    ) -> *mut c_char {
+        let snapshot = ref_from_ptr(snapshot);
+        CString::new(dump(snapshot, &options)).unwrap().into_raw()
+    }
+}
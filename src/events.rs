@@ -0,0 +1,115 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{ActionHandler, ActionRequest, ActivationHandler, DeactivationHandler, TreeUpdate};
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+    os::unix::{
+        io::{AsRawFd, RawFd},
+        net::UnixStream,
+    },
+    sync::{Arc, Mutex},
+};
+
+use crate::action_request;
+
+/// Something that happened to an adapter, returned by its `poll_event`
+/// function when it was created with an event queue.
+#[repr(C)]
+pub enum adapter_event {
+    /// Assistive technologies started using the adapter. It has no tree
+    /// yet: give it one using its `provide_initial_tree` function.
+    Activated,
+    /// Ownership of the request is transferred to the caller. It must be
+    /// freed using `accesskit_action_request_free`.
+    ActionRequested(*mut action_request),
+    Deactivated,
+}
+
+enum QueuedEvent {
+    Activated,
+    ActionRequested(ActionRequest),
+    Deactivated,
+}
+
+impl From<QueuedEvent> for adapter_event {
+    fn from(event: QueuedEvent) -> Self {
+        match event {
+            QueuedEvent::Activated => Self::Activated,
+            QueuedEvent::ActionRequested(request) => {
+                Self::ActionRequested(Box::into_raw(Box::new(request.into())))
+            }
+            QueuedEvent::Deactivated => Self::Deactivated,
+        }
+    }
+}
+
+struct EventQueueState {
+    events: Mutex<VecDeque<QueuedEvent>>,
+    /// A byte is written to the socket when the queue stops being empty and
+    /// read back when it becomes empty again, so that the other end of the
+    /// pair is readable exactly while events are pending.
+    reader: UnixStream,
+    writer: UnixStream,
+}
+
+/// Events received by the handlers of an adapter, waiting for the thread
+/// owning the adapter to take them.
+#[derive(Clone)]
+pub(crate) struct EventQueue(Arc<EventQueueState>);
+
+impl EventQueue {
+    pub(crate) fn new() -> io::Result<Self> {
+        let (reader, writer) = UnixStream::pair()?;
+        reader.set_nonblocking(true)?;
+        writer.set_nonblocking(true)?;
+        Ok(Self(Arc::new(EventQueueState {
+            events: Mutex::new(VecDeque::new()),
+            reader,
+            writer,
+        })))
+    }
+
+    fn push(&self, event: QueuedEvent) {
+        let mut events = self.0.events.lock().unwrap();
+        if events.is_empty() {
+            let _ = (&self.0.writer).write(&[0]);
+        }
+        events.push_back(event);
+    }
+
+    pub(crate) fn pop(&self) -> Option<adapter_event> {
+        let mut events = self.0.events.lock().unwrap();
+        let event = events.pop_front()?;
+        if events.is_empty() {
+            let _ = (&self.0.reader).read(&mut [0]);
+        }
+        Some(event.into())
+    }
+
+    pub(crate) fn fd(&self) -> RawFd {
+        self.0.reader.as_raw_fd()
+    }
+}
+
+impl ActivationHandler for EventQueue {
+    fn request_initial_tree(&mut self) -> Option<TreeUpdate> {
+        self.push(QueuedEvent::Activated);
+        None
+    }
+}
+
+impl ActionHandler for EventQueue {
+    fn do_action(&mut self, request: ActionRequest) {
+        self.push(QueuedEvent::ActionRequested(request));
+    }
+}
+
+impl DeactivationHandler for EventQueue {
+    fn deactivate_accessibility(&mut self) {
+        self.push(QueuedEvent::Deactivated);
+    }
+}
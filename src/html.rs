@@ -0,0 +1,310 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{Action, Affine, Node, NodeId, Point, Role, Toggled};
+use std::{
+    ffi::{c_char, CString},
+    fmt::Write,
+};
+
+use crate::{
+    aria_role, format_number, is_range_role, is_text_role, ref_from_ptr, role_name, tree_snapshot,
+    uses_aria_checked, variant_name, AriaRole, TreeSnapshot,
+};
+
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\0' => escaped.push('\u{fffd}'),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn id_refs(ids: &[NodeId]) -> String {
+    ids.iter()
+        .map(|id| format!("n{}", id.0))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn flag(attributes: &mut Vec<(&'static str, String)>, name: &'static str, set: bool) {
+    if set {
+        attributes.push((name, "true".into()));
+    }
+}
+
+fn attributes(
+    snapshot: &TreeSnapshot,
+    id: NodeId,
+    node: &Node,
+    position: Option<(Point, Point)>,
+) -> Vec<(&'static str, String)> {
+    let mut attributes = vec![("id", format!("n{}", id.0))];
+    let role = node.role();
+    match aria_role(role) {
+        AriaRole::Exact(aria) => attributes.push(("role", aria.into())),
+        AriaRole::Approximate(aria) => {
+            attributes.push(("role", aria.into()));
+            attributes.push(("data-accesskit-role", role_name(role).into()));
+        }
+        AriaRole::NoEquivalent => attributes.push(("data-accesskit-role", role_name(role).into())),
+    }
+
+    for (name, value) in [
+        ("aria-label", node.label()),
+        ("aria-description", node.description()),
+        ("aria-roledescription", node.role_description()),
+        ("aria-placeholder", node.placeholder()),
+        ("title", node.tooltip()),
+        ("aria-keyshortcuts", node.keyboard_shortcut()),
+        ("lang", node.language()),
+    ] {
+        if let Some(value) = value {
+            attributes.push((name, value.into()));
+        }
+    }
+    if let Some(value) = node.value() {
+        if is_range_role(role) {
+            attributes.push(("aria-valuetext", value.into()));
+        } else if !is_text_role(role) {
+            attributes.push(("data-accesskit-value", value.into()));
+        }
+    }
+    for (name, value) in [
+        ("aria-valuenow", node.numeric_value()),
+        ("aria-valuemin", node.min_numeric_value()),
+        ("aria-valuemax", node.max_numeric_value()),
+    ] {
+        if let Some(value) = value {
+            attributes.push((name, format_number(value)));
+        }
+    }
+    for (name, value) in [
+        ("aria-level", node.level()),
+        ("aria-posinset", node.position_in_set()),
+        ("aria-setsize", node.size_of_set()),
+        ("aria-rowindex", node.row_index().map(|index| index + 1)),
+        ("aria-colindex", node.column_index().map(|index| index + 1)),
+        ("aria-rowcount", node.row_count()),
+        ("aria-colcount", node.column_count()),
+        ("aria-rowspan", node.row_span()),
+        ("aria-colspan", node.column_span()),
+    ] {
+        if let Some(value) = value {
+            attributes.push((name, value.to_string()));
+        }
+    }
+
+    if let Some(toggled) = node.toggled() {
+        let name = if uses_aria_checked(role) {
+            "aria-checked"
+        } else {
+            "aria-pressed"
+        };
+        let value = match toggled {
+            Toggled::False => "false",
+            Toggled::True => "true",
+            Toggled::Mixed => "mixed",
+        };
+        attributes.push((name, value.into()));
+    }
+    for (name, value) in [
+        ("aria-expanded", node.is_expanded()),
+        ("aria-selected", node.is_selected()),
+    ] {
+        if let Some(value) = value {
+            attributes.push((name, value.to_string()));
+        }
+    }
+    for (name, value) in [
+        ("aria-current", node.aria_current().map(variant_name)),
+        ("aria-invalid", node.invalid().map(variant_name)),
+        ("aria-haspopup", node.has_popup().map(variant_name)),
+        ("aria-autocomplete", node.auto_complete().map(variant_name)),
+        ("aria-orientation", node.orientation().map(variant_name)),
+        ("aria-sort", node.sort_direction().map(variant_name)),
+        ("aria-live", node.live().map(variant_name)),
+    ] {
+        if let Some(value) = value {
+            attributes.push((name, value.into()));
+        }
+    }
+    flag(&mut attributes, "aria-atomic", node.is_live_atomic());
+    flag(&mut attributes, "aria-busy", node.is_busy());
+    flag(&mut attributes, "aria-disabled", node.is_disabled());
+    flag(&mut attributes, "aria-hidden", node.is_hidden());
+    flag(&mut attributes, "aria-required", node.is_required());
+    flag(&mut attributes, "aria-readonly", node.is_read_only());
+    flag(
+        &mut attributes,
+        "aria-multiselectable",
+        node.is_multiselectable(),
+    );
+    flag(&mut attributes, "aria-modal", node.is_modal());
+    flag(
+        &mut attributes,
+        "aria-multiline",
+        role == Role::MultilineTextInput,
+    );
+
+    for (name, ids) in [
+        ("aria-labelledby", node.labelled_by()),
+        ("aria-describedby", node.described_by()),
+        ("aria-controls", node.controls()),
+        ("aria-details", node.details()),
+        ("aria-flowto", node.flow_to()),
+        ("aria-owns", node.owns()),
+    ] {
+        if !ids.is_empty() {
+            attributes.push((name, id_refs(ids)));
+        }
+    }
+    for (name, target) in [
+        ("aria-activedescendant", node.active_descendant()),
+        ("aria-errormessage", node.error_message()),
+    ] {
+        if let Some(target) = target {
+            attributes.push((name, id_refs(&[target])));
+        }
+    }
+
+    if node.supports_action(Action::Focus) {
+        attributes.push(("tabindex", "0".into()));
+    }
+    if snapshot.focus_id() == id {
+        attributes.push(("autofocus", String::new()));
+    }
+    if let Some((origin, corner)) = position {
+        attributes.push((
+            "style",
+            format!(
+                "position: absolute; left: {}px; top: {}px; width: {}px; height: {}px",
+                format_number(origin.x),
+                format_number(origin.y),
+                format_number(corner.x - origin.x),
+                format_number(corner.y - origin.y)
+            ),
+        ));
+    }
+    attributes
+}
+
+struct HtmlWriter<'a> {
+    snapshot: &'a TreeSnapshot,
+    output: String,
+}
+
+impl HtmlWriter<'_> {
+    fn indent(&mut self, depth: usize) {
+        for _ in 0..depth {
+            self.output.push_str("  ");
+        }
+    }
+
+    /// `origin` is the top-left corner of the nearest ancestor that was
+    /// given a position, in window coordinates.
+    fn write_node(&mut self, id: NodeId, depth: usize, transform: Affine, origin: Point) {
+        let node = self.snapshot.node(id).unwrap();
+        let transform = match node.transform() {
+            Some(node_transform) => transform * *node_transform,
+            None => transform,
+        };
+        let bounds = node
+            .bounds()
+            .map(|bounds| transform.transform_rect_bbox(bounds));
+        let position = bounds.map(|bounds| {
+            (
+                Point::new(bounds.x0 - origin.x, bounds.y0 - origin.y),
+                Point::new(bounds.x1 - origin.x, bounds.y1 - origin.y),
+            )
+        });
+        let child_origin = bounds.map_or(origin, |bounds| bounds.origin());
+
+        self.indent(depth);
+        self.output.push_str("<div");
+        for (name, value) in attributes(self.snapshot, id, node, position) {
+            if value.is_empty() {
+                write!(self.output, " {}", name).unwrap();
+            } else {
+                write!(self.output, " {}=\"{}\"", name, escape_html(&value)).unwrap();
+            }
+        }
+        self.output.push_str(">\n");
+        if let Some(value) = node.value().filter(|_| is_text_role(node.role())) {
+            self.indent(depth + 1);
+            self.output.push_str(&escape_html(value));
+            self.output.push('\n');
+        }
+        let children: Vec<NodeId> = self.snapshot.child_ids(id).collect();
+        for child in children {
+            self.write_node(child, depth + 1, transform, child_origin);
+        }
+        self.indent(depth);
+        self.output.push_str("</div>\n");
+    }
+}
+
+pub(crate) fn to_html(snapshot: &TreeSnapshot) -> String {
+    let title = snapshot
+        .name(snapshot.root_id())
+        .unwrap_or_else(|| "AccessKit tree".into());
+    let mut writer = HtmlWriter {
+        snapshot,
+        output: String::new(),
+    };
+    writer.output.push_str("<!DOCTYPE html>\n<html>\n<head>\n");
+    writer.output.push_str("<meta charset=\"utf-8\">\n");
+    writeln!(writer.output, "<title>{}</title>", escape_html(&title)).unwrap();
+    writer.output.push_str(
+        "<style>\nbody { margin: 0; }\n\
+         [style] { box-sizing: border-box; outline: 1px solid rgba(0, 0, 255, 0.4); }\n\
+         </style>\n",
+    );
+    writer.output.push_str("</head>\n<body>\n");
+    writer.write_node(snapshot.root_id(), 0, Affine::IDENTITY, Point::ZERO);
+    writer.output.push_str("</body>\n</html>\n");
+    writer.output
+}
+
+impl tree_snapshot {
+    /// Renders the tree as a static HTML document that browser-based
+    /// accessibility tools can inspect. Each node becomes a `div` whose
+    /// `id` is `n` followed by the node ID, and relations refer to nodes
+    /// by these IDs.
+    ///
+    /// Roles with an ARIA equivalent are emitted as the `role` attribute.
+    /// Other roles are approximated by the closest ARIA role, if any, and
+    /// kept in a `data-accesskit-role` attribute holding the role name
+    /// in snake case, such as `default_button`.
+    ///
+    /// Properties map to the corresponding ARIA attributes: `label` to
+    /// `aria-label`, `description` to `aria-description`, `tooltip` to
+    /// `title`, `toggled` to `aria-checked` (or `aria-pressed` for roles
+    /// that can't be checked), and `invalid`, `live`, `has_popup`,
+    /// `aria_current`, `auto_complete`, `orientation` and `sort_direction`
+    /// to the attribute of the same meaning, with the lowercase variant
+    /// name as value. Row and column indices become 1-based. Boolean
+    /// states are emitted when set, as `"true"`. The string value becomes
+    /// the text content of labels and text inputs, `aria-valuetext` for
+    /// range roles such as sliders, and `data-accesskit-value` otherwise.
+    /// Focusable nodes get `tabindex="0"`, and the focused node `autofocus`.
+    ///
+    /// Nodes with bounds are absolute-positioned boxes, relative to their
+    /// nearest positioned ancestor, with transforms applied.
+    ///
+    /// Caller must call `accesskit_string_free` with the return value.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_to_html(snapshot: *const tree_snapshot) -> *mut c_char {
+        let snapshot = ref_from_ptr(snapshot);
+        CString::new(to_html(snapshot)).unwrap().into_raw()
+    }
+}
@@ -0,0 +1,910 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{
+    Action, AriaCurrent, AutoComplete, HasPopup, Invalid, Live, Node, NodeId, Orientation, Point,
+    Rect, Role, SortDirection, Toggled, Tree, TreeId, TreeUpdate,
+};
+use std::{
+    collections::HashMap,
+    ffi::{c_char, CStr, CString},
+    ptr,
+};
+
+use crate::{
+    box_from_ptr, is_range_role, is_text_role, mut_from_ptr, opt_node_id, ref_from_ptr,
+    role_from_aria, role_from_name, string_from_c_slice, tree_update, BoxCastPtr, CastPtr,
+};
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements whose content is skipped.
+const SKIPPED_ELEMENTS: &[&str] = &["head", "script", "style", "template", "title"];
+
+/// Elements that don't produce a node, but whose content does.
+const TRANSPARENT_ELEMENTS: &[&str] = &["html", "body"];
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct HtmlError {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+    pub(crate) message: String,
+}
+
+struct Attribute {
+    name: String,
+    value: String,
+    position: usize,
+}
+
+struct Reference {
+    source: NodeId,
+    attribute: String,
+    ids: Vec<String>,
+    position: usize,
+}
+
+struct OpenElement {
+    tag: String,
+    id: NodeId,
+    children: Vec<NodeId>,
+    text: Vec<String>,
+    origin: Point,
+}
+
+fn role_from_tag(tag: &str, attributes: &[Attribute]) -> Role {
+    let input_type = || {
+        attributes
+            .iter()
+            .find(|attribute| attribute.name == "type")
+            .map(|attribute| attribute.value.to_ascii_lowercase())
+    };
+    match tag {
+        "a" => Role::Link,
+        "article" => Role::Article,
+        "aside" => Role::Complementary,
+        "button" => Role::Button,
+        "dialog" => Role::Dialog,
+        "fieldset" => Role::Group,
+        "footer" => Role::Footer,
+        "form" => Role::Form,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => Role::Heading,
+        "header" => Role::Header,
+        "img" => Role::Image,
+        "input" => match input_type().as_deref() {
+            Some("checkbox") => Role::CheckBox,
+            Some("radio") => Role::RadioButton,
+            Some("range") => Role::Slider,
+            Some("number") => Role::NumberInput,
+            Some("search") => Role::SearchInput,
+            Some("email") => Role::EmailInput,
+            Some("password") => Role::PasswordInput,
+            Some("tel") => Role::PhoneNumberInput,
+            Some("url") => Role::UrlInput,
+            Some("button") | Some("submit") | Some("reset") => Role::Button,
+            _ => Role::TextInput,
+        },
+        "label" => Role::Label,
+        "legend" => Role::Legend,
+        "li" => Role::ListItem,
+        "main" => Role::Main,
+        "meter" => Role::Meter,
+        "nav" => Role::Navigation,
+        "ol" | "ul" => Role::List,
+        "option" => Role::ListBoxOption,
+        "p" => Role::Paragraph,
+        "progress" => Role::ProgressIndicator,
+        "section" => Role::Section,
+        "select" => Role::ComboBox,
+        "table" => Role::Table,
+        "td" => Role::Cell,
+        "textarea" => Role::MultilineTextInput,
+        "th" => Role::ColumnHeader,
+        "tr" => Role::Row,
+        _ => Role::GenericContainer,
+    }
+}
+
+fn is_natively_focusable(tag: &str) -> bool {
+    matches!(tag, "a" | "button" | "input" | "select" | "textarea")
+}
+
+fn parse_toggled(value: &str) -> Option<Toggled> {
+    match value {
+        "true" => Some(Toggled::True),
+        "false" => Some(Toggled::False),
+        "mixed" => Some(Toggled::Mixed),
+        _ => None,
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parses a CSS length in pixels, as in `12px` or `12`.
+fn parse_pixels(value: &str) -> Option<f64> {
+    let value = value.trim();
+    value
+        .strip_suffix("px")
+        .unwrap_or(value)
+        .trim()
+        .parse()
+        .ok()
+}
+
+struct HtmlParser<'a> {
+    input: &'a str,
+    position: usize,
+    nodes: Vec<Node>,
+    ids: HashMap<String, NodeId>,
+    references: Vec<Reference>,
+    focus: Option<NodeId>,
+    stack: Vec<OpenElement>,
+    root: Option<NodeId>,
+}
+
+fn error_at<T>(input: &str, position: usize, message: impl Into<String>) -> Result<T, HtmlError> {
+    let before = &input[..position];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    Err(HtmlError {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+        message: message.into(),
+    })
+}
+
+/// Escapes the control characters of a value quoted in an error message.
+fn escaped(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_control() {
+                c.escape_default().collect()
+            } else {
+                c.to_string()
+            }
+        })
+        .collect()
+}
+
+impl<'a> HtmlParser<'a> {
+    fn error_at<T>(&self, position: usize, message: impl Into<String>) -> Result<T, HtmlError> {
+        error_at(self.input, position, message)
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, HtmlError> {
+        self.error_at(self.position, message)
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn eat(&mut self, expected: &str) -> bool {
+        if self.rest().starts_with(expected) {
+            self.position += expected.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.position += c.len_utf8();
+        }
+    }
+
+    /// Moves past the next occurrence of `end`, compared case-insensitively.
+    fn skip_past(&mut self, end: &str, what: &str) -> Result<(), HtmlError> {
+        let start = self.position;
+        match self.rest().to_ascii_lowercase().find(end) {
+            Some(index) => {
+                self.position += index + end.len();
+                Ok(())
+            }
+            None => self.error_at(start, format!("unterminated {}", what)),
+        }
+    }
+
+    fn name(&mut self) -> Result<String, HtmlError> {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == ':')
+        {
+            self.position += 1;
+        }
+        if start == self.position {
+            return self.error("expected a name");
+        }
+        Ok(self.input[start..self.position].to_ascii_lowercase())
+    }
+
+    fn decode(&self, text: &str, start: usize) -> Result<String, HtmlError> {
+        if let Some(index) = text.find('\0') {
+            return self.error_at(start + index, "unexpected NUL character");
+        }
+        let mut decoded = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(index) = rest.find('&') {
+            decoded.push_str(&rest[..index]);
+            let position = start + (text.len() - rest.len()) + index;
+            let entity = &rest[index + 1..];
+            let Some(end) = entity.find(';').filter(|end| *end <= 10) else {
+                return self.error_at(position, "expected ';' after character reference");
+            };
+            let name = &entity[..end];
+            let c = match name {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => {
+                    if let Some(hex) = name.strip_prefix("#x").or(name.strip_prefix("#X")) {
+                        u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+                    } else if let Some(decimal) = name.strip_prefix('#') {
+                        decimal.parse().ok().and_then(char::from_u32)
+                    } else {
+                        None
+                    }
+                }
+            };
+            if c == Some('\0') {
+                return self.error_at(position, "invalid NUL character reference");
+            }
+            match c {
+                Some(c) => decoded.push(c),
+                None => {
+                    return self.error_at(
+                        position,
+                        format!("unknown character reference '&{};'", escaped(name)),
+                    )
+                }
+            }
+            rest = &entity[end + 1..];
+        }
+        decoded.push_str(rest);
+        Ok(decoded)
+    }
+
+    fn attribute_value(&mut self) -> Result<(String, usize), HtmlError> {
+        if let Some(quote) = self.peek().filter(|c| *c == '"' || *c == '\'') {
+            self.position += 1;
+            let start = self.position;
+            let Some(length) = self.rest().find(quote) else {
+                return self.error_at(start - 1, "unterminated attribute value");
+            };
+            self.position += length + 1;
+            let value = self.decode(&self.input[start..start + length], start)?;
+            return Ok((value, start));
+        }
+        let start = self.position;
+        while let Some(c) = self
+            .peek()
+            .filter(|c| !c.is_whitespace() && !"\"'<>=`".contains(*c))
+        {
+            self.position += c.len_utf8();
+        }
+        if start == self.position {
+            return self.error("expected an attribute value");
+        }
+        let value = self.decode(&self.input[start..self.position], start)?;
+        Ok((value, start))
+    }
+
+    /// Parses the attributes of a start tag, and returns whether the tag
+    /// is self-closing.
+    fn attributes(&mut self) -> Result<(Vec<Attribute>, bool), HtmlError> {
+        let mut attributes: Vec<Attribute> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat("/>") {
+                return Ok((attributes, true));
+            }
+            if self.eat(">") {
+                return Ok((attributes, false));
+            }
+            if self.peek().is_none() {
+                return self.error("unterminated start tag");
+            }
+            let start = self.position;
+            let name = self.name()?;
+            if attributes.iter().any(|attribute| attribute.name == name) {
+                return self.error_at(start, format!("duplicate attribute '{}'", name));
+            }
+            self.skip_whitespace();
+            let (value, position) = if self.eat("=") {
+                self.skip_whitespace();
+                self.attribute_value()?
+            } else {
+                (String::new(), start)
+            };
+            attributes.push(Attribute {
+                name,
+                value,
+                position,
+            });
+        }
+    }
+
+    fn add_child(&mut self, id: NodeId, position: usize) -> Result<(), HtmlError> {
+        match self.stack.last_mut() {
+            Some(parent) => parent.children.push(id),
+            None if self.root.is_none() => self.root = Some(id),
+            None => return self.error_at(position, "expected a single root element"),
+        }
+        Ok(())
+    }
+
+    fn apply_attribute(
+        &mut self,
+        id: NodeId,
+        tag: &str,
+        attribute: &Attribute,
+        origin: Point,
+    ) -> Result<Option<Point>, HtmlError> {
+        let input = self.input;
+        let node = &mut self.nodes[id.0 as usize];
+        let value = attribute.value.as_str();
+        let position = attribute.position;
+        let invalid = || {
+            error_at(
+                input,
+                position,
+                format!(
+                    "invalid value '{}' for '{}'",
+                    escaped(value),
+                    attribute.name
+                ),
+            )
+        };
+        macro_rules! parse {
+            ($parser:expr) => {
+                match $parser {
+                    Some(value) => value,
+                    None => return invalid(),
+                }
+            };
+        }
+        match attribute.name.as_str() {
+            "id" if self.ids.insert(value.to_string(), id).is_some() => {
+                return self.error_at(position, format!("duplicate id '{}'", escaped(value)));
+            }
+            "aria-label" => node.set_label(value),
+            "aria-description" => node.set_description(value),
+            "aria-roledescription" => node.set_role_description(value),
+            "aria-placeholder" | "placeholder" => node.set_placeholder(value),
+            "title" => node.set_tooltip(value),
+            "aria-keyshortcuts" => node.set_keyboard_shortcut(value),
+            "lang" => node.set_language(value),
+            "aria-valuetext" | "data-accesskit-value" => node.set_value(value),
+            "value" if tag == "input" => node.set_value(value),
+            "aria-valuenow" => node.set_numeric_value(parse!(value.parse().ok())),
+            "aria-valuemin" => node.set_min_numeric_value(parse!(value.parse().ok())),
+            "aria-valuemax" => node.set_max_numeric_value(parse!(value.parse().ok())),
+            "aria-level" => node.set_level(parse!(value.parse().ok())),
+            "aria-posinset" => node.set_position_in_set(parse!(value.parse().ok())),
+            "aria-setsize" => node.set_size_of_set(parse!(value.parse().ok())),
+            "aria-rowindex" => node.set_row_index(parse!(value
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| i.checked_sub(1)))),
+            "aria-colindex" => node.set_column_index(parse!(value
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| i.checked_sub(1)))),
+            "aria-rowcount" => node.set_row_count(parse!(value.parse().ok())),
+            "aria-colcount" => node.set_column_count(parse!(value.parse().ok())),
+            "aria-rowspan" => node.set_row_span(parse!(value.parse().ok())),
+            "aria-colspan" => node.set_column_span(parse!(value.parse().ok())),
+            "aria-checked" | "aria-pressed" => node.set_toggled(parse!(parse_toggled(value))),
+            "checked" => node.set_toggled(Toggled::True),
+            "aria-expanded" => node.set_expanded(parse!(parse_bool(value))),
+            "aria-selected" => node.set_selected(parse!(parse_bool(value))),
+            "aria-current" => node.set_aria_current(parse!(match value {
+                "false" => Some(AriaCurrent::False),
+                "true" => Some(AriaCurrent::True),
+                "page" => Some(AriaCurrent::Page),
+                "step" => Some(AriaCurrent::Step),
+                "location" => Some(AriaCurrent::Location),
+                "date" => Some(AriaCurrent::Date),
+                "time" => Some(AriaCurrent::Time),
+                _ => None,
+            })),
+            "aria-invalid" => match value {
+                "false" => {}
+                "true" => node.set_invalid(Invalid::True),
+                "grammar" => node.set_invalid(Invalid::Grammar),
+                "spelling" => node.set_invalid(Invalid::Spelling),
+                _ => return invalid(),
+            },
+            "aria-haspopup" => match value {
+                "false" => {}
+                "true" | "menu" => node.set_has_popup(HasPopup::Menu),
+                "listbox" => node.set_has_popup(HasPopup::Listbox),
+                "tree" => node.set_has_popup(HasPopup::Tree),
+                "grid" => node.set_has_popup(HasPopup::Grid),
+                "dialog" => node.set_has_popup(HasPopup::Dialog),
+                _ => return invalid(),
+            },
+            "aria-autocomplete" => match value {
+                "none" => {}
+                "inline" => node.set_auto_complete(AutoComplete::Inline),
+                "list" => node.set_auto_complete(AutoComplete::List),
+                "both" => node.set_auto_complete(AutoComplete::Both),
+                _ => return invalid(),
+            },
+            "aria-orientation" => node.set_orientation(parse!(match value {
+                "horizontal" => Some(Orientation::Horizontal),
+                "vertical" => Some(Orientation::Vertical),
+                _ => None,
+            })),
+            "aria-sort" => match value {
+                "none" => {}
+                "ascending" => node.set_sort_direction(SortDirection::Ascending),
+                "descending" => node.set_sort_direction(SortDirection::Descending),
+                "other" => node.set_sort_direction(SortDirection::Other),
+                _ => return invalid(),
+            },
+            "aria-live" => node.set_live(parse!(match value {
+                "off" => Some(Live::Off),
+                "polite" => Some(Live::Polite),
+                "assertive" => Some(Live::Assertive),
+                _ => None,
+            })),
+            "aria-atomic"
+            | "aria-busy"
+            | "aria-disabled"
+            | "aria-hidden"
+            | "aria-required"
+            | "aria-readonly"
+            | "aria-multiselectable"
+            | "aria-modal" => {
+                if parse!(parse_bool(value)) {
+                    match attribute.name.as_str() {
+                        "aria-atomic" => node.set_live_atomic(),
+                        "aria-busy" => node.set_busy(),
+                        "aria-disabled" => node.set_disabled(),
+                        "aria-hidden" => node.set_hidden(),
+                        "aria-required" => node.set_required(),
+                        "aria-readonly" => node.set_read_only(),
+                        "aria-multiselectable" => node.set_multiselectable(),
+                        _ => node.set_modal(),
+                    }
+                }
+            }
+            "disabled" => node.set_disabled(),
+            "hidden" => node.set_hidden(),
+            "required" => node.set_required(),
+            "readonly" => node.set_read_only(),
+            "tabindex" => {
+                parse!(value.parse::<i32>().ok());
+                node.add_action(Action::Focus);
+            }
+            "autofocus" => self.focus = Some(id),
+            "aria-labelledby"
+            | "aria-describedby"
+            | "aria-controls"
+            | "aria-details"
+            | "aria-flowto"
+            | "aria-owns"
+            | "aria-activedescendant"
+            | "aria-errormessage" => {
+                let ids: Vec<String> = value.split_whitespace().map(String::from).collect();
+                if ids.is_empty()
+                    || (ids.len() > 1
+                        && matches!(
+                            attribute.name.as_str(),
+                            "aria-activedescendant" | "aria-errormessage"
+                        ))
+                {
+                    return invalid();
+                }
+                self.references.push(Reference {
+                    source: id,
+                    attribute: attribute.name.clone(),
+                    ids,
+                    position,
+                });
+            }
+            "style" => {
+                let mut rect = (None, None, None, None);
+                for declaration in value.split(';') {
+                    let Some((property, length)) = declaration.split_once(':') else {
+                        continue;
+                    };
+                    let slot = match property.trim() {
+                        "left" => &mut rect.0,
+                        "top" => &mut rect.1,
+                        "width" => &mut rect.2,
+                        "height" => &mut rect.3,
+                        _ => continue,
+                    };
+                    *slot = Some(parse!(parse_pixels(length)));
+                }
+                if let (left, top, Some(width), Some(height)) = rect {
+                    let x0 = origin.x + left.unwrap_or(0.0);
+                    let y0 = origin.y + top.unwrap_or(0.0);
+                    node.set_bounds(Rect::new(x0, y0, x0 + width, y0 + height));
+                    return Ok(Some(Point::new(x0, y0)));
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn start_element(&mut self, start: usize) -> Result<(), HtmlError> {
+        let tag = self.name()?;
+        let (attributes, self_closing) = self.attributes()?;
+        if SKIPPED_ELEMENTS.contains(&tag.as_str()) {
+            if !self_closing {
+                self.skip_past(&format!("</{}", tag), &format!("<{}> element", tag))?;
+                self.skip_past(">", "end tag")?;
+            }
+            return Ok(());
+        }
+        if TRANSPARENT_ELEMENTS.contains(&tag.as_str()) {
+            return Ok(());
+        }
+
+        let mut role = role_from_tag(&tag, &attributes);
+        if let Some(attribute) = attributes.iter().find(|a| a.name == "role") {
+            let aria = attribute.value.split_whitespace().next().unwrap_or("");
+            role = match role_from_aria(aria) {
+                Some(role) => role,
+                None => {
+                    return self.error_at(
+                        attribute.position,
+                        format!("unknown role '{}'", escaped(&attribute.value)),
+                    )
+                }
+            };
+            if role == Role::TextInput
+                && attributes
+                    .iter()
+                    .any(|a| a.name == "aria-multiline" && a.value == "true")
+            {
+                role = Role::MultilineTextInput;
+            }
+        }
+        if let Some(attribute) = attributes.iter().find(|a| a.name == "data-accesskit-role") {
+            role = match role_from_name(&attribute.value) {
+                Some(role) => role,
+                None => {
+                    return self.error_at(
+                        attribute.position,
+                        format!("unknown role '{}'", escaped(&attribute.value)),
+                    )
+                }
+            };
+        }
+
+        let id = NodeId(self.nodes.len() as u64);
+        let mut node = Node::new(role);
+        if is_natively_focusable(&tag) {
+            node.add_action(Action::Focus);
+        }
+        if role_from_tag(&tag, &attributes) == Role::Heading {
+            node.set_level(tag[1..].parse().unwrap());
+        }
+        self.nodes.push(node);
+        self.add_child(id, start)?;
+        let parent_origin = self
+            .stack
+            .last()
+            .map_or(Point::ZERO, |parent| parent.origin);
+        let mut origin = parent_origin;
+        for attribute in &attributes {
+            if let Some(node_origin) = self.apply_attribute(id, &tag, attribute, parent_origin)? {
+                origin = node_origin;
+            }
+        }
+
+        let element = OpenElement {
+            tag,
+            id,
+            children: Vec::new(),
+            text: Vec::new(),
+            origin,
+        };
+        if self_closing || VOID_ELEMENTS.contains(&element.tag.as_str()) {
+            self.finish_element(element);
+        } else {
+            self.stack.push(element);
+        }
+        Ok(())
+    }
+
+    fn finish_element(&mut self, element: OpenElement) {
+        let node = &mut self.nodes[element.id.0 as usize];
+        if !element.children.is_empty() {
+            node.set_children(element.children);
+        }
+        if !element.text.is_empty() {
+            node.set_value(element.text.join(" "));
+        }
+    }
+
+    fn end_element(&mut self, start: usize) -> Result<(), HtmlError> {
+        let tag = self.name()?;
+        self.skip_whitespace();
+        if !self.eat(">") {
+            return self.error("expected '>'");
+        }
+        if TRANSPARENT_ELEMENTS.contains(&tag.as_str()) {
+            return Ok(());
+        }
+        match self.stack.pop() {
+            Some(element) if element.tag == tag => {
+                self.finish_element(element);
+                Ok(())
+            }
+            Some(element) => self.error_at(
+                start,
+                format!("unexpected </{}>, expected </{}>", tag, element.tag),
+            ),
+            None => self.error_at(start, format!("unexpected </{}>", tag)),
+        }
+    }
+
+    fn text(&mut self) -> Result<(), HtmlError> {
+        let start = self.position;
+        let length = self.rest().find('<').unwrap_or(self.rest().len());
+        self.position += length;
+        let text = self.decode(&self.input[start..self.position], start)?;
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.is_empty() {
+            return Ok(());
+        }
+        let Some(parent) = self.stack.last() else {
+            return self.error_at(start, "text outside of an element");
+        };
+        let parent_role = self.nodes[parent.id.0 as usize].role();
+        if is_text_role(parent_role) && !is_range_role(parent_role) {
+            self.stack.last_mut().unwrap().text.push(text);
+        } else {
+            let id = NodeId(self.nodes.len() as u64);
+            let mut node = Node::new(Role::Label);
+            node.set_value(text);
+            self.nodes.push(node);
+            self.stack.last_mut().unwrap().children.push(id);
+        }
+        Ok(())
+    }
+
+    fn resolve_references(&mut self) -> Result<(), HtmlError> {
+        for reference in std::mem::take(&mut self.references) {
+            let mut targets = Vec::new();
+            for id in &reference.ids {
+                match self.ids.get(id) {
+                    Some(target) => targets.push(*target),
+                    None => {
+                        return self.error_at(
+                            reference.position,
+                            format!(
+                                "'{}' refers to unknown id '{}'",
+                                reference.attribute,
+                                escaped(id)
+                            ),
+                        )
+                    }
+                }
+            }
+            let node = &mut self.nodes[reference.source.0 as usize];
+            match reference.attribute.as_str() {
+                "aria-labelledby" => node.set_labelled_by(targets),
+                "aria-describedby" => node.set_described_by(targets),
+                "aria-controls" => node.set_controls(targets),
+                "aria-details" => node.set_details(targets),
+                "aria-flowto" => node.set_flow_to(targets),
+                "aria-owns" => node.set_owns(targets),
+                "aria-activedescendant" => node.set_active_descendant(targets[0]),
+                _ => node.set_error_message(targets[0]),
+            }
+        }
+        Ok(())
+    }
+
+    fn parse(mut self) -> Result<(TreeUpdate, HashMap<String, NodeId>), HtmlError> {
+        loop {
+            let start = self.position;
+            if self.peek().is_none() {
+                if let Some(element) = self.stack.last() {
+                    return self.error(format!("expected </{}>", element.tag));
+                }
+                break;
+            }
+            if self.eat("<!--") {
+                self.skip_past("-->", "comment")?;
+            } else if self.eat("<!") || self.eat("<?") {
+                self.skip_past(">", "declaration")?;
+            } else if self.eat("</") {
+                self.end_element(start)?;
+            } else if self.eat("<") {
+                self.start_element(start)?;
+            } else {
+                self.text()?;
+            }
+        }
+        let Some(root) = self.root else {
+            return self.error("expected a root element");
+        };
+        self.resolve_references()?;
+        let update = TreeUpdate {
+            nodes: self
+                .nodes
+                .into_iter()
+                .enumerate()
+                .map(|(index, node)| (NodeId(index as u64), node))
+                .collect(),
+            tree: Some(Tree::new(root)),
+            tree_id: TreeId::ROOT,
+            focus: self.focus.unwrap_or(root),
+        };
+        Ok((update, self.ids))
+    }
+}
+
+pub(crate) fn parse_html(input: &str) -> Result<(TreeUpdate, HashMap<String, NodeId>), HtmlError> {
+    HtmlParser {
+        input,
+        position: 0,
+        nodes: Vec::new(),
+        ids: HashMap::new(),
+        references: Vec::new(),
+        focus: None,
+        stack: Vec::new(),
+        root: None,
+    }
+    .parse()
+}
+
+pub(crate) struct HtmlImport {
+    update: Option<TreeUpdate>,
+    ids: HashMap<String, NodeId>,
+    error: Option<HtmlError>,
+}
+
+pub struct html_import {
+    _private: [u8; 0],
+}
+
+impl CastPtr for html_import {
+    type RustType = HtmlImport;
+}
+
+impl BoxCastPtr for html_import {}
+
+impl html_import {
+    /// Builds a full tree update from an HTML fragment or document, such
+    /// as the output of `accesskit_tree_to_html`.
+    ///
+    /// Each element becomes a node, with IDs assigned from 0 in document
+    /// order. The role comes from the `data-accesskit-role` attribute,
+    /// the `role` attribute or the tag name, in that order. ARIA attributes
+    /// are mapped back to properties as described in
+    /// `accesskit_tree_to_html`, and references to other elements through
+    /// `aria-labelledby`, `aria-controls` and the like are resolved using
+    /// their `id` attribute. The text content of labels and text inputs
+    /// becomes their value; elsewhere, each run of text becomes a child
+    /// node with the `ACCESSKIT_ROLE_LABEL` role. Elements with the
+    /// `tabindex` attribute are focusable, and the element with the
+    /// `autofocus` attribute is focused; the root is focused otherwise.
+    /// The content of `head`, `script` and `style` elements is ignored.
+    ///
+    /// Never returns null. Check `accesskit_html_import_error` to find out
+    /// whether parsing succeeded.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_from_html(
+        html: *const c_char,
+        length: usize,
+    ) -> *mut html_import {
+        let html = unsafe { string_from_c_slice(html, length) };
+        let import = match parse_html(&html) {
+            Ok((update, ids)) => HtmlImport {
+                update: Some(update),
+                ids,
+                error: None,
+            },
+            Err(error) => HtmlImport {
+                update: None,
+                ids: HashMap::new(),
+                error: Some(error),
+            },
+        };
+        BoxCastPtr::to_mut_ptr(import)
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_html_import_free(import: *mut html_import) {
+        drop(box_from_ptr(import));
+    }
+
+    /// Returns null if parsing succeeded, otherwise a description
+    /// of the error, including its line and column.
+    ///
+    /// Caller must call `accesskit_string_free` with the return value.
+    #[no_mangle]
+    pub extern "C" fn accesskit_html_import_error(import: *const html_import) -> *mut c_char {
+        let import = ref_from_ptr(import);
+        match &import.error {
+            Some(error) => {
+                let message = format!(
+                    "line {}, column {}: {}",
+                    error.line, error.column, error.message
+                )
+                .replace('\0', "\\0");
+                CString::new(message).unwrap_or_default().into_raw()
+            }
+            None => ptr::null_mut(),
+        }
+    }
+
+    /// Returns the 1-based line of the parse error, or 0 if parsing succeeded.
+    #[no_mangle]
+    pub extern "C" fn accesskit_html_import_error_line(import: *const html_import) -> usize {
+        let import = ref_from_ptr(import);
+        import.error.as_ref().map_or(0, |error| error.line)
+    }
+
+    /// Returns the 1-based column of the parse error, counted in characters,
+    /// or 0 if parsing succeeded.
+    #[no_mangle]
+    pub extern "C" fn accesskit_html_import_error_column(import: *const html_import) -> usize {
+        let import = ref_from_ptr(import);
+        import.error.as_ref().map_or(0, |error| error.column)
+    }
+
+    /// Returns null if parsing failed or if the tree update was already taken.
+    /// Ownership of the returned value is transferred to the caller.
+    #[no_mangle]
+    pub extern "C" fn accesskit_html_import_take_tree_update(
+        import: *mut html_import,
+    ) -> *mut tree_update {
+        let import = mut_from_ptr(import);
+        BoxCastPtr::to_nullable_mut_ptr(import.update.take())
+    }
+
+    /// Returns the ID of the node created for the element with the given
+    /// `id` attribute.
+    #[no_mangle]
+    pub extern "C" fn accesskit_html_import_node_id(
+        import: *const html_import,
+        id: *const c_char,
+    ) -> opt_node_id {
+        let import = ref_from_ptr(import);
+        let id = unsafe { CStr::from_ptr(id) };
+        import.ids.get(&*id.to_string_lossy()).copied().into()
+    }
+
+    /// Returns the ID of the node created for the element with the given
+    /// `id` attribute.
+    #[no_mangle]
+    pub extern "C" fn accesskit_html_import_node_id_with_length(
+        import: *const html_import,
+        id: *const c_char,
+        length: usize,
+    ) -> opt_node_id {
+        let import = ref_from_ptr(import);
+        let id = unsafe { string_from_c_slice(id, length) };
+        import.ids.get(&id).copied().into()
+    }
+}
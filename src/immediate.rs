@@ -0,0 +1,245 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{Node, NodeId, Role, Tree, TreeId, TreeUpdate};
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::{c_char, CStr},
+    ptr,
+};
+
+use crate::{
+    box_from_ptr, mut_from_ptr, node, opt_node_id, ref_from_ptr, tree_update, BoxCastPtr, CastPtr,
+};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Hashes `bytes` with 64-bit FNV-1a, which gives the same result from
+/// one run to the next.
+pub(crate) fn fnv1a<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u64 {
+    bytes.into_iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Derives the ID of a node from the ID of its parent and its key.
+fn child_id(parent: Option<NodeId>, key: &[u8]) -> NodeId {
+    let parent = parent.map(|parent| parent.0.to_le_bytes());
+    NodeId(fnv1a(parent.iter().flatten().chain(key)))
+}
+
+#[derive(Default)]
+pub(crate) struct ImmediateContext {
+    /// The nodes that have begun but not ended, innermost last. Nodes
+    /// that were rejected, and their descendants, have no `Node`.
+    stack: Vec<(NodeId, Option<Node>)>,
+    /// The nodes that have ended during the current frame.
+    nodes: Vec<(NodeId, Node)>,
+    ids: HashSet<NodeId>,
+    root: Option<NodeId>,
+    focus: Option<NodeId>,
+    previous_nodes: HashMap<NodeId, Node>,
+    previous_root: Option<NodeId>,
+    previous_focus: Option<NodeId>,
+}
+
+impl ImmediateContext {
+    /// Rejected nodes are still pushed to the stack, so that the matching
+    /// call to `end_node` ends them rather than their parent.
+    fn begin_node(&mut self, key: &[u8], role: Role) -> Option<&mut Node> {
+        let (parent_id, can_add) = match self.stack.last() {
+            Some((id, parent)) => (Some(*id), parent.is_some()),
+            None => (None, self.root.is_none()),
+        };
+        let id = child_id(parent_id, key);
+        if !can_add || !self.ids.insert(id) {
+            self.stack.push((id, None));
+            return None;
+        }
+        match self.stack.last_mut() {
+            Some((_, parent)) => parent.as_mut().unwrap().push_child(id),
+            None => self.root = Some(id),
+        }
+        self.stack.push((id, Some(Node::new(role))));
+        self.stack.last_mut().unwrap().1.as_mut()
+    }
+
+    fn end_node(&mut self) -> bool {
+        let Some((id, node)) = self.stack.pop() else {
+            return false;
+        };
+        if let Some(node) = node {
+            self.nodes.push((id, node));
+        }
+        true
+    }
+
+    fn end_frame(&mut self) -> Option<TreeUpdate> {
+        if !self.stack.is_empty() {
+            return None;
+        }
+        let root = self.root.take()?;
+        let focus = self.focus.take().unwrap_or(root);
+        let nodes: Vec<(NodeId, Node)> = self
+            .nodes
+            .drain(..)
+            .filter(|(id, node)| self.previous_nodes.get(id) != Some(node))
+            .collect();
+        let tree = (self.previous_root != Some(root)).then(|| Tree::new(root));
+        for (id, node) in &nodes {
+            self.previous_nodes.insert(*id, node.clone());
+        }
+        self.previous_nodes.retain(|id, _| self.ids.contains(id));
+        self.ids.clear();
+        self.previous_root = Some(root);
+        self.previous_focus = Some(focus);
+        Some(TreeUpdate {
+            nodes,
+            tree,
+            tree_id: TreeId::ROOT,
+            focus,
+        })
+    }
+
+    fn full_update(&self) -> Option<TreeUpdate> {
+        let root = self.previous_root?;
+        Some(TreeUpdate {
+            nodes: self
+                .previous_nodes
+                .iter()
+                .map(|(id, node)| (*id, node.clone()))
+                .collect(),
+            tree: Some(Tree::new(root)),
+            tree_id: TreeId::ROOT,
+            focus: self.previous_focus.unwrap(),
+        })
+    }
+}
+
+/// State kept from one frame to the next by toolkits that declare their
+/// whole user interface every frame.
+///
+/// A frame is declared by nesting calls to `accesskit_imm_begin_node` and
+/// `accesskit_imm_end_node`, with a single top-level node which becomes
+/// the root, then ended with `accesskit_imm_end_frame`. The ID of each
+/// node is derived from its key and the ID of its parent, so keys only
+/// need to be unique among siblings, and a node keeps its ID across
+/// frames as long as the keys leading to it don't change. The children
+/// of a node are the nodes declared while it is open, in order.
+///
+/// A context isn't synchronized, so it must only be used on the thread
+/// declaring the frames. If the activation handler of an adapter runs on
+/// another thread, return null from it and give the full update to the
+/// `provide_initial_tree` function of the adapter from that thread.
+pub struct imm_context {
+    _private: [u8; 0],
+}
+
+impl CastPtr for imm_context {
+    type RustType = ImmediateContext;
+}
+
+impl BoxCastPtr for imm_context {}
+
+impl imm_context {
+    #[no_mangle]
+    pub extern "C" fn accesskit_imm_context_new() -> *mut imm_context {
+        BoxCastPtr::to_mut_ptr(ImmediateContext::default())
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_imm_context_free(ctx: *mut imm_context) {
+        drop(box_from_ptr(ctx));
+    }
+
+    /// Begins a node, as a child of the innermost node that hasn't ended.
+    /// Returns the new node so that its properties can be set, except for
+    /// its children which are filled in from the nodes declared until the
+    /// matching call to `accesskit_imm_end_node`.
+    ///
+    /// The returned node is owned by the context, and is only valid until
+    /// the next call to `accesskit_imm_begin_node` or `accesskit_imm_end_node`.
+    ///
+    /// Returns null if `key` is already used by a sibling in this frame, if
+    /// this is a second top-level node, or if the parent was rejected. The
+    /// rejected node, along with the nodes declared inside it, is left out
+    /// of the frame, but must still be ended.
+    #[no_mangle]
+    pub extern "C" fn accesskit_imm_begin_node(
+        ctx: *mut imm_context,
+        key: *const c_char,
+        role: Role,
+    ) -> *mut node {
+        let ctx = mut_from_ptr(ctx);
+        let key = unsafe { CStr::from_ptr(key) };
+        match ctx.begin_node(key.to_bytes(), role) {
+            Some(node) => node as *mut _ as *mut node,
+            None => ptr::null_mut(),
+        }
+    }
+
+    /// Ends the innermost node that hasn't ended. Returns false if there is
+    /// no such node.
+    #[no_mangle]
+    pub extern "C" fn accesskit_imm_end_node(ctx: *mut imm_context) -> bool {
+        let ctx = mut_from_ptr(ctx);
+        ctx.end_node()
+    }
+
+    /// Returns the ID of the innermost node that hasn't ended, for use in
+    /// relations between nodes and to recognize the targets of action
+    /// requests. Has no value if there is no such node.
+    #[no_mangle]
+    pub extern "C" fn accesskit_imm_current_id(ctx: *const imm_context) -> opt_node_id {
+        let ctx = ref_from_ptr(ctx);
+        ctx.stack.last().map(|(id, _)| *id).into()
+    }
+
+    /// Gives the focus to the innermost node that hasn't ended. Without a
+    /// call to this function during a frame, the root has the focus.
+    /// Returns false, leaving the focus unchanged, if there is no such
+    /// node or it was rejected.
+    #[no_mangle]
+    pub extern "C" fn accesskit_imm_set_focus(ctx: *mut imm_context) -> bool {
+        let ctx = mut_from_ptr(ctx);
+        match ctx.stack.last() {
+            Some((id, Some(_))) => {
+                ctx.focus = Some(*id);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Ends the frame and returns an update holding the nodes that differ
+    /// from the previous frame. Nodes that are no longer declared are
+    /// dropped by adapters since their parent, which changed, no longer
+    /// lists them. The first frame, and any frame whose root has another
+    /// key, produces a full update.
+    ///
+    /// The returned update should be given to an adapter, or else the next
+    /// updates will miss what changed in this one; use
+    /// `accesskit_imm_full_update` to bring an adapter up to date.
+    ///
+    /// Returns null if no node was declared. Also returns null if a node
+    /// hasn't ended, in which case the frame goes on.
+    #[no_mangle]
+    pub extern "C" fn accesskit_imm_end_frame(ctx: *mut imm_context) -> *mut tree_update {
+        let ctx = mut_from_ptr(ctx);
+        BoxCastPtr::to_nullable_mut_ptr(ctx.end_frame())
+    }
+
+    /// Returns an update holding the whole tree as of the last frame, or
+    /// null if no frame has ended yet.
+    #[no_mangle]
+    pub extern "C" fn accesskit_imm_full_update(ctx: *const imm_context) -> *mut tree_update {
+        let ctx = ref_from_ptr(ctx);
+        match ctx.full_update() {
+            Some(update) => BoxCastPtr::to_mut_ptr(update),
+            None => ptr::null_mut(),
+        }
+    }
+}
@@ -4,13 +4,16 @@
 // the LICENSE-MIT file), at your option.
 
 use accesskit_ios::{Adapter, CGPoint, QueuedEvents, SubclassingAdapter};
-use std::ffi::{c_char, c_void};
+use std::{
+    ffi::{c_char, c_void},
+    ptr,
+};
 
 use crate::{
-    box_from_ptr, debug_repr_from_ptr, mut_from_ptr, tree_update_factory,
+    box_from_ptr, debug_repr_from_ptr, mut_from_ptr, tree_update, tree_update_factory,
     tree_update_factory_userdata, ActionHandlerCallback, ActivationHandlerCallback, BoxCastPtr,
     CastPtr, DeactivationHandlerCallback, FfiActionHandler, FfiActivationHandler,
-    FfiDeactivationHandler,
+    FfiDeactivationHandler, UpdateValidator, ValidatingAdapter, ValidationHandlerCallback,
 };
 
 pub struct ios_queued_events {
@@ -37,7 +40,7 @@ pub struct ios_adapter {
 }
 
 impl CastPtr for ios_adapter {
-    type RustType = Adapter;
+    type RustType = ValidatingAdapter<Adapter>;
 }
 
 impl BoxCastPtr for ios_adapter {}
@@ -59,18 +62,23 @@ impl ios_adapter {
         deactivation_handler: DeactivationHandlerCallback,
         deactivation_handler_userdata: *mut c_void,
     ) -> *mut ios_adapter {
-        let activation_handler =
-            FfiActivationHandler::new(activation_handler, activation_handler_userdata);
+        let validator = UpdateValidator::default();
+        let activation_handler = validator.activation_handler(FfiActivationHandler::new(
+            activation_handler,
+            activation_handler_userdata,
+        ));
         let action_handler = FfiActionHandler::new(action_handler, action_handler_userdata);
-        let deactivation_handler =
-            FfiDeactivationHandler::new(deactivation_handler, deactivation_handler_userdata);
+        let deactivation_handler = validator.deactivation_handler(FfiDeactivationHandler::new(
+            deactivation_handler,
+            deactivation_handler_userdata,
+        ));
         let adapter = Adapter::new(
             view,
             activation_handler,
             action_handler,
             deactivation_handler,
         );
-        BoxCastPtr::to_mut_ptr(adapter)
+        BoxCastPtr::to_mut_ptr(ValidatingAdapter::new(adapter, validator))
     }
 
     #[no_mangle]
@@ -78,6 +86,19 @@ impl ios_adapter {
         drop(box_from_ptr(adapter));
     }
 
+    /// Validates every tree update given to the adapter and reports the
+    /// invalid ones to `handler` instead of applying them. Pass a null
+    /// `handler` to turn validation off.
+    #[no_mangle]
+    pub extern "C" fn accesskit_ios_adapter_set_validation_handler(
+        adapter: *mut ios_adapter,
+        handler: ValidationHandlerCallback,
+        userdata: *mut c_void,
+    ) {
+        let adapter = mut_from_ptr(adapter);
+        adapter.validator.set_handler(handler, userdata);
+    }
+
     /// You must call `accesskit_ios_queued_events_raise` on the returned pointer. It can be null if the adapter is not active.
     #[no_mangle]
     pub extern "C" fn accesskit_ios_adapter_update_if_active(
@@ -85,11 +106,36 @@ impl ios_adapter {
         update_factory: tree_update_factory,
         update_factory_userdata: *mut c_void,
     ) -> *mut ios_queued_events {
-        let update_factory = update_factory.unwrap();
+        let Some(update_factory) = update_factory else {
+            return ptr::null_mut();
+        };
         let update_factory_userdata = tree_update_factory_userdata(update_factory_userdata);
         let adapter = mut_from_ptr(adapter);
-        let events =
-            adapter.update_if_active(|| *box_from_ptr(update_factory(update_factory_userdata)));
+        let validator = adapter.validator.clone();
+        let events = adapter.update_if_active(|| {
+            validator.check_update(*box_from_ptr(update_factory(update_factory_userdata)))
+        });
+        BoxCastPtr::to_nullable_mut_ptr(events)
+    }
+
+    /// Gives the adapter the initial tree that its activation handler didn't
+    /// return, which must be a full tree update. Passing one to
+    /// `accesskit_ios_adapter_update_if_active` works too. Takes ownership of
+    /// `update`, which is dropped if the adapter isn't waiting for its initial
+    /// tree.
+    ///
+    /// You must call `accesskit_ios_queued_events_raise` on the returned pointer. It can be null if the adapter wasn't waiting for its initial tree.
+    #[no_mangle]
+    pub extern "C" fn accesskit_ios_adapter_provide_initial_tree(
+        adapter: *mut ios_adapter,
+        update: *mut tree_update,
+    ) -> *mut ios_queued_events {
+        let update = *box_from_ptr(update);
+        let adapter = mut_from_ptr(adapter);
+        let Some(update) = adapter.validator.provide_initial_tree(update) else {
+            return ptr::null_mut();
+        };
+        let events = adapter.update_if_active(|| update);
         BoxCastPtr::to_nullable_mut_ptr(events)
     }
 
@@ -153,7 +199,7 @@ pub struct ios_subclassing_adapter {
 }
 
 impl CastPtr for ios_subclassing_adapter {
-    type RustType = SubclassingAdapter;
+    type RustType = ValidatingAdapter<SubclassingAdapter>;
 }
 
 impl BoxCastPtr for ios_subclassing_adapter {}
@@ -174,18 +220,23 @@ impl ios_subclassing_adapter {
         deactivation_handler: DeactivationHandlerCallback,
         deactivation_handler_userdata: *mut c_void,
     ) -> *mut ios_subclassing_adapter {
-        let activation_handler =
-            FfiActivationHandler::new(activation_handler, activation_handler_userdata);
+        let validator = UpdateValidator::default();
+        let activation_handler = validator.activation_handler(FfiActivationHandler::new(
+            activation_handler,
+            activation_handler_userdata,
+        ));
         let action_handler = FfiActionHandler::new(action_handler, action_handler_userdata);
-        let deactivation_handler =
-            FfiDeactivationHandler::new(deactivation_handler, deactivation_handler_userdata);
+        let deactivation_handler = validator.deactivation_handler(FfiDeactivationHandler::new(
+            deactivation_handler,
+            deactivation_handler_userdata,
+        ));
         let adapter = SubclassingAdapter::new(
             view,
             activation_handler,
             action_handler,
             deactivation_handler,
         );
-        BoxCastPtr::to_mut_ptr(adapter)
+        BoxCastPtr::to_mut_ptr(ValidatingAdapter::new(adapter, validator))
     }
 
     /// All handlers will always be called on the main thread.
@@ -208,18 +259,23 @@ impl ios_subclassing_adapter {
         deactivation_handler: DeactivationHandlerCallback,
         deactivation_handler_userdata: *mut c_void,
     ) -> *mut ios_subclassing_adapter {
-        let activation_handler =
-            FfiActivationHandler::new(activation_handler, activation_handler_userdata);
+        let validator = UpdateValidator::default();
+        let activation_handler = validator.activation_handler(FfiActivationHandler::new(
+            activation_handler,
+            activation_handler_userdata,
+        ));
         let action_handler = FfiActionHandler::new(action_handler, action_handler_userdata);
-        let deactivation_handler =
-            FfiDeactivationHandler::new(deactivation_handler, deactivation_handler_userdata);
+        let deactivation_handler = validator.deactivation_handler(FfiDeactivationHandler::new(
+            deactivation_handler,
+            deactivation_handler_userdata,
+        ));
         let adapter = SubclassingAdapter::for_window(
             window,
             activation_handler,
             action_handler,
             deactivation_handler,
         );
-        BoxCastPtr::to_mut_ptr(adapter)
+        BoxCastPtr::to_mut_ptr(ValidatingAdapter::new(adapter, validator))
     }
 
     #[no_mangle]
@@ -229,6 +285,19 @@ impl ios_subclassing_adapter {
         drop(box_from_ptr(adapter));
     }
 
+    /// Validates every tree update given to the adapter and reports the
+    /// invalid ones to `handler` instead of applying them. Pass a null
+    /// `handler` to turn validation off.
+    #[no_mangle]
+    pub extern "C" fn accesskit_ios_subclassing_adapter_set_validation_handler(
+        adapter: *mut ios_subclassing_adapter,
+        handler: ValidationHandlerCallback,
+        userdata: *mut c_void,
+    ) {
+        let adapter = mut_from_ptr(adapter);
+        adapter.validator.set_handler(handler, userdata);
+    }
+
     /// You must call `accesskit_ios_queued_events_raise` on the returned pointer. It can be null if the adapter is not active.
     #[no_mangle]
     pub extern "C" fn accesskit_ios_subclassing_adapter_update_if_active(
@@ -236,11 +305,36 @@ impl ios_subclassing_adapter {
         update_factory: tree_update_factory,
         update_factory_userdata: *mut c_void,
     ) -> *mut ios_queued_events {
-        let update_factory = update_factory.unwrap();
+        let Some(update_factory) = update_factory else {
+            return ptr::null_mut();
+        };
         let update_factory_userdata = tree_update_factory_userdata(update_factory_userdata);
         let adapter = mut_from_ptr(adapter);
-        let events =
-            adapter.update_if_active(|| *box_from_ptr(update_factory(update_factory_userdata)));
+        let validator = adapter.validator.clone();
+        let events = adapter.update_if_active(|| {
+            validator.check_update(*box_from_ptr(update_factory(update_factory_userdata)))
+        });
+        BoxCastPtr::to_nullable_mut_ptr(events)
+    }
+
+    /// Gives the adapter the initial tree that its activation handler didn't
+    /// return, which must be a full tree update. Passing one to
+    /// `accesskit_ios_subclassing_adapter_update_if_active` works too. Takes
+    /// ownership of `update`, which is dropped if the adapter isn't waiting for
+    /// its initial tree.
+    ///
+    /// You must call `accesskit_ios_queued_events_raise` on the returned pointer. It can be null if the adapter wasn't waiting for its initial tree.
+    #[no_mangle]
+    pub extern "C" fn accesskit_ios_subclassing_adapter_provide_initial_tree(
+        adapter: *mut ios_subclassing_adapter,
+        update: *mut tree_update,
+    ) -> *mut ios_queued_events {
+        let update = *box_from_ptr(update);
+        let adapter = mut_from_ptr(adapter);
+        let Some(update) = adapter.validator.provide_initial_tree(update) else {
+            return ptr::null_mut();
+        };
+        let events = adapter.update_if_active(|| update);
         BoxCastPtr::to_nullable_mut_ptr(events)
     }
 }
@@ -13,8 +13,47 @@
 #![allow(non_camel_case_types)]
 #![allow(clippy::not_unsafe_ptr_arg_deref)]
 
+mod aria;
+mod braille;
+mod builder;
+mod coalesce;
 mod common;
+mod contrast;
+mod diff;
+mod dispatch;
+mod dot;
+mod dump;
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    feature = "cbindgen"
+))]
+mod events;
 mod geometry;
+mod html;
+mod html_parser;
+mod immediate;
+mod lint;
+mod names;
+mod pool;
+mod registry;
+mod retained;
+mod selector;
+mod snapshot;
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    feature = "cbindgen"
+))]
+mod tasks;
+mod testing;
+mod validation;
 
 #[cfg(any(target_os = "android", feature = "cbindgen"))]
 mod android;
@@ -48,8 +87,27 @@ use std::{
 
 #[cfg(any(target_os = "android", feature = "cbindgen"))]
 pub use android::*;
+pub(crate) use aria::*;
+pub use braille::*;
+pub use builder::*;
+pub use coalesce::*;
 pub use common::*;
+pub use contrast::*;
+pub use diff::*;
+pub use dispatch::*;
+pub use dump::*;
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    feature = "cbindgen"
+))]
+pub use events::*;
 pub use geometry::*;
+pub use html_parser::*;
+pub use immediate::*;
 #[cfg(any(
     target_os = "ios",
     target_os = "tvos",
@@ -58,8 +116,25 @@ pub use geometry::*;
     feature = "cbindgen"
 ))]
 pub use ios::*;
+pub use lint::*;
 #[cfg(any(target_os = "macos", feature = "cbindgen"))]
 pub use macos::*;
+pub(crate) use names::*;
+pub use pool::*;
+pub use registry::*;
+pub use retained::*;
+pub(crate) use selector::*;
+pub use snapshot::*;
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    feature = "cbindgen"
+))]
+pub use tasks::*;
+pub use testing::*;
 #[cfg(any(
     target_os = "linux",
     target_os = "dragonfly",
@@ -69,6 +144,7 @@ pub use macos::*;
     feature = "cbindgen"
 ))]
 pub use unix::*;
+pub use validation::*;
 #[cfg(any(target_os = "windows", feature = "cbindgen"))]
 pub use windows::*;
 
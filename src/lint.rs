@@ -0,0 +1,395 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{Action, Node, NodeId, Role};
+use std::{
+    ffi::{c_char, CStr, CString},
+    fmt::Write,
+};
+
+use crate::{
+    box_from_ptr, mut_from_ptr, node_id, node_relations, ref_from_ptr, role_name, tree_snapshot,
+    tree_update, BoxCastPtr, CastPtr, TreeSnapshot,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum lint_rule {
+    /// A focusable control, such as a button or a text input, has no name.
+    ControlName,
+    /// An image that isn't hidden has no name.
+    ImageName,
+    /// A slider, spin button, scroll bar or meter is missing its numeric
+    /// value, minimum or maximum.
+    RangeValue,
+    /// A numeric value is outside of its range, or the minimum is greater
+    /// than the maximum.
+    RangeBounds,
+    /// A relation such as `labelled_by` or `controls` points to a node that
+    /// isn't in the tree.
+    RelationTarget,
+    /// `position_in_set` is 0, or greater than `size_of_set`.
+    PositionInSet,
+    /// The focused node is hidden.
+    HiddenFocus,
+}
+
+impl lint_rule {
+    fn id(self) -> &'static CStr {
+        match self {
+            Self::ControlName => c"control-name",
+            Self::ImageName => c"image-name",
+            Self::RangeValue => c"range-value",
+            Self::RangeBounds => c"range-bounds",
+            Self::RelationTarget => c"relation-target",
+            Self::PositionInSet => c"position-in-set",
+            Self::HiddenFocus => c"hidden-focus",
+        }
+    }
+
+    fn severity(self) -> lint_severity {
+        match self {
+            Self::RangeBounds | Self::HiddenFocus => lint_severity::Warning,
+            _ => lint_severity::Error,
+        }
+    }
+
+    /// Returns a stable identifier for the rule, such as `control-name`.
+    /// The returned string is static and must not be freed.
+    #[no_mangle]
+    pub extern "C" fn accesskit_lint_rule_id(rule: lint_rule) -> *const c_char {
+        rule.id().as_ptr()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum lint_severity {
+    Warning,
+    Error,
+}
+
+pub(crate) struct LintFinding {
+    rule: lint_rule,
+    node: NodeId,
+    message: CString,
+}
+
+fn is_control(role: Role) -> bool {
+    matches!(
+        role,
+        Role::Button
+            | Role::DefaultButton
+            | Role::Link
+            | Role::CheckBox
+            | Role::RadioButton
+            | Role::Switch
+            | Role::MenuItem
+            | Role::MenuItemCheckBox
+            | Role::MenuItemRadio
+            | Role::Tab
+            | Role::TreeItem
+            | Role::ListBoxOption
+            | Role::Slider
+            | Role::SpinButton
+            | Role::ComboBox
+            | Role::EditableComboBox
+            | Role::TextInput
+            | Role::MultilineTextInput
+            | Role::SearchInput
+            | Role::DateInput
+            | Role::DateTimeInput
+            | Role::WeekInput
+            | Role::MonthInput
+            | Role::TimeInput
+            | Role::EmailInput
+            | Role::NumberInput
+            | Role::PasswordInput
+            | Role::PhoneNumberInput
+            | Role::UrlInput
+    )
+}
+
+fn check_node(
+    snapshot: &TreeSnapshot,
+    id: NodeId,
+    node: &Node,
+    report: &mut impl FnMut(lint_rule, String),
+) {
+    let role = node.role();
+    let has_name = snapshot
+        .name(id)
+        .is_some_and(|name| !name.trim().is_empty());
+    if is_control(role) && node.supports_action(Action::Focus) && !has_name {
+        report(
+            lint_rule::ControlName,
+            format!("focusable {} has no name", role_name(role)),
+        );
+    }
+    if role == Role::Image && !node.is_hidden() && !has_name {
+        report(lint_rule::ImageName, "image has no name".into());
+    }
+
+    if matches!(
+        role,
+        Role::Slider | Role::SpinButton | Role::ScrollBar | Role::Meter
+    ) {
+        let missing: Vec<&str> = [
+            ("numeric_value", node.numeric_value()),
+            ("min_numeric_value", node.min_numeric_value()),
+            ("max_numeric_value", node.max_numeric_value()),
+        ]
+        .iter()
+        .filter(|(_, value)| value.is_none())
+        .map(|(name, _)| *name)
+        .collect();
+        if !missing.is_empty() {
+            report(
+                lint_rule::RangeValue,
+                format!("{} is missing {}", role_name(role), missing.join(", ")),
+            );
+        }
+    }
+    let min = node.min_numeric_value();
+    let max = node.max_numeric_value();
+    if let (Some(min), Some(max)) = (min, max) {
+        if min > max {
+            report(
+                lint_rule::RangeBounds,
+                format!(
+                    "min_numeric_value {} is greater than max_numeric_value {}",
+                    min, max
+                ),
+            );
+        }
+    }
+    if let Some(value) = node.numeric_value() {
+        if min.is_some_and(|min| value < min) || max.is_some_and(|max| value > max) {
+            report(
+                lint_rule::RangeBounds,
+                format!("numeric_value {} is outside of its range", value),
+            );
+        }
+    }
+
+    for (relation, targets) in node_relations(node) {
+        for target in targets {
+            if snapshot.node(target).is_none() {
+                report(
+                    lint_rule::RelationTarget,
+                    format!("{} refers to missing node #{}", relation, target.0),
+                );
+            }
+        }
+    }
+
+    match (node.position_in_set(), node.size_of_set()) {
+        (Some(0), _) => report(
+            lint_rule::PositionInSet,
+            "position_in_set is 0, but positions start at 1".into(),
+        ),
+        (Some(position), Some(size)) if position > size => report(
+            lint_rule::PositionInSet,
+            format!(
+                "position_in_set {} is greater than size_of_set {}",
+                position, size
+            ),
+        ),
+        _ => {}
+    }
+
+    if snapshot.focus_id() == id
+        && (node.is_hidden()
+            || snapshot
+                .ancestor_ids(id)
+                .any(|ancestor| snapshot.node(ancestor).unwrap().is_hidden()))
+    {
+        report(lint_rule::HiddenFocus, "the focused node is hidden".into());
+    }
+}
+
+/// Which rules a lint pass runs.
+pub(crate) struct Linter {
+    disabled: u32,
+}
+
+impl Linter {
+    fn is_enabled(&self, rule: lint_rule) -> bool {
+        self.disabled & (1 << rule as u8) == 0
+    }
+
+    /// Findings are in document order, and in rule order for each node.
+    pub(crate) fn check(&self, snapshot: &TreeSnapshot) -> Vec<LintFinding> {
+        let mut findings = Vec::new();
+        snapshot.visit(|id, node, _| {
+            check_node(snapshot, id, node, &mut |rule, message| {
+                if self.is_enabled(rule) {
+                    findings.push(LintFinding {
+                        rule,
+                        node: id,
+                        message: CString::new(message).unwrap(),
+                    });
+                }
+            });
+        });
+        findings
+    }
+}
+
+fn render(findings: &[LintFinding]) -> String {
+    let mut output = String::new();
+    for finding in findings {
+        writeln!(
+            output,
+            "{} #{} [{}]: {}",
+            match finding.rule.severity() {
+                lint_severity::Warning => "warning",
+                lint_severity::Error => "error",
+            },
+            finding.node.0,
+            finding.rule.id().to_string_lossy(),
+            finding.message.to_string_lossy()
+        )
+        .unwrap();
+    }
+    output
+}
+
+pub struct linter {
+    _private: [u8; 0],
+}
+
+impl CastPtr for linter {
+    type RustType = Linter;
+}
+
+impl BoxCastPtr for linter {}
+
+impl linter {
+    /// Creates a linter with all rules enabled.
+    #[no_mangle]
+    pub extern "C" fn accesskit_linter_new() -> *mut linter {
+        BoxCastPtr::to_mut_ptr(Linter { disabled: 0 })
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_linter_free(linter: *mut linter) {
+        drop(box_from_ptr(linter));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_linter_set_rule_enabled(
+        linter: *mut linter,
+        rule: lint_rule,
+        enabled: bool,
+    ) {
+        let linter = mut_from_ptr(linter);
+        if enabled {
+            linter.disabled &= !(1 << rule as u8);
+        } else {
+            linter.disabled |= 1 << rule as u8;
+        }
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_linter_is_rule_enabled(
+        linter: *const linter,
+        rule: lint_rule,
+    ) -> bool {
+        let linter = ref_from_ptr(linter);
+        linter.is_enabled(rule)
+    }
+
+    /// Runs the enabled rules on every node of the tree.
+    #[no_mangle]
+    pub extern "C" fn accesskit_lint(
+        linter: *const linter,
+        snapshot: *const tree_snapshot,
+    ) -> *mut lint_findings {
+        let linter = ref_from_ptr(linter);
+        let snapshot = ref_from_ptr(snapshot);
+        BoxCastPtr::to_mut_ptr(linter.check(snapshot))
+    }
+
+    /// Runs the enabled rules on the tree described by a full tree update,
+    /// such as the one returned from an activation handler.
+    ///
+    /// Returns null if the tree update doesn't have its `tree` field set.
+    #[no_mangle]
+    pub extern "C" fn accesskit_lint_tree_update(
+        linter: *const linter,
+        update: *const tree_update,
+    ) -> *mut lint_findings {
+        let linter = ref_from_ptr(linter);
+        let update = ref_from_ptr(update);
+        BoxCastPtr::to_nullable_mut_ptr(
+            TreeSnapshot::new(update.clone()).map(|snapshot| linter.check(&snapshot)),
+        )
+    }
+}
+
+/// A problem found by a lint rule.
+///
+/// `message` is owned by the `accesskit_lint_findings` this was retrieved
+/// from.
+#[repr(C)]
+pub struct lint_finding {
+    pub rule: lint_rule,
+    pub severity: lint_severity,
+    pub node: node_id,
+    pub message: *const c_char,
+}
+
+pub struct lint_findings {
+    _private: [u8; 0],
+}
+
+impl CastPtr for lint_findings {
+    type RustType = Vec<LintFinding>;
+}
+
+impl BoxCastPtr for lint_findings {}
+
+impl lint_findings {
+    #[no_mangle]
+    pub extern "C" fn accesskit_lint_findings_free(findings: *mut lint_findings) {
+        drop(box_from_ptr(findings));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_lint_findings_len(findings: *const lint_findings) -> usize {
+        let findings = ref_from_ptr(findings);
+        findings.len()
+    }
+
+    /// `index` must be lower than the value returned by
+    /// `accesskit_lint_findings_len`.
+    #[no_mangle]
+    pub extern "C" fn accesskit_lint_findings_get(
+        findings: *const lint_findings,
+        index: usize,
+    ) -> lint_finding {
+        let findings = ref_from_ptr(findings);
+        let finding = &findings[index];
+        lint_finding {
+            rule: finding.rule,
+            severity: finding.rule.severity(),
+            node: finding.node.into(),
+            message: finding.message.as_ptr(),
+        }
+    }
+
+    /// Renders the findings one per line, for instance
+    /// `error #4 [image-name]: image has no name`.
+    ///
+    /// Caller must call `accesskit_string_free` with the return value.
+    #[no_mangle]
+    pub extern "C" fn accesskit_lint_findings_to_string(
+        findings: *const lint_findings,
+    ) -> *mut c_char {
+        let findings = ref_from_ptr(findings);
+        CString::new(render(findings)).unwrap().into_raw()
+    }
+}
@@ -6,12 +6,16 @@
 use accesskit_macos::{
     add_focus_forwarder_to_window_class, Adapter, NSPoint, QueuedEvents, SubclassingAdapter,
 };
-use std::ffi::{c_char, c_void, CStr};
+use std::{
+    ffi::{c_char, c_void, CStr},
+    ptr,
+};
 
 use crate::{
-    box_from_ptr, debug_repr_from_ptr, mut_from_ptr, string_from_c_slice, tree_update_factory,
-    tree_update_factory_userdata, ActionHandlerCallback, ActivationHandlerCallback, BoxCastPtr,
-    CastPtr, FfiActionHandler, FfiActivationHandler,
+    box_from_ptr, debug_repr_from_ptr, mut_from_ptr, string_from_c_slice, tree_update,
+    tree_update_factory, tree_update_factory_userdata, ActionHandlerCallback,
+    ActivationHandlerCallback, BoxCastPtr, CastPtr, FfiActionHandler, FfiActivationHandler,
+    UpdateValidator, ValidatingAdapter, ValidationHandlerCallback,
 };
 
 pub struct macos_queued_events {
@@ -38,7 +42,7 @@ pub struct macos_adapter {
 }
 
 impl CastPtr for macos_adapter {
-    type RustType = Adapter;
+    type RustType = ValidatingAdapter<Adapter>;
 }
 
 impl BoxCastPtr for macos_adapter {}
@@ -56,7 +60,7 @@ impl macos_adapter {
     ) -> *mut macos_adapter {
         let action_handler = FfiActionHandler::new(action_handler, action_handler_userdata);
         let adapter = Adapter::new(view, is_view_focused, action_handler);
-        BoxCastPtr::to_mut_ptr(adapter)
+        BoxCastPtr::to_mut_ptr(ValidatingAdapter::new(adapter, UpdateValidator::default()))
     }
 
     #[no_mangle]
@@ -64,6 +68,19 @@ impl macos_adapter {
         drop(box_from_ptr(adapter));
     }
 
+    /// Validates every tree update given to the adapter and reports the
+    /// invalid ones to `handler` instead of applying them. Pass a null
+    /// `handler` to turn validation off.
+    #[no_mangle]
+    pub extern "C" fn accesskit_macos_adapter_set_validation_handler(
+        adapter: *mut macos_adapter,
+        handler: ValidationHandlerCallback,
+        userdata: *mut c_void,
+    ) {
+        let adapter = mut_from_ptr(adapter);
+        adapter.validator.set_handler(handler, userdata);
+    }
+
     /// You must call `accesskit_macos_queued_events_raise` on the returned pointer. It can be null if the adapter is not active.
     #[no_mangle]
     pub extern "C" fn accesskit_macos_adapter_update_if_active(
@@ -71,11 +88,36 @@ impl macos_adapter {
         update_factory: tree_update_factory,
         update_factory_userdata: *mut c_void,
     ) -> *mut macos_queued_events {
-        let update_factory = update_factory.unwrap();
+        let Some(update_factory) = update_factory else {
+            return ptr::null_mut();
+        };
         let update_factory_userdata = tree_update_factory_userdata(update_factory_userdata);
         let adapter = mut_from_ptr(adapter);
-        let events =
-            adapter.update_if_active(|| *box_from_ptr(update_factory(update_factory_userdata)));
+        let validator = adapter.validator.clone();
+        let events = adapter.update_if_active(|| {
+            validator.check_update(*box_from_ptr(update_factory(update_factory_userdata)))
+        });
+        BoxCastPtr::to_nullable_mut_ptr(events)
+    }
+
+    /// Gives the adapter the initial tree that its activation handler didn't
+    /// return, which must be a full tree update. Passing one to
+    /// `accesskit_macos_adapter_update_if_active` works too. Takes ownership of
+    /// `update`, which is dropped if the adapter isn't waiting for its initial
+    /// tree.
+    ///
+    /// You must call `accesskit_macos_queued_events_raise` on the returned pointer. It can be null if the adapter wasn't waiting for its initial tree.
+    #[no_mangle]
+    pub extern "C" fn accesskit_macos_adapter_provide_initial_tree(
+        adapter: *mut macos_adapter,
+        update: *mut tree_update,
+    ) -> *mut macos_queued_events {
+        let update = *box_from_ptr(update);
+        let adapter = mut_from_ptr(adapter);
+        let Some(update) = adapter.validator.provide_initial_tree(update) else {
+            return ptr::null_mut();
+        };
+        let events = adapter.update_if_active(|| update);
         BoxCastPtr::to_nullable_mut_ptr(events)
     }
 
@@ -101,7 +143,12 @@ impl macos_adapter {
     ) -> *mut c_void {
         let adapter = mut_from_ptr(adapter);
         let mut activation_handler =
-            FfiActivationHandler::new(activation_handler, activation_handler_userdata);
+            adapter
+                .validator
+                .activation_handler(FfiActivationHandler::new(
+                    activation_handler,
+                    activation_handler_userdata,
+                ));
         adapter.view_children(&mut activation_handler) as *mut _
     }
 
@@ -114,7 +161,12 @@ impl macos_adapter {
     ) -> *mut c_void {
         let adapter = mut_from_ptr(adapter);
         let mut activation_handler =
-            FfiActivationHandler::new(activation_handler, activation_handler_userdata);
+            adapter
+                .validator
+                .activation_handler(FfiActivationHandler::new(
+                    activation_handler,
+                    activation_handler_userdata,
+                ));
         adapter.focus(&mut activation_handler) as *mut _
     }
 
@@ -129,7 +181,12 @@ impl macos_adapter {
     ) -> *mut c_void {
         let adapter = mut_from_ptr(adapter);
         let mut activation_handler =
-            FfiActivationHandler::new(activation_handler, activation_handler_userdata);
+            adapter
+                .validator
+                .activation_handler(FfiActivationHandler::new(
+                    activation_handler,
+                    activation_handler_userdata,
+                ));
         adapter.hit_test(NSPoint::new(x, y), &mut activation_handler) as *mut _
     }
 
@@ -145,7 +202,7 @@ pub struct macos_subclassing_adapter {
 }
 
 impl CastPtr for macos_subclassing_adapter {
-    type RustType = SubclassingAdapter;
+    type RustType = ValidatingAdapter<SubclassingAdapter>;
 }
 
 impl BoxCastPtr for macos_subclassing_adapter {}
@@ -162,11 +219,14 @@ impl macos_subclassing_adapter {
         action_handler: ActionHandlerCallback,
         action_handler_userdata: *mut c_void,
     ) -> *mut macos_subclassing_adapter {
-        let activation_handler =
-            FfiActivationHandler::new(activation_handler, activation_handler_userdata);
+        let validator = UpdateValidator::default();
+        let activation_handler = validator.activation_handler(FfiActivationHandler::new(
+            activation_handler,
+            activation_handler_userdata,
+        ));
         let action_handler = FfiActionHandler::new(action_handler, action_handler_userdata);
         let adapter = SubclassingAdapter::new(view, activation_handler, action_handler);
-        BoxCastPtr::to_mut_ptr(adapter)
+        BoxCastPtr::to_mut_ptr(ValidatingAdapter::new(adapter, validator))
     }
 
     /// # Safety
@@ -185,11 +245,14 @@ impl macos_subclassing_adapter {
         action_handler: ActionHandlerCallback,
         action_handler_userdata: *mut c_void,
     ) -> *mut macos_subclassing_adapter {
-        let activation_handler =
-            FfiActivationHandler::new(activation_handler, activation_handler_userdata);
+        let validator = UpdateValidator::default();
+        let activation_handler = validator.activation_handler(FfiActivationHandler::new(
+            activation_handler,
+            activation_handler_userdata,
+        ));
         let action_handler = FfiActionHandler::new(action_handler, action_handler_userdata);
         let adapter = SubclassingAdapter::for_window(window, activation_handler, action_handler);
-        BoxCastPtr::to_mut_ptr(adapter)
+        BoxCastPtr::to_mut_ptr(ValidatingAdapter::new(adapter, validator))
     }
 
     #[no_mangle]
@@ -199,6 +262,19 @@ impl macos_subclassing_adapter {
         drop(box_from_ptr(adapter));
     }
 
+    /// Validates every tree update given to the adapter and reports the
+    /// invalid ones to `handler` instead of applying them. Pass a null
+    /// `handler` to turn validation off.
+    #[no_mangle]
+    pub extern "C" fn accesskit_macos_subclassing_adapter_set_validation_handler(
+        adapter: *mut macos_subclassing_adapter,
+        handler: ValidationHandlerCallback,
+        userdata: *mut c_void,
+    ) {
+        let adapter = mut_from_ptr(adapter);
+        adapter.validator.set_handler(handler, userdata);
+    }
+
     /// You must call `accesskit_macos_queued_events_raise` on the returned pointer. It can be null if the adapter is not active.
     #[no_mangle]
     pub extern "C" fn accesskit_macos_subclassing_adapter_update_if_active(
@@ -206,11 +282,36 @@ impl macos_subclassing_adapter {
         update_factory: tree_update_factory,
         update_factory_userdata: *mut c_void,
     ) -> *mut macos_queued_events {
-        let update_factory = update_factory.unwrap();
+        let Some(update_factory) = update_factory else {
+            return ptr::null_mut();
+        };
         let update_factory_userdata = tree_update_factory_userdata(update_factory_userdata);
         let adapter = mut_from_ptr(adapter);
-        let events =
-            adapter.update_if_active(|| *box_from_ptr(update_factory(update_factory_userdata)));
+        let validator = adapter.validator.clone();
+        let events = adapter.update_if_active(|| {
+            validator.check_update(*box_from_ptr(update_factory(update_factory_userdata)))
+        });
+        BoxCastPtr::to_nullable_mut_ptr(events)
+    }
+
+    /// Gives the adapter the initial tree that its activation handler didn't
+    /// return, which must be a full tree update. Passing one to
+    /// `accesskit_macos_subclassing_adapter_update_if_active` works too. Takes
+    /// ownership of `update`, which is dropped if the adapter isn't waiting for
+    /// its initial tree.
+    ///
+    /// You must call `accesskit_macos_queued_events_raise` on the returned pointer. It can be null if the adapter wasn't waiting for its initial tree.
+    #[no_mangle]
+    pub extern "C" fn accesskit_macos_subclassing_adapter_provide_initial_tree(
+        adapter: *mut macos_subclassing_adapter,
+        update: *mut tree_update,
+    ) -> *mut macos_queued_events {
+        let update = *box_from_ptr(update);
+        let adapter = mut_from_ptr(adapter);
+        let Some(update) = adapter.validator.provide_initial_tree(update) else {
+            return ptr::null_mut();
+        };
+        let events = adapter.update_if_active(|| update);
         BoxCastPtr::to_nullable_mut_ptr(events)
     }
 
@@ -0,0 +1,297 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{
+    Action, AriaCurrent, AutoComplete, HasPopup, Invalid, Live, Orientation, Role, SortDirection,
+};
+
+/// An enum whose variants have names in the text formats of the library.
+/// The names are spelled out rather than derived from `Debug`, so that
+/// they only change on purpose, and the matches are exhaustive, so that
+/// a variant added to AccessKit has to be given a name.
+pub(crate) trait VariantName: Copy + Sized + 'static {
+    /// Every variant, in declaration order.
+    const ALL: &'static [Self];
+
+    fn name(self) -> &'static str;
+}
+
+macro_rules! variant_names {
+    ($($ty:ident { $($variant:ident => $name:literal,)* })*) => {
+        $(
+            impl VariantName for $ty {
+                const ALL: &'static [Self] = &[$($ty::$variant),*];
+
+                fn name(self) -> &'static str {
+                    match self {
+                        $($ty::$variant => $name,)*
+                    }
+                }
+            }
+        )*
+    };
+}
+
+variant_names! {
+    Role {
+        Unknown => "unknown",
+        TextRun => "text_run",
+        Cell => "cell",
+        Label => "label",
+        Image => "image",
+        Link => "link",
+        Row => "row",
+        ListItem => "list_item",
+        ListMarker => "list_marker",
+        TreeItem => "tree_item",
+        ListBoxOption => "list_box_option",
+        MenuItem => "menu_item",
+        MenuListOption => "menu_list_option",
+        Paragraph => "paragraph",
+        GenericContainer => "generic_container",
+        CheckBox => "check_box",
+        RadioButton => "radio_button",
+        TextInput => "text_input",
+        Button => "button",
+        DefaultButton => "default_button",
+        Pane => "pane",
+        RowHeader => "row_header",
+        ColumnHeader => "column_header",
+        RowGroup => "row_group",
+        List => "list",
+        Table => "table",
+        LayoutTableCell => "layout_table_cell",
+        LayoutTableRow => "layout_table_row",
+        LayoutTable => "layout_table",
+        Switch => "switch",
+        Menu => "menu",
+        MultilineTextInput => "multiline_text_input",
+        SearchInput => "search_input",
+        DateInput => "date_input",
+        DateTimeInput => "date_time_input",
+        WeekInput => "week_input",
+        MonthInput => "month_input",
+        TimeInput => "time_input",
+        EmailInput => "email_input",
+        NumberInput => "number_input",
+        PasswordInput => "password_input",
+        PhoneNumberInput => "phone_number_input",
+        UrlInput => "url_input",
+        Abbr => "abbr",
+        Alert => "alert",
+        AlertDialog => "alert_dialog",
+        Application => "application",
+        Article => "article",
+        Audio => "audio",
+        Banner => "banner",
+        Blockquote => "blockquote",
+        Canvas => "canvas",
+        Caption => "caption",
+        Caret => "caret",
+        Code => "code",
+        ColorWell => "color_well",
+        ComboBox => "combo_box",
+        EditableComboBox => "editable_combo_box",
+        Complementary => "complementary",
+        Comment => "comment",
+        ContentDeletion => "content_deletion",
+        ContentInsertion => "content_insertion",
+        ContentInfo => "content_info",
+        Definition => "definition",
+        DescriptionList => "description_list",
+        Details => "details",
+        Dialog => "dialog",
+        DisclosureTriangle => "disclosure_triangle",
+        Document => "document",
+        EmbeddedObject => "embedded_object",
+        Emphasis => "emphasis",
+        Feed => "feed",
+        FigureCaption => "figure_caption",
+        Figure => "figure",
+        Footer => "footer",
+        Form => "form",
+        Grid => "grid",
+        GridCell => "grid_cell",
+        Group => "group",
+        Header => "header",
+        Heading => "heading",
+        Iframe => "iframe",
+        IframePresentational => "iframe_presentational",
+        ImeCandidate => "ime_candidate",
+        Keyboard => "keyboard",
+        Legend => "legend",
+        LineBreak => "line_break",
+        ListBox => "list_box",
+        Log => "log",
+        Main => "main",
+        Mark => "mark",
+        Marquee => "marquee",
+        Math => "math",
+        MenuBar => "menu_bar",
+        MenuItemCheckBox => "menu_item_check_box",
+        MenuItemRadio => "menu_item_radio",
+        MenuListPopup => "menu_list_popup",
+        Meter => "meter",
+        Navigation => "navigation",
+        Note => "note",
+        PluginObject => "plugin_object",
+        ProgressIndicator => "progress_indicator",
+        RadioGroup => "radio_group",
+        Region => "region",
+        RootWebArea => "root_web_area",
+        Ruby => "ruby",
+        RubyAnnotation => "ruby_annotation",
+        ScrollBar => "scroll_bar",
+        ScrollView => "scroll_view",
+        Search => "search",
+        Section => "section",
+        SectionFooter => "section_footer",
+        SectionHeader => "section_header",
+        Slider => "slider",
+        SpinButton => "spin_button",
+        Splitter => "splitter",
+        Status => "status",
+        Strong => "strong",
+        Suggestion => "suggestion",
+        SvgRoot => "svg_root",
+        Tab => "tab",
+        TabList => "tab_list",
+        TabPanel => "tab_panel",
+        Term => "term",
+        Time => "time",
+        Timer => "timer",
+        TitleBar => "title_bar",
+        Toolbar => "toolbar",
+        Tooltip => "tooltip",
+        Tree => "tree",
+        TreeGrid => "tree_grid",
+        Video => "video",
+        WebView => "web_view",
+        Window => "window",
+        PdfActionableHighlight => "pdf_actionable_highlight",
+        PdfRoot => "pdf_root",
+        GraphicsDocument => "graphics_document",
+        GraphicsObject => "graphics_object",
+        GraphicsSymbol => "graphics_symbol",
+        DocAbstract => "doc_abstract",
+        DocAcknowledgements => "doc_acknowledgements",
+        DocAfterword => "doc_afterword",
+        DocAppendix => "doc_appendix",
+        DocBackLink => "doc_back_link",
+        DocBiblioEntry => "doc_biblio_entry",
+        DocBibliography => "doc_bibliography",
+        DocBiblioRef => "doc_biblio_ref",
+        DocChapter => "doc_chapter",
+        DocColophon => "doc_colophon",
+        DocConclusion => "doc_conclusion",
+        DocCover => "doc_cover",
+        DocCredit => "doc_credit",
+        DocCredits => "doc_credits",
+        DocDedication => "doc_dedication",
+        DocEndnote => "doc_endnote",
+        DocEndnotes => "doc_endnotes",
+        DocEpigraph => "doc_epigraph",
+        DocEpilogue => "doc_epilogue",
+        DocErrata => "doc_errata",
+        DocExample => "doc_example",
+        DocFootnote => "doc_footnote",
+        DocForeword => "doc_foreword",
+        DocGlossary => "doc_glossary",
+        DocGlossRef => "doc_gloss_ref",
+        DocIndex => "doc_index",
+        DocIntroduction => "doc_introduction",
+        DocNoteRef => "doc_note_ref",
+        DocNotice => "doc_notice",
+        DocPageBreak => "doc_page_break",
+        DocPageFooter => "doc_page_footer",
+        DocPageHeader => "doc_page_header",
+        DocPageList => "doc_page_list",
+        DocPart => "doc_part",
+        DocPreface => "doc_preface",
+        DocPrologue => "doc_prologue",
+        DocPullquote => "doc_pullquote",
+        DocQna => "doc_qna",
+        DocSubtitle => "doc_subtitle",
+        DocTip => "doc_tip",
+        DocToc => "doc_toc",
+        ListGrid => "list_grid",
+        Terminal => "terminal",
+    }
+    Action {
+        Click => "click",
+        Focus => "focus",
+        Blur => "blur",
+        Collapse => "collapse",
+        Expand => "expand",
+        CustomAction => "custom_action",
+        Decrement => "decrement",
+        Increment => "increment",
+        HideTooltip => "hide_tooltip",
+        ShowTooltip => "show_tooltip",
+        ReplaceSelectedText => "replace_selected_text",
+        ScrollDown => "scroll_down",
+        ScrollLeft => "scroll_left",
+        ScrollRight => "scroll_right",
+        ScrollUp => "scroll_up",
+        ScrollIntoView => "scroll_into_view",
+        ScrollToPoint => "scroll_to_point",
+        SetScrollOffset => "set_scroll_offset",
+        SetTextSelection => "set_text_selection",
+        SetSequentialFocusNavigationStartingPoint => "set_sequential_focus_navigation_starting_point",
+        SetValue => "set_value",
+        ShowContextMenu => "show_context_menu",
+    }
+    AriaCurrent {
+        False => "false",
+        True => "true",
+        Page => "page",
+        Step => "step",
+        Location => "location",
+        Date => "date",
+        Time => "time",
+    }
+    AutoComplete {
+        Inline => "inline",
+        List => "list",
+        Both => "both",
+    }
+    HasPopup {
+        Menu => "menu",
+        Listbox => "listbox",
+        Tree => "tree",
+        Grid => "grid",
+        Dialog => "dialog",
+    }
+    Invalid {
+        True => "true",
+        Grammar => "grammar",
+        Spelling => "spelling",
+    }
+    Live {
+        Off => "off",
+        Polite => "polite",
+        Assertive => "assertive",
+    }
+    Orientation {
+        Horizontal => "horizontal",
+        Vertical => "vertical",
+    }
+    SortDirection {
+        Ascending => "ascending",
+        Descending => "descending",
+        Other => "other",
+    }
+}
+
+/// The name of an enum variant in lower snake case.
+pub(crate) fn variant_name(value: impl VariantName) -> &'static str {
+    value.name()
+}
+
+/// The name of a role in lower snake case, as in the suffix of the
+/// corresponding `ACCESSKIT_ROLE_*` constant.
+pub(crate) fn role_name(role: Role) -> &'static str {
+    role.name()
+}
@@ -0,0 +1,159 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{TreeId, TreeUpdate};
+
+use crate::{
+    box_from_ptr, merge_from, mut_from_ptr, node_id, tree_snapshot, tree_update, tree_update_queue,
+    BoxCastPtr, CastPtr,
+};
+
+pub(crate) struct TreeUpdatePool {
+    updates: Vec<TreeUpdate>,
+    max_updates: usize,
+}
+
+impl TreeUpdatePool {
+    fn release(&mut self, mut update: TreeUpdate) {
+        if self.updates.len() < self.max_updates {
+            update.nodes.clear();
+            update.tree = None;
+            self.updates.push(update);
+        }
+    }
+}
+
+/// Tree updates whose storage is kept around to be filled again.
+///
+/// Updates come back to the pool when they are released, when they are
+/// merged into another one using `accesskit_tree_update_pool_merge` or
+/// `accesskit_tree_update_pool_push_to_queue`, when a queue is cleared
+/// using `accesskit_tree_update_pool_clear_queue`, and when they are
+/// applied to a snapshot using `accesskit_tree_update_pool_update_snapshot`.
+///
+/// Updates given to a platform adapter don't come back: the adapter frees
+/// them once applied, so the list of nodes of each of them is allocated
+/// again.
+pub struct tree_update_pool {
+    _private: [u8; 0],
+}
+
+impl CastPtr for tree_update_pool {
+    type RustType = TreeUpdatePool;
+}
+
+impl BoxCastPtr for tree_update_pool {}
+
+impl tree_update_pool {
+    /// Creates a pool that keeps at most `max_updates` released updates.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_pool_new(max_updates: usize) -> *mut tree_update_pool {
+        BoxCastPtr::to_mut_ptr(TreeUpdatePool {
+            updates: Vec::with_capacity(max_updates),
+            max_updates,
+        })
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_pool_free(pool: *mut tree_update_pool) {
+        drop(box_from_ptr(pool));
+    }
+
+    /// Returns an empty update for the root tree, reusing one previously
+    /// released to the pool if there is any, so that pushing no more nodes
+    /// than it held before doesn't allocate memory for the list of nodes.
+    ///
+    /// The returned update must be freed, released to the pool or given
+    /// to a function taking ownership of it.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_pool_acquire(
+        pool: *mut tree_update_pool,
+        focus: node_id,
+    ) -> *mut tree_update {
+        let pool = mut_from_ptr(pool);
+        let update = match pool.updates.pop() {
+            Some(mut update) => {
+                update.tree_id = TreeId::ROOT;
+                update.focus = focus.into();
+                update
+            }
+            None => TreeUpdate {
+                nodes: Vec::new(),
+                tree: None,
+                tree_id: TreeId::ROOT,
+                focus: focus.into(),
+            },
+        };
+        BoxCastPtr::to_mut_ptr(update)
+    }
+
+    /// Clears `update` and keeps it for a later call to
+    /// `accesskit_tree_update_pool_acquire`, or frees it if the pool is full.
+    /// Takes ownership of `update`.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_pool_release(
+        pool: *mut tree_update_pool,
+        update: *mut tree_update,
+    ) {
+        let pool = mut_from_ptr(pool);
+        pool.release(*box_from_ptr(update));
+    }
+
+    /// Same as `accesskit_tree_update_merge`, except that `src` is released
+    /// to the pool instead of being freed.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_pool_merge(
+        pool: *mut tree_update_pool,
+        dst: *mut tree_update,
+        src: *mut tree_update,
+    ) -> bool {
+        let pool = mut_from_ptr(pool);
+        let dst = mut_from_ptr(dst);
+        let mut src = box_from_ptr(src);
+        let merged = merge_from(dst, &mut src);
+        pool.release(*src);
+        merged
+    }
+
+    /// Same as `accesskit_tree_update_queue_push`, except that what is left
+    /// of `update` is released to the pool instead of being freed.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_pool_push_to_queue(
+        pool: *mut tree_update_pool,
+        queue: *mut tree_update_queue,
+        update: *mut tree_update,
+    ) -> bool {
+        let pool = mut_from_ptr(pool);
+        let queue = mut_from_ptr(queue);
+        queue.push(*box_from_ptr(update), |update| pool.release(update))
+    }
+
+    /// Same as `accesskit_tree_update_queue_clear`, except that the pending
+    /// update is released to the pool instead of being freed.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_pool_clear_queue(
+        pool: *mut tree_update_pool,
+        queue: *mut tree_update_queue,
+    ) {
+        let pool = mut_from_ptr(pool);
+        let queue = mut_from_ptr(queue);
+        queue.clear(|update| pool.release(update));
+    }
+
+    /// Same as `accesskit_tree_snapshot_update`, except that `update` is
+    /// released to the pool instead of being freed.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_pool_update_snapshot(
+        pool: *mut tree_update_pool,
+        snapshot: *mut tree_snapshot,
+        update: *mut tree_update,
+    ) {
+        let pool = mut_from_ptr(pool);
+        let snapshot = mut_from_ptr(snapshot);
+        let mut update = box_from_ptr(update);
+        snapshot.apply_from(&mut update);
+        pool.release(*update);
+    }
+}
@@ -0,0 +1,286 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{NodeId, TreeUpdate};
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::{c_char, c_void, CStr},
+    ptr,
+};
+
+use crate::{
+    box_from_ptr, fnv1a, mut_from_ptr, node_id, ref_from_ptr, tree_update, BoxCastPtr, CastPtr,
+};
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Key {
+    Path(Vec<u8>),
+    Object(usize, u64),
+}
+
+struct Entry {
+    key: Key,
+    /// `None` once the ID has been released.
+    object: Option<*mut c_void>,
+}
+
+#[derive(Default)]
+pub(crate) struct NodeIdRegistry {
+    ids: HashMap<Key, NodeId>,
+    entries: HashMap<NodeId, Entry>,
+    released: HashSet<NodeId>,
+    free_object_ids: Vec<NodeId>,
+    next_object_id: u64,
+    collision_count: usize,
+    /// The children of every node in the tree as of the last noted
+    /// update, and the number of nodes listing each of them.
+    children: HashMap<NodeId, Vec<NodeId>>,
+    parent_count: HashMap<NodeId, usize>,
+    root: Option<NodeId>,
+}
+
+impl NodeIdRegistry {
+    fn is_taken(&self, id: NodeId) -> bool {
+        self.entries.contains_key(&id) || self.children.contains_key(&id)
+    }
+
+    fn register(&mut self, key: Key, object: *mut c_void) -> NodeId {
+        if let Some(&id) = self.ids.get(&key) {
+            self.entries.get_mut(&id).unwrap().object = Some(object);
+            self.released.remove(&id);
+            return id;
+        }
+        let id = match &key {
+            Key::Path(path) => {
+                let mut id = NodeId(fnv1a(path));
+                if self.is_taken(id) {
+                    self.collision_count += 1;
+                    while self.is_taken(id) {
+                        id = NodeId(id.0.wrapping_add(1));
+                    }
+                }
+                id
+            }
+            Key::Object(..) => loop {
+                let id = match self.free_object_ids.pop() {
+                    Some(id) => id,
+                    None => {
+                        self.next_object_id += 1;
+                        NodeId(self.next_object_id)
+                    }
+                };
+                if !self.is_taken(id) {
+                    break id;
+                }
+            },
+        };
+        self.ids.insert(key.clone(), id);
+        self.entries.insert(
+            id,
+            Entry {
+                key,
+                object: Some(object),
+            },
+        );
+        id
+    }
+
+    fn recycle(&mut self, id: NodeId) {
+        let entry = self.entries.remove(&id).unwrap();
+        self.ids.remove(&entry.key);
+        if let Key::Object(..) = entry.key {
+            self.free_object_ids.push(id);
+        }
+    }
+
+    fn release(&mut self, id: NodeId) -> bool {
+        match self.entries.get_mut(&id) {
+            Some(entry) if entry.object.is_some() => {
+                entry.object = None;
+                if self.children.contains_key(&id) {
+                    self.released.insert(id);
+                } else {
+                    self.recycle(id);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes `id` and the descendants only it refers to from the known
+    /// tree, the way an adapter does when they are no longer attached.
+    fn detach(&mut self, id: NodeId) {
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            for child in self.children.remove(&id).unwrap_or_default() {
+                let count = self.parent_count.get_mut(&child).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    self.parent_count.remove(&child);
+                    if Some(child) != self.root {
+                        stack.push(child);
+                    }
+                }
+            }
+        }
+    }
+
+    fn note_update(&mut self, update: &TreeUpdate) {
+        let mut previous_children = Vec::new();
+        for (id, node) in &update.nodes {
+            for child in node.children() {
+                *self.parent_count.entry(*child).or_default() += 1;
+            }
+            let old = self.children.insert(*id, node.children().to_vec());
+            previous_children.extend(old.unwrap_or_default());
+        }
+        let mut orphans = Vec::new();
+        for child in previous_children {
+            let count = self.parent_count.get_mut(&child).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                self.parent_count.remove(&child);
+                orphans.push(child);
+            }
+        }
+        if let Some(tree) = &update.tree {
+            if let Some(old_root) = self.root.replace(tree.root) {
+                if !self.parent_count.contains_key(&old_root) {
+                    orphans.push(old_root);
+                }
+            }
+        }
+        for orphan in orphans {
+            if Some(orphan) != self.root {
+                self.detach(orphan);
+            }
+        }
+        let recyclable: Vec<NodeId> = self
+            .released
+            .iter()
+            .copied()
+            .filter(|id| !self.children.contains_key(id))
+            .collect();
+        for id in recyclable {
+            self.released.remove(&id);
+            self.recycle(id);
+        }
+    }
+}
+
+/// Allocates node IDs for the objects of an application and maps them back.
+///
+/// IDs come either from string paths, which give the same ID from one run
+/// to the next, or from object pointers paired with a generation number
+/// that distinguishes objects allocated at the same address. Once released,
+/// an ID isn't handed out again until an update noted with
+/// `accesskit_node_id_registry_note_update` has removed it from the tree,
+/// so that adapters never mistake a new object for an old one.
+pub struct node_id_registry {
+    _private: [u8; 0],
+}
+
+impl CastPtr for node_id_registry {
+    type RustType = NodeIdRegistry;
+}
+
+impl BoxCastPtr for node_id_registry {}
+
+impl node_id_registry {
+    #[no_mangle]
+    pub extern "C" fn accesskit_node_id_registry_new() -> *mut node_id_registry {
+        BoxCastPtr::to_mut_ptr(NodeIdRegistry::default())
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_node_id_registry_free(registry: *mut node_id_registry) {
+        drop(box_from_ptr(registry));
+    }
+
+    /// Returns the ID of the object at `path`, associating it with `object`.
+    /// The ID is a hash of the path, unless it is taken by another path or
+    /// object, in which case the next free ID is used and the collision is
+    /// counted. Calling this function again with the same path returns the
+    /// same ID, and replaces the object, until the ID is recycled.
+    #[no_mangle]
+    pub extern "C" fn accesskit_node_id_registry_id_for_path(
+        registry: *mut node_id_registry,
+        path: *const c_char,
+        object: *mut c_void,
+    ) -> node_id {
+        let registry = mut_from_ptr(registry);
+        let path = unsafe { CStr::from_ptr(path) };
+        registry
+            .register(Key::Path(path.to_bytes().to_vec()), object)
+            .into()
+    }
+
+    /// Returns the ID of `object`, allocating one if this pointer and
+    /// generation weren't seen before. `generation` must change whenever
+    /// a new object is allocated at the address of a destroyed one.
+    #[no_mangle]
+    pub extern "C" fn accesskit_node_id_registry_id_for_object(
+        registry: *mut node_id_registry,
+        object: *mut c_void,
+        generation: u64,
+    ) -> node_id {
+        let registry = mut_from_ptr(registry);
+        registry
+            .register(Key::Object(object as usize, generation), object)
+            .into()
+    }
+
+    /// Returns the object associated with `id`, for instance the target
+    /// of an action request, or null if the ID is unknown or was released.
+    #[no_mangle]
+    pub extern "C" fn accesskit_node_id_registry_object(
+        registry: *const node_id_registry,
+        id: node_id,
+    ) -> *mut c_void {
+        let registry = ref_from_ptr(registry);
+        registry
+            .entries
+            .get(&id.into())
+            .and_then(|entry| entry.object)
+            .unwrap_or(ptr::null_mut())
+    }
+
+    /// Forgets the object associated with `id`, to be called when it is
+    /// destroyed. The ID is recycled right away if it isn't in the tree,
+    /// or else once a noted update has removed it. Returns false if the ID
+    /// is unknown or was already released.
+    #[no_mangle]
+    pub extern "C" fn accesskit_node_id_registry_release(
+        registry: *mut node_id_registry,
+        id: node_id,
+    ) -> bool {
+        let registry = mut_from_ptr(registry);
+        registry.release(id.into())
+    }
+
+    /// Records which nodes are in the tree after `update` is applied. Call
+    /// this with every update, including initial trees, right before giving
+    /// it to an adapter.
+    #[no_mangle]
+    pub extern "C" fn accesskit_node_id_registry_note_update(
+        registry: *mut node_id_registry,
+        update: *const tree_update,
+    ) {
+        let registry = mut_from_ptr(registry);
+        let update = ref_from_ptr(update);
+        registry.note_update(update);
+    }
+
+    /// Returns the number of paths whose hash was already taken when they
+    /// were registered.
+    #[no_mangle]
+    pub extern "C" fn accesskit_node_id_registry_collision_count(
+        registry: *const node_id_registry,
+    ) -> usize {
+        let registry = ref_from_ptr(registry);
+        registry.collision_count
+    }
+}
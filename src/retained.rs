@@ -0,0 +1,309 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{Node, NodeId, Tree, TreeId, TreeUpdate};
+use std::{
+    collections::{HashMap, HashSet},
+    ptr,
+};
+
+use crate::{
+    box_from_ptr, mut_from_ptr, node, node_id, ref_from_ptr, tree, tree_update,
+    tree_update_factory_userdata, BoxCastPtr, CastPtr,
+};
+
+pub(crate) struct RetainedTree {
+    tree: Tree,
+    focus: NodeId,
+    nodes: HashMap<NodeId, Node>,
+    dirty: HashSet<NodeId>,
+    /// The nodes as of the last produced update, limited to the ones
+    /// reachable from the root, which are the ones an adapter keeps.
+    submitted: HashMap<NodeId, Node>,
+    submitted_tree: Option<Tree>,
+    /// The parent of each node reachable from the root, as of the last
+    /// produced update.
+    parents: HashMap<NodeId, NodeId>,
+}
+
+impl RetainedTree {
+    fn reachable_ids(&self) -> Vec<NodeId> {
+        let mut ids = Vec::new();
+        let mut stack = vec![self.tree.root];
+        while let Some(id) = stack.pop() {
+            if let Some(node) = self.nodes.get(&id) {
+                ids.push(id);
+                stack.extend(node.children().iter().rev());
+            }
+        }
+        ids
+    }
+
+    fn parent_id(&self, id: NodeId) -> Option<NodeId> {
+        let lists_child = |parent: &NodeId| {
+            self.nodes
+                .get(parent)
+                .is_some_and(|node| node.children().contains(&id))
+        };
+        match self.parents.get(&id) {
+            Some(parent) if lists_child(parent) => Some(*parent),
+            _ => self
+                .nodes
+                .keys()
+                .find(|parent| lists_child(parent))
+                .copied(),
+        }
+    }
+
+    fn remove(&mut self, id: NodeId) -> bool {
+        if self.nodes.remove(&id).is_none() {
+            return false;
+        }
+        if let Some(parent_id) = self.parent_id(id) {
+            let parent = self.nodes.get_mut(&parent_id).unwrap();
+            let children: Vec<NodeId> = parent
+                .children()
+                .iter()
+                .copied()
+                .filter(|child| *child != id)
+                .collect();
+            parent.set_children(children);
+            self.dirty.insert(parent_id);
+        }
+        self.dirty.remove(&id);
+        true
+    }
+
+    /// Returns the nodes that changed since the last update, along with
+    /// the ones that weren't reachable from the root back then, in
+    /// document order. If `full` is set, all reachable nodes are included.
+    fn take_update(&mut self, full: bool) -> TreeUpdate {
+        let mut nodes = Vec::new();
+        let mut submitted = HashMap::new();
+        let mut parents = HashMap::new();
+        for id in self.reachable_ids() {
+            let node = &self.nodes[&id];
+            for child in node.children() {
+                parents.insert(*child, id);
+            }
+            let previous = self.submitted.remove(&id);
+            let changed = match &previous {
+                Some(previous) => self.dirty.contains(&id) && previous != node,
+                None => true,
+            };
+            if full || changed {
+                nodes.push((id, node.clone()));
+            }
+            submitted.insert(
+                id,
+                if changed {
+                    node.clone()
+                } else {
+                    previous.unwrap()
+                },
+            );
+        }
+        let tree =
+            (full || self.submitted_tree.as_ref() != Some(&self.tree)).then(|| self.tree.clone());
+        self.submitted = submitted;
+        self.submitted_tree = Some(self.tree.clone());
+        self.parents = parents;
+        self.dirty.clear();
+        TreeUpdate {
+            nodes,
+            tree,
+            tree_id: TreeId::ROOT,
+            focus: self.focus,
+        }
+    }
+}
+
+/// A copy of the accessibility tree, maintained by the application,
+/// from which the library computes tree updates.
+///
+/// A retained tree isn't synchronized, so it must only be used on the
+/// thread that changes it. If the activation handler of an adapter runs
+/// on another thread, return null from it and give the full update to the
+/// `provide_initial_tree` function of the adapter from that thread.
+pub struct retained_tree {
+    _private: [u8; 0],
+}
+
+impl CastPtr for retained_tree {
+    type RustType = RetainedTree;
+}
+
+impl BoxCastPtr for retained_tree {}
+
+impl retained_tree {
+    /// Creates an empty retained tree. The focus is initially on the root.
+    /// Takes ownership of `tree`.
+    #[no_mangle]
+    pub extern "C" fn accesskit_retained_tree_new(tree: *mut tree) -> *mut retained_tree {
+        let tree = *box_from_ptr(tree);
+        BoxCastPtr::to_mut_ptr(RetainedTree {
+            focus: tree.root,
+            tree,
+            nodes: HashMap::new(),
+            dirty: HashSet::new(),
+            submitted: HashMap::new(),
+            submitted_tree: None,
+            parents: HashMap::new(),
+        })
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_retained_tree_free(tree: *mut retained_tree) {
+        drop(box_from_ptr(tree));
+    }
+
+    /// Takes ownership of `tree`.
+    #[no_mangle]
+    pub extern "C" fn accesskit_retained_tree_set_tree(
+        retained_tree: *mut retained_tree,
+        tree: *mut tree,
+    ) {
+        let retained_tree = mut_from_ptr(retained_tree);
+        retained_tree.tree = *box_from_ptr(tree);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_retained_tree_set_focus(tree: *mut retained_tree, focus: node_id) {
+        let tree = mut_from_ptr(tree);
+        tree.focus = focus.into();
+    }
+
+    /// Adds a node or replaces the node with the same ID.
+    /// Takes ownership of `node`.
+    #[no_mangle]
+    pub extern "C" fn accesskit_retained_tree_set_node(
+        tree: *mut retained_tree,
+        id: node_id,
+        node: *mut node,
+    ) {
+        let tree = mut_from_ptr(tree);
+        let id = id.into();
+        tree.nodes.insert(id, *box_from_ptr(node));
+        tree.dirty.insert(id);
+    }
+
+    /// Adds a node and appends it to the children of `parent`. Takes
+    /// ownership of `node`.
+    ///
+    /// Returns false, freeing `node`, if there is no node with the ID
+    /// of `parent`.
+    #[no_mangle]
+    pub extern "C" fn accesskit_retained_tree_append_child(
+        tree: *mut retained_tree,
+        parent: node_id,
+        id: node_id,
+        node: *mut node,
+    ) -> bool {
+        let tree = mut_from_ptr(tree);
+        let node = box_from_ptr(node);
+        let parent = parent.into();
+        let id = id.into();
+        let Some(parent_node) = tree.nodes.get_mut(&parent) else {
+            return false;
+        };
+        parent_node.push_child(id);
+        tree.nodes.insert(id, *node);
+        tree.dirty.insert(parent);
+        tree.dirty.insert(id);
+        true
+    }
+
+    /// Returns null if there is no node with this ID.
+    /// The returned node is owned by the retained tree.
+    #[no_mangle]
+    pub extern "C" fn accesskit_retained_tree_node(
+        tree: *const retained_tree,
+        id: node_id,
+    ) -> *const node {
+        let tree = ref_from_ptr(tree);
+        match tree.nodes.get(&id.into()) {
+            Some(node) => node as *const _ as *const node,
+            None => ptr::null(),
+        }
+    }
+
+    /// Returns the node with this ID so that it can be modified in place,
+    /// and marks it as changed. Returns null if there is no such node.
+    /// The returned node is owned by the retained tree.
+    #[no_mangle]
+    pub extern "C" fn accesskit_retained_tree_node_mut(
+        tree: *mut retained_tree,
+        id: node_id,
+    ) -> *mut node {
+        let tree = mut_from_ptr(tree);
+        let id = id.into();
+        match tree.nodes.get_mut(&id) {
+            Some(node) => {
+                tree.dirty.insert(id);
+                node as *mut _ as *mut node
+            }
+            None => ptr::null_mut(),
+        }
+    }
+
+    /// Removes a node and takes it out of the children of its parent.
+    /// Its descendants are no longer part of the tree, but are kept until
+    /// removed, so that they can be attached somewhere else. Returns false
+    /// if there is no node with this ID.
+    #[no_mangle]
+    pub extern "C" fn accesskit_retained_tree_remove_node(
+        tree: *mut retained_tree,
+        id: node_id,
+    ) -> bool {
+        let tree = mut_from_ptr(tree);
+        tree.remove(id.into())
+    }
+
+    /// Returns whether nodes were modified since the last update was
+    /// produced. The next update may still be empty if the modifications
+    /// cancelled each other out.
+    #[no_mangle]
+    pub extern "C" fn accesskit_retained_tree_has_changes(tree: *const retained_tree) -> bool {
+        let tree = ref_from_ptr(tree);
+        !tree.dirty.is_empty() || tree.submitted_tree.as_ref() != Some(&tree.tree)
+    }
+
+    /// Produces the smallest update bringing a platform adapter from the
+    /// state of the previous update to the current one: the nodes that were
+    /// modified and differ from what was last submitted, including parents
+    /// whose list of children changed, and the nodes that became reachable
+    /// from the root. Nodes that aren't reachable from the root are left
+    /// out. The first update includes the whole tree.
+    ///
+    /// Every child listed by a reachable node must be in the tree, and so
+    /// must the focus.
+    #[no_mangle]
+    pub extern "C" fn accesskit_retained_tree_take_update(
+        tree: *mut retained_tree,
+    ) -> *mut tree_update {
+        let tree = mut_from_ptr(tree);
+        BoxCastPtr::to_mut_ptr(tree.take_update(false))
+    }
+
+    /// Produces an update holding the whole tree, to give to an adapter that
+    /// has just been activated. Later updates are computed from this state.
+    #[no_mangle]
+    pub extern "C" fn accesskit_retained_tree_take_full_update(
+        tree: *mut retained_tree,
+    ) -> *mut tree_update {
+        let tree = mut_from_ptr(tree);
+        BoxCastPtr::to_mut_ptr(tree.take_update(true))
+    }
+
+    /// A tree update factory calling `accesskit_retained_tree_take_update`
+    /// on the retained tree passed as userdata, to be given to the
+    /// `update_if_active` function of an adapter.
+    #[no_mangle]
+    pub extern "C" fn accesskit_retained_tree_update_factory(
+        userdata: tree_update_factory_userdata,
+    ) -> *mut tree_update {
+        Self::accesskit_retained_tree_take_update(userdata.0 as *mut retained_tree)
+    }
+}
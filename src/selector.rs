@@ -0,0 +1,353 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{Action, Node, NodeId, Toggled};
+use std::{collections::HashMap, fmt};
+
+use crate::{role_name, TreeSnapshot};
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct SelectorError {
+    pub(crate) position: usize,
+    pub(crate) message: String,
+}
+
+impl fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.position)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Combinator {
+    Child,
+    Descendant,
+}
+
+enum Filter {
+    Attribute { name: String, value: Option<String> },
+    Nth(usize),
+}
+
+struct Compound {
+    filters: Vec<Filter>,
+}
+
+pub(crate) struct Selector {
+    steps: Vec<(Combinator, Compound)>,
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, SelectorError> {
+        Err(SelectorError {
+            position: self.position,
+            message: message.into(),
+        })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), SelectorError> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            self.error(format!("expected '{}'", expected))
+        }
+    }
+
+    fn skip_whitespace(&mut self) -> bool {
+        let start = self.position;
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+        self.position != start
+    }
+
+    fn identifier(&mut self) -> Result<String, SelectorError> {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            self.bump();
+        }
+        if start == self.position {
+            return self.error("expected an identifier");
+        }
+        Ok(self.input[start..self.position].to_string())
+    }
+
+    fn value(&mut self) -> Result<String, SelectorError> {
+        if let Some(quote) = self.peek().filter(|c| *c == '"' || *c == '\'') {
+            self.bump();
+            let mut value = String::new();
+            loop {
+                match self.bump() {
+                    Some('\\') => match self.bump() {
+                        Some(c) => value.push(c),
+                        None => return self.error("unterminated string"),
+                    },
+                    Some(c) if c == quote => return Ok(value),
+                    Some(c) => value.push(c),
+                    None => return self.error("unterminated string"),
+                }
+            }
+        }
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|c| !c.is_whitespace() && !"[]>:\"'".contains(c))
+        {
+            self.bump();
+        }
+        if start == self.position {
+            return self.error("expected a value");
+        }
+        Ok(self.input[start..self.position].to_string())
+    }
+
+    fn attribute(&mut self) -> Result<Filter, SelectorError> {
+        let start = self.position;
+        let name = self.identifier()?;
+        if !is_known_attribute(&name) {
+            self.position = start;
+            return self.error(format!("unknown attribute '{}'", name));
+        }
+        let value = if self.eat('=') {
+            Some(self.value()?)
+        } else {
+            None
+        };
+        Ok(Filter::Attribute { name, value })
+    }
+
+    fn compound(&mut self) -> Result<Compound, SelectorError> {
+        let mut filters = Vec::new();
+        let universal = self.eat('*');
+        if !universal && self.peek().is_some_and(|c| c.is_ascii_alphanumeric()) {
+            let start = self.position;
+            let name = self.identifier()?;
+            if self.peek() == Some('=') {
+                self.position = start;
+                filters.push(self.attribute()?);
+            } else {
+                filters.push(Filter::Attribute {
+                    name: "role".into(),
+                    value: Some(name),
+                });
+            }
+        }
+        loop {
+            if self.eat('[') {
+                self.skip_whitespace();
+                filters.push(self.attribute()?);
+                self.skip_whitespace();
+                self.expect(']')?;
+            } else if self.eat(':') {
+                let start = self.position;
+                let name = self.identifier()?;
+                if name != "nth" {
+                    self.position = start;
+                    return self.error(format!("unknown pseudo-class '{}'", name));
+                }
+                self.expect('(')?;
+                let start = self.position;
+                while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    self.bump();
+                }
+                match self.input[start..self.position].parse::<usize>() {
+                    Ok(n) if n > 0 => filters.push(Filter::Nth(n)),
+                    _ => {
+                        self.position = start;
+                        return self.error("expected a positive integer");
+                    }
+                }
+                self.expect(')')?;
+            } else {
+                break;
+            }
+        }
+        if filters.is_empty() && !universal {
+            return self.error("expected a selector");
+        }
+        Ok(Compound { filters })
+    }
+
+    fn selector(&mut self) -> Result<Selector, SelectorError> {
+        let mut steps = Vec::new();
+        self.skip_whitespace();
+        let mut combinator = Combinator::Descendant;
+        loop {
+            steps.push((combinator, self.compound()?));
+            let had_whitespace = self.skip_whitespace();
+            if self.peek().is_none() {
+                break;
+            }
+            combinator = if self.eat('>') {
+                self.skip_whitespace();
+                Combinator::Child
+            } else if had_whitespace {
+                Combinator::Descendant
+            } else {
+                return self.error("unexpected character");
+            };
+        }
+        Ok(Selector { steps })
+    }
+}
+
+fn is_known_attribute(name: &str) -> bool {
+    matches!(
+        name,
+        "role"
+            | "id"
+            | "author_id"
+            | "name"
+            | "label"
+            | "value"
+            | "description"
+            | "placeholder"
+            | "tooltip"
+            | "class_name"
+            | "toggled"
+            | "checked"
+            | "expanded"
+            | "selected"
+            | "disabled"
+            | "hidden"
+            | "required"
+            | "read_only"
+            | "focused"
+            | "focusable"
+    )
+}
+
+fn matches_text(actual: Option<&str>, expected: &Option<String>) -> bool {
+    match expected {
+        Some(expected) => actual == Some(expected.as_str()),
+        None => actual.is_some(),
+    }
+}
+
+fn matches_flag(actual: bool, expected: &Option<String>) -> bool {
+    match expected.as_deref() {
+        None | Some("true") => actual,
+        Some("false") => !actual,
+        Some(_) => false,
+    }
+}
+
+fn matches_attribute(
+    snapshot: &TreeSnapshot,
+    id: NodeId,
+    node: &Node,
+    name: &str,
+    value: &Option<String>,
+) -> bool {
+    match name {
+        "role" => value
+            .as_deref()
+            .is_none_or(|value| role_name(node.role()) == value),
+        "id" => value
+            .as_deref()
+            .is_none_or(|value| value.parse() == Ok(id.0)),
+        "author_id" => matches_text(node.author_id(), value),
+        "name" => matches_text(snapshot.name(id).as_deref(), value),
+        "label" => matches_text(node.label(), value),
+        "value" => matches_text(node.value(), value),
+        "description" => matches_text(node.description(), value),
+        "placeholder" => matches_text(node.placeholder(), value),
+        "tooltip" => matches_text(node.tooltip(), value),
+        "class_name" => matches_text(node.class_name(), value),
+        "toggled" | "checked" => {
+            let toggled = match node.toggled() {
+                Some(Toggled::True) => "true",
+                Some(Toggled::Mixed) => "mixed",
+                Some(Toggled::False) => "false",
+                None => return value.as_deref() == Some("false"),
+            };
+            value.as_deref().unwrap_or("true") == toggled
+        }
+        "expanded" => matches_flag(node.is_expanded() == Some(true), value),
+        "selected" => matches_flag(node.is_selected() == Some(true), value),
+        "disabled" => matches_flag(node.is_disabled(), value),
+        "hidden" => matches_flag(node.is_hidden(), value),
+        "required" => matches_flag(node.is_required(), value),
+        "read_only" => matches_flag(node.is_read_only(), value),
+        "focused" => matches_flag(snapshot.focus_id() == id, value),
+        "focusable" => matches_flag(node.supports_action(Action::Focus), value),
+        _ => false,
+    }
+}
+
+impl Selector {
+    pub(crate) fn parse(input: &str) -> Result<Self, SelectorError> {
+        Parser { input, position: 0 }.selector()
+    }
+
+    /// Returns the matching nodes in document order.
+    pub(crate) fn query(&self, snapshot: &TreeSnapshot) -> Vec<NodeId> {
+        let mut order = Vec::new();
+        snapshot.visit(|id, _, _| order.push(id));
+        let index: HashMap<NodeId, usize> =
+            order.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let mut current: Option<Vec<NodeId>> = None;
+        for (combinator, compound) in &self.steps {
+            let candidates: Vec<NodeId> = match &current {
+                None => order.clone(),
+                Some(context) => {
+                    let mut candidates = Vec::new();
+                    for id in context {
+                        match combinator {
+                            Combinator::Child => candidates.extend(snapshot.child_ids(*id)),
+                            Combinator::Descendant => {
+                                candidates.extend(snapshot.descendant_ids(*id))
+                            }
+                        }
+                    }
+                    candidates.sort_by_key(|id| index[id]);
+                    candidates.dedup();
+                    candidates
+                }
+            };
+            let mut matches = candidates;
+            for filter in &compound.filters {
+                match filter {
+                    Filter::Attribute { name, value } => matches.retain(|id| {
+                        let node = snapshot.node(*id).unwrap();
+                        matches_attribute(snapshot, *id, node, name, value)
+                    }),
+                    Filter::Nth(n) => {
+                        matches = matches.get(n - 1).copied().into_iter().collect();
+                    }
+                }
+            }
+            current = Some(matches);
+        }
+        current.unwrap_or_default()
+    }
+}
@@ -0,0 +1,309 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{Node, NodeId, Role, Tree, TreeId, TreeUpdate};
+use std::{
+    collections::{HashMap, HashSet},
+    ptr,
+};
+
+use crate::{
+    box_from_ptr, mut_from_ptr, node, node_id, opt_node_id, ref_from_ptr, tree_update, BoxCastPtr,
+    CastPtr,
+};
+
+/// The state of a tree after applying a full tree update followed by zero
+/// or more incremental ones, the same way a platform adapter does.
+#[derive(Clone)]
+pub(crate) struct TreeSnapshot {
+    tree: Tree,
+    tree_id: TreeId,
+    focus: NodeId,
+    nodes: HashMap<NodeId, Node>,
+    parents: HashMap<NodeId, NodeId>,
+}
+
+impl TreeSnapshot {
+    /// Returns `None` if the update doesn't contain tree information.
+    pub(crate) fn new(update: TreeUpdate) -> Option<Self> {
+        let tree = update.tree.clone()?;
+        let mut snapshot = Self {
+            tree,
+            tree_id: update.tree_id,
+            focus: update.focus,
+            nodes: HashMap::new(),
+            parents: HashMap::new(),
+        };
+        snapshot.apply(update);
+        Some(snapshot)
+    }
+
+    /// Nodes that are no longer reachable from the root are dropped.
+    pub(crate) fn apply(&mut self, mut update: TreeUpdate) {
+        self.apply_from(&mut update);
+    }
+
+    /// Same as `apply`, except that the nodes are moved out of `update`,
+    /// which keeps the storage of its list of nodes.
+    pub(crate) fn apply_from(&mut self, update: &mut TreeUpdate) {
+        if let Some(tree) = update.tree.take() {
+            self.tree = tree;
+        }
+        self.focus = update.focus;
+        self.nodes.extend(update.nodes.drain(..));
+        self.parents.clear();
+        let mut reachable = HashSet::new();
+        let mut stack = vec![self.tree.root];
+        while let Some(id) = stack.pop() {
+            if !reachable.insert(id) {
+                continue;
+            }
+            if let Some(node) = self.nodes.get(&id) {
+                // A child list that leads back to the root or to a node
+                // already reached forms a cycle, which is cut there.
+                for child in node.children() {
+                    if *child != self.tree.root
+                        && !reachable.contains(child)
+                        && self.nodes.contains_key(child)
+                        && !self.parents.contains_key(child)
+                    {
+                        self.parents.insert(*child, id);
+                        stack.push(*child);
+                    }
+                }
+            }
+        }
+        self.nodes.retain(|id, _| reachable.contains(id));
+    }
+
+    pub(crate) fn tree_id(&self) -> TreeId {
+        self.tree_id
+    }
+
+    pub(crate) fn root_id(&self) -> NodeId {
+        self.tree.root
+    }
+
+    pub(crate) fn focus_id(&self) -> NodeId {
+        self.focus
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub(crate) fn node(&self, id: NodeId) -> Option<&Node> {
+        self.nodes.get(&id)
+    }
+
+    pub(crate) fn parent_id(&self, id: NodeId) -> Option<NodeId> {
+        self.parents.get(&id).copied()
+    }
+
+    /// The children of a node that are actually present in the tree.
+    pub(crate) fn child_ids(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes
+            .get(&id)
+            .map_or(&[][..], |node| node.children())
+            .iter()
+            .copied()
+            .filter(move |child| self.parents.get(child) == Some(&id))
+    }
+
+    /// Ancestors of the node, from its parent up to the root.
+    pub(crate) fn ancestor_ids(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let mut current = id;
+        std::iter::from_fn(move || {
+            current = self.parent_id(current)?;
+            Some(current)
+        })
+    }
+
+    /// All descendants of a node, in document order.
+    pub(crate) fn descendant_ids(&self, id: NodeId) -> Vec<NodeId> {
+        let mut descendants = Vec::new();
+        let mut stack: Vec<NodeId> = self.child_ids(id).collect();
+        stack.reverse();
+        while let Some(id) = stack.pop() {
+            descendants.push(id);
+            let start = stack.len();
+            stack.extend(self.child_ids(id));
+            stack[start..].reverse();
+        }
+        descendants
+    }
+
+    /// Calls `f` with each node and its depth, in document order.
+    pub(crate) fn visit(&self, mut f: impl FnMut(NodeId, &Node, usize)) {
+        let mut stack = vec![(self.tree.root, 0)];
+        while let Some((id, depth)) = stack.pop() {
+            let Some(node) = self.nodes.get(&id) else {
+                continue;
+            };
+            f(id, node, depth);
+            let start = stack.len();
+            stack.extend(self.child_ids(id).map(|child| (child, depth + 1)));
+            stack[start..].reverse();
+        }
+    }
+
+    /// Computes the accessible name of a node: its label, the names of the
+    /// nodes labelling it, or for some roles, the text of its descendants.
+    pub(crate) fn name(&self, id: NodeId) -> Option<String> {
+        let node = self.node(id)?;
+        if let Some(label) = node.label() {
+            return Some(label.to_string());
+        }
+        let labelled_by: Vec<String> = node
+            .labelled_by()
+            .iter()
+            .filter_map(|id| self.text_content(*id))
+            .collect();
+        if !labelled_by.is_empty() {
+            return Some(labelled_by.join(" "));
+        }
+        if supports_name_from_contents(node.role()) {
+            let contents: Vec<String> = self
+                .child_ids(id)
+                .filter_map(|child| self.text_content(child))
+                .collect();
+            if !contents.is_empty() {
+                return Some(contents.join(" "));
+            }
+        }
+        None
+    }
+
+    fn text_content(&self, id: NodeId) -> Option<String> {
+        let node = self.node(id)?;
+        if node.is_hidden() {
+            return None;
+        }
+        if let Some(text) = node.label().or_else(|| node.value()) {
+            return (!text.is_empty()).then(|| text.to_string());
+        }
+        let contents: Vec<String> = self
+            .child_ids(id)
+            .filter_map(|child| self.text_content(child))
+            .collect();
+        (!contents.is_empty()).then(|| contents.join(" "))
+    }
+}
+
+fn supports_name_from_contents(role: Role) -> bool {
+    matches!(
+        role,
+        Role::Button
+            | Role::DefaultButton
+            | Role::Cell
+            | Role::CheckBox
+            | Role::ColumnHeader
+            | Role::GridCell
+            | Role::Heading
+            | Role::Label
+            | Role::Link
+            | Role::ListBoxOption
+            | Role::ListItem
+            | Role::MenuItem
+            | Role::MenuItemCheckBox
+            | Role::MenuItemRadio
+            | Role::MenuListOption
+            | Role::RadioButton
+            | Role::Row
+            | Role::RowHeader
+            | Role::Switch
+            | Role::Tab
+            | Role::Tooltip
+            | Role::TreeItem
+    )
+}
+
+pub struct tree_snapshot {
+    _private: [u8; 0],
+}
+
+impl CastPtr for tree_snapshot {
+    type RustType = TreeSnapshot;
+}
+
+impl BoxCastPtr for tree_snapshot {}
+
+impl tree_snapshot {
+    /// Builds a snapshot from a full tree update, such as the one returned
+    /// from an activation handler. Takes ownership of `update`.
+    ///
+    /// Returns null if the tree update doesn't have its `tree` field set.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_snapshot_new(update: *mut tree_update) -> *mut tree_snapshot {
+        let update = box_from_ptr(update);
+        BoxCastPtr::to_nullable_mut_ptr(TreeSnapshot::new(*update))
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_snapshot_free(snapshot: *mut tree_snapshot) {
+        drop(box_from_ptr(snapshot));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_snapshot_clone(
+        snapshot: *const tree_snapshot,
+    ) -> *mut tree_snapshot {
+        let snapshot = ref_from_ptr(snapshot);
+        BoxCastPtr::to_mut_ptr(snapshot.clone())
+    }
+
+    /// Applies an incremental tree update. Takes ownership of `update`.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_snapshot_update(
+        snapshot: *mut tree_snapshot,
+        update: *mut tree_update,
+    ) {
+        let snapshot = mut_from_ptr(snapshot);
+        let update = box_from_ptr(update);
+        snapshot.apply(*update);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_snapshot_root(snapshot: *const tree_snapshot) -> node_id {
+        let snapshot = ref_from_ptr(snapshot);
+        snapshot.root_id().into()
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_snapshot_focus(snapshot: *const tree_snapshot) -> node_id {
+        let snapshot = ref_from_ptr(snapshot);
+        snapshot.focus_id().into()
+    }
+
+    /// Returns the number of nodes reachable from the root.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_snapshot_len(snapshot: *const tree_snapshot) -> usize {
+        let snapshot = ref_from_ptr(snapshot);
+        snapshot.len()
+    }
+
+    /// Returns null if there is no node with this ID in the tree.
+    /// The returned node is owned by the snapshot.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_snapshot_node(
+        snapshot: *const tree_snapshot,
+        id: node_id,
+    ) -> *const node {
+        let snapshot = ref_from_ptr(snapshot);
+        match snapshot.node(id.into()) {
+            Some(node) => node as *const _ as *const node,
+            None => ptr::null(),
+        }
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_snapshot_parent(
+        snapshot: *const tree_snapshot,
+        id: node_id,
+    ) -> opt_node_id {
+        let snapshot = ref_from_ptr(snapshot);
+        snapshot.parent_id(id.into()).into()
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{ActionHandler, ActionRequest, ActivationHandler, DeactivationHandler, TreeUpdate};
+use std::{
+    ffi::c_void,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    box_from_ptr, BoxCastPtr, CastPtr, FfiActionHandler, FfiActivationHandler,
+    FfiDeactivationHandler,
+};
+
+/// A call to one of the handlers of an adapter, given to the application
+/// by its dispatcher so that it runs on a thread of its choosing.
+pub struct handler_task {
+    _private: [u8; 0],
+}
+
+impl CastPtr for handler_task {
+    type RustType = Box<dyn FnOnce() + Send>;
+}
+
+impl BoxCastPtr for handler_task {}
+
+impl handler_task {
+    /// Calls the handler, then frees the task. Takes ownership of `task`.
+    #[no_mangle]
+    pub extern "C" fn accesskit_handler_task_run(task: *mut handler_task) {
+        let task = box_from_ptr(task);
+        task();
+    }
+
+    /// Frees the task without calling the handler, for instance when the
+    /// application is shutting down.
+    #[no_mangle]
+    pub extern "C" fn accesskit_handler_task_free(task: *mut handler_task) {
+        drop(box_from_ptr(task));
+    }
+}
+
+type HandlerDispatcherCallbackUnwrapped =
+    extern "C" fn(task: *mut handler_task, userdata: *mut c_void);
+
+/// Called by an adapter, from any thread, instead of calling one of its
+/// handlers directly. The application must run the task later using
+/// `accesskit_handler_task_run`, or free it using
+/// `accesskit_handler_task_free`.
+///
+/// The task must be run on the thread that owns the adapter, typically by
+/// posting it to the UI thread, and not from within a call to one of the
+/// functions of the adapter, since running the activation handler gives
+/// the adapter its initial tree. For the same reason, it must not be run
+/// before the dispatcher returns.
+///
+/// Ownership of `task` is transferred to the callback.
+pub type HandlerDispatcherCallback =
+    Option<extern "C" fn(task: *mut handler_task, userdata: *mut c_void)>;
+
+#[derive(Clone, Copy)]
+struct TaskDispatcherUserdata(*mut c_void);
+
+unsafe impl Send for TaskDispatcherUserdata {}
+
+#[derive(Clone)]
+pub(crate) struct TaskDispatcher {
+    callback: HandlerDispatcherCallbackUnwrapped,
+    userdata: TaskDispatcherUserdata,
+}
+
+impl TaskDispatcher {
+    pub(crate) fn new(callback: HandlerDispatcherCallback, userdata: *mut c_void) -> Self {
+        Self {
+            callback: callback.unwrap(),
+            userdata: TaskDispatcherUserdata(userdata),
+        }
+    }
+
+    fn dispatch(&self, task: impl FnOnce() + Send + 'static) {
+        let task: Box<dyn FnOnce() + Send> = Box::new(task);
+        (self.callback)(BoxCastPtr::to_mut_ptr(task), self.userdata.0);
+    }
+}
+
+struct AdapterPtr<A>(*mut A);
+
+unsafe impl<A> Send for AdapterPtr<A> {}
+
+/// Gives tasks access to the adapter that dispatched them, for as long as
+/// it hasn't been freed.
+pub(crate) struct AdapterHandle<A>(Arc<Mutex<Option<AdapterPtr<A>>>>);
+
+impl<A> Clone for AdapterHandle<A> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<A> Default for AdapterHandle<A> {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(None)))
+    }
+}
+
+impl<A> AdapterHandle<A> {
+    pub(crate) fn set(&self, adapter: *mut A) {
+        *self.0.lock().unwrap() = Some(AdapterPtr(adapter));
+    }
+
+    /// Must be called before the adapter is freed.
+    pub(crate) fn clear(&self) {
+        *self.0.lock().unwrap() = None;
+    }
+
+    /// Must only be called on the thread that owns the adapter, outside of
+    /// any of its functions, so that the adapter isn't borrowed elsewhere.
+    pub(crate) fn with(&self, f: impl FnOnce(&mut A)) {
+        if let Some(adapter) = self.0.lock().unwrap().as_ref() {
+            f(unsafe { &mut *adapter.0 });
+        }
+    }
+}
+
+/// Calls the activation handler of the application through the dispatcher,
+/// then gives the tree it returns to `deliver`. The adapter is told that
+/// there is no tree yet in the meantime.
+pub(crate) struct DispatchedActivationHandler {
+    handler: FfiActivationHandler,
+    dispatcher: TaskDispatcher,
+    deliver: Arc<dyn Fn(TreeUpdate) + Send + Sync>,
+}
+
+impl DispatchedActivationHandler {
+    pub(crate) fn new(
+        handler: FfiActivationHandler,
+        dispatcher: TaskDispatcher,
+        deliver: impl Fn(TreeUpdate) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            handler,
+            dispatcher,
+            deliver: Arc::new(deliver),
+        }
+    }
+}
+
+impl ActivationHandler for DispatchedActivationHandler {
+    fn request_initial_tree(&mut self) -> Option<TreeUpdate> {
+        let mut handler = self.handler.clone();
+        let deliver = Arc::clone(&self.deliver);
+        self.dispatcher.dispatch(move || {
+            if let Some(update) = handler.request_initial_tree() {
+                deliver(update);
+            }
+        });
+        None
+    }
+}
+
+pub(crate) struct DispatchedActionHandler {
+    handler: FfiActionHandler,
+    dispatcher: TaskDispatcher,
+}
+
+impl DispatchedActionHandler {
+    pub(crate) fn new(handler: FfiActionHandler, dispatcher: TaskDispatcher) -> Self {
+        Self {
+            handler,
+            dispatcher,
+        }
+    }
+}
+
+impl ActionHandler for DispatchedActionHandler {
+    fn do_action(&mut self, request: ActionRequest) {
+        let mut handler = self.handler.clone();
+        self.dispatcher.dispatch(move || handler.do_action(request));
+    }
+}
+
+pub(crate) struct DispatchedDeactivationHandler {
+    handler: FfiDeactivationHandler,
+    dispatcher: TaskDispatcher,
+}
+
+impl DispatchedDeactivationHandler {
+    pub(crate) fn new(handler: FfiDeactivationHandler, dispatcher: TaskDispatcher) -> Self {
+        Self {
+            handler,
+            dispatcher,
+        }
+    }
+}
+
+impl DeactivationHandler for DispatchedDeactivationHandler {
+    fn deactivate_accessibility(&mut self) {
+        let mut handler = self.handler.clone();
+        self.dispatcher
+            .dispatch(move || handler.deactivate_accessibility());
+    }
+}
@@ -0,0 +1,299 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{Action, ActionData, ActionHandler, ActionRequest, ActivationHandler, NodeId};
+use std::{
+    ffi::{c_char, c_void, CStr, CString},
+    ptr,
+};
+
+use crate::{
+    box_from_ptr, mut_from_ptr, node_id, node_id_list, opt_node_id, ref_from_ptr,
+    string_from_c_slice, tree_snapshot, tree_update_factory, tree_update_factory_userdata,
+    ActionHandlerCallback, ActivationHandlerCallback, BoxCastPtr, CastPtr, FfiActionHandler,
+    FfiActivationHandler, Selector, TreeSnapshot,
+};
+
+pub(crate) struct TestDriver {
+    activation_handler: FfiActivationHandler,
+    action_handler: FfiActionHandler,
+    snapshot: Option<TreeSnapshot>,
+}
+
+impl TestDriver {
+    fn activate(&mut self) -> bool {
+        if self.snapshot.is_none() {
+            self.snapshot = self
+                .activation_handler
+                .request_initial_tree()
+                .and_then(TreeSnapshot::new);
+        }
+        self.snapshot.is_some()
+    }
+
+    fn query(&self, selector: &str) -> Option<Vec<NodeId>> {
+        let selector = Selector::parse(selector).ok()?;
+        Some(match &self.snapshot {
+            Some(snapshot) => selector.query(snapshot),
+            None => Vec::new(),
+        })
+    }
+
+    fn do_action(&mut self, target: node_id, action: Action, data: Option<ActionData>) -> bool {
+        let Some(snapshot) = &self.snapshot else {
+            return false;
+        };
+        if snapshot.node(target.into()).is_none() {
+            return false;
+        }
+        self.action_handler.do_action(ActionRequest {
+            action,
+            target_tree: snapshot.tree_id(),
+            target_node: target.into(),
+            data,
+        });
+        true
+    }
+}
+
+fn selector_error(selector: &str) -> *mut c_char {
+    match Selector::parse(selector) {
+        Ok(_) => ptr::null_mut(),
+        Err(error) => CString::new(error.to_string()).unwrap().into_raw(),
+    }
+}
+
+pub struct test_driver {
+    _private: [u8; 0],
+}
+
+impl CastPtr for test_driver {
+    type RustType = TestDriver;
+}
+
+impl BoxCastPtr for test_driver {}
+
+impl test_driver {
+    /// Creates a stand-in for a platform adapter that lets tests inspect
+    /// the tree and perform actions, using the same handlers as a real
+    /// adapter. The activation handler is called immediately.
+    #[no_mangle]
+    pub extern "C" fn accesskit_test_driver_new(
+        activation_handler: ActivationHandlerCallback,
+        activation_handler_userdata: *mut c_void,
+        action_handler: ActionHandlerCallback,
+        action_handler_userdata: *mut c_void,
+    ) -> *mut test_driver {
+        let mut driver = TestDriver {
+            activation_handler: FfiActivationHandler::new(
+                activation_handler,
+                activation_handler_userdata,
+            ),
+            action_handler: FfiActionHandler::new(action_handler, action_handler_userdata),
+            snapshot: None,
+        };
+        driver.activate();
+        BoxCastPtr::to_mut_ptr(driver)
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_test_driver_free(driver: *mut test_driver) {
+        drop(box_from_ptr(driver));
+    }
+
+    /// Calls the activation handler again if it previously returned null.
+    /// Returns whether the driver now has a tree.
+    #[no_mangle]
+    pub extern "C" fn accesskit_test_driver_activate(driver: *mut test_driver) -> bool {
+        let driver = mut_from_ptr(driver);
+        driver.activate()
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_test_driver_update_if_active(
+        driver: *mut test_driver,
+        update_factory: tree_update_factory,
+        update_factory_userdata: *mut c_void,
+    ) {
+        let Some(update_factory) = update_factory else {
+            return;
+        };
+        let update_factory_userdata = tree_update_factory_userdata(update_factory_userdata);
+        let driver = mut_from_ptr(driver);
+        if let Some(snapshot) = &mut driver.snapshot {
+            snapshot.apply(*box_from_ptr(update_factory(update_factory_userdata)));
+        }
+    }
+
+    /// Returns null if the driver doesn't have a tree yet.
+    /// The returned snapshot is owned by the driver and is only valid
+    /// until the next update.
+    #[no_mangle]
+    pub extern "C" fn accesskit_test_driver_snapshot(
+        driver: *const test_driver,
+    ) -> *const tree_snapshot {
+        let driver = ref_from_ptr(driver);
+        match &driver.snapshot {
+            Some(snapshot) => snapshot as *const _ as *const tree_snapshot,
+            None => ptr::null(),
+        }
+    }
+
+    /// Returns null if `selector` is valid, otherwise a description
+    /// of the first syntax error.
+    ///
+    /// Caller must call `accesskit_string_free` with the return value.
+    #[no_mangle]
+    pub extern "C" fn accesskit_selector_error(selector: *const c_char) -> *mut c_char {
+        let selector = unsafe { CStr::from_ptr(selector) };
+        selector_error(&selector.to_string_lossy())
+    }
+
+    /// Returns null if `selector` is valid, otherwise a description
+    /// of the first syntax error.
+    ///
+    /// Caller must call `accesskit_string_free` with the return value.
+    #[no_mangle]
+    pub extern "C" fn accesskit_selector_error_with_length(
+        selector: *const c_char,
+        length: usize,
+    ) -> *mut c_char {
+        selector_error(&unsafe { string_from_c_slice(selector, length) })
+    }
+
+    /// Finds the nodes matching `selector`, in document order.
+    ///
+    /// A selector is a list of compound selectors separated by `>`, which
+    /// matches children, or by whitespace, which matches descendants,
+    /// as in `window > list > list_item`. A compound selector is made of
+    /// an optional role name, attribute test or `*`, followed by any number
+    /// of bracketed attribute tests and `:nth(n)` filters, which keep
+    /// the n-th match counting from 1. Filters apply from left to right.
+    ///
+    /// Attribute tests take the form `attribute=value` or `attribute` alone,
+    /// and values may be quoted. The supported attributes are `role`, `id`,
+    /// `author_id`, `name` (the computed name), `label`, `value`,
+    /// `description`, `placeholder`, `tooltip`, `class_name`, `toggled`
+    /// (or `checked`) and the boolean states `expanded`, `selected`,
+    /// `disabled`, `hidden`, `required`, `read_only`, `focused`
+    /// and `focusable`. For example: `role=button[label="OK"]`,
+    /// `author_id=save` or `list_item:nth(3)`.
+    ///
+    /// Returns null if the selector is invalid; see `accesskit_selector_error`.
+    /// Caller must call `accesskit_node_id_list_free` with the return value.
+    #[no_mangle]
+    pub extern "C" fn accesskit_test_driver_query(
+        driver: *const test_driver,
+        selector: *const c_char,
+    ) -> *mut node_id_list {
+        let driver = ref_from_ptr(driver);
+        let selector = unsafe { CStr::from_ptr(selector) };
+        BoxCastPtr::to_nullable_mut_ptr(
+            driver
+                .query(&selector.to_string_lossy())
+                .map(|matches| matches.into()),
+        )
+    }
+
+    /// Returns null if the selector is invalid; see `accesskit_selector_error`.
+    /// Caller must call `accesskit_node_id_list_free` with the return value.
+    #[no_mangle]
+    pub extern "C" fn accesskit_test_driver_query_with_length(
+        driver: *const test_driver,
+        selector: *const c_char,
+        length: usize,
+    ) -> *mut node_id_list {
+        let driver = ref_from_ptr(driver);
+        let selector = unsafe { string_from_c_slice(selector, length) };
+        BoxCastPtr::to_nullable_mut_ptr(driver.query(&selector).map(|matches| matches.into()))
+    }
+
+    /// Returns the first node matching `selector`, if any.
+    #[no_mangle]
+    pub extern "C" fn accesskit_test_driver_query_one(
+        driver: *const test_driver,
+        selector: *const c_char,
+    ) -> opt_node_id {
+        let driver = ref_from_ptr(driver);
+        let selector = unsafe { CStr::from_ptr(selector) };
+        driver
+            .query(&selector.to_string_lossy())
+            .and_then(|matches| matches.first().copied())
+            .into()
+    }
+
+    /// Returns the first node matching `selector`, if any.
+    #[no_mangle]
+    pub extern "C" fn accesskit_test_driver_query_one_with_length(
+        driver: *const test_driver,
+        selector: *const c_char,
+        length: usize,
+    ) -> opt_node_id {
+        let driver = ref_from_ptr(driver);
+        let selector = unsafe { string_from_c_slice(selector, length) };
+        driver
+            .query(&selector)
+            .and_then(|matches| matches.first().copied())
+            .into()
+    }
+
+    /// Sends an action request to the action handler, as an assistive
+    /// technology would. Returns false without calling the handler
+    /// if `target` isn't in the tree.
+    #[no_mangle]
+    pub extern "C" fn accesskit_test_driver_do_action(
+        driver: *mut test_driver,
+        target: node_id,
+        action: Action,
+    ) -> bool {
+        let driver = mut_from_ptr(driver);
+        driver.do_action(target, action, None)
+    }
+
+    /// Like `accesskit_test_driver_do_action`, with a string value attached
+    /// to the request, for instance for `ACCESSKIT_ACTION_SET_VALUE`.
+    ///
+    /// Caller is responsible for freeing the memory pointed by `value`.
+    #[no_mangle]
+    pub extern "C" fn accesskit_test_driver_do_action_with_value(
+        driver: *mut test_driver,
+        target: node_id,
+        action: Action,
+        value: *const c_char,
+    ) -> bool {
+        let driver = mut_from_ptr(driver);
+        let value = unsafe { CStr::from_ptr(value) }.to_string_lossy();
+        driver.do_action(target, action, Some(ActionData::Value(value.into())))
+    }
+
+    /// Like `accesskit_test_driver_do_action`, with a numeric value attached
+    /// to the request.
+    #[no_mangle]
+    pub extern "C" fn accesskit_test_driver_do_action_with_numeric_value(
+        driver: *mut test_driver,
+        target: node_id,
+        action: Action,
+        value: f64,
+    ) -> bool {
+        let driver = mut_from_ptr(driver);
+        driver.do_action(target, action, Some(ActionData::NumericValue(value)))
+    }
+
+    /// Requests `ACCESSKIT_ACTION_CUSTOM_ACTION` with the given custom
+    /// action ID.
+    #[no_mangle]
+    pub extern "C" fn accesskit_test_driver_do_custom_action(
+        driver: *mut test_driver,
+        target: node_id,
+        id: i32,
+    ) -> bool {
+        let driver = mut_from_ptr(driver);
+        driver.do_action(
+            target,
+            Action::CustomAction,
+            Some(ActionData::CustomAction(id)),
+        )
+    }
+}
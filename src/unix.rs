@@ -5,25 +5,80 @@
 
 use accesskit::Rect;
 use accesskit_unix::Adapter;
-use std::ffi::{c_char, c_void};
+use std::{
+    ffi::{c_char, c_int, c_void},
+    fmt::{self, Debug},
+    ops::{Deref, DerefMut},
+    ptr,
+};
 
 use crate::{
-    box_from_ptr, debug_repr_from_ptr, mut_from_ptr, tree_update_factory,
-    tree_update_factory_userdata, ActionHandlerCallback, ActivationHandlerCallback, BoxCastPtr,
-    CastPtr, DeactivationHandlerCallback, FfiActionHandler, FfiActivationHandler,
-    FfiDeactivationHandler,
+    adapter_event, box_from_ptr, debug_repr_from_ptr, mut_from_ptr, ref_from_ptr, tree_update,
+    tree_update_factory, tree_update_factory_userdata, ActionHandlerCallback,
+    ActivationHandlerCallback, AdapterHandle, BoxCastPtr, CastPtr, DeactivationHandlerCallback,
+    DestroyNotifyCallback, DispatchedActionHandler, DispatchedActivationHandler,
+    DispatchedDeactivationHandler, EventQueue, FfiActionHandler, FfiActivationHandler,
+    FfiDeactivationHandler, HandlerDispatcherCallback, TaskDispatcher, UpdateValidator,
+    ValidatingAdapter, ValidationHandlerCallback,
 };
 
+pub(crate) struct UnixAdapter {
+    adapter: ValidatingAdapter<Adapter>,
+    /// The queue the handlers push events to, if the adapter was created
+    /// with one.
+    events: Option<EventQueue>,
+    handle: AdapterHandle<Adapter>,
+}
+
+impl Deref for UnixAdapter {
+    type Target = ValidatingAdapter<Adapter>;
+
+    fn deref(&self) -> &ValidatingAdapter<Adapter> {
+        &self.adapter
+    }
+}
+
+impl DerefMut for UnixAdapter {
+    fn deref_mut(&mut self) -> &mut ValidatingAdapter<Adapter> {
+        &mut self.adapter
+    }
+}
+
+impl Debug for UnixAdapter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.adapter.fmt(f)
+    }
+}
+
 pub struct unix_adapter {
     _private: [u8; 0],
 }
 
 impl CastPtr for unix_adapter {
-    type RustType = Adapter;
+    type RustType = UnixAdapter;
 }
 
 impl BoxCastPtr for unix_adapter {}
 
+impl unix_adapter {
+    fn new(
+        adapter: Adapter,
+        validator: UpdateValidator,
+        events: Option<EventQueue>,
+        handle: AdapterHandle<Adapter>,
+    ) -> *mut unix_adapter {
+        let adapter = BoxCastPtr::to_mut_ptr(UnixAdapter {
+            adapter: ValidatingAdapter::new(adapter, validator),
+            events,
+            handle,
+        });
+        let unix_adapter: &mut UnixAdapter = mut_from_ptr(adapter);
+        let inner: *mut Adapter = &mut *unix_adapter.adapter;
+        unix_adapter.handle.set(inner);
+        adapter
+    }
+}
+
 impl unix_adapter {
     /// All of the handlers will always be called from another thread.
     #[no_mangle]
@@ -35,18 +90,175 @@ impl unix_adapter {
         deactivation_handler: DeactivationHandlerCallback,
         deactivation_handler_userdata: *mut c_void,
     ) -> *mut unix_adapter {
-        let activation_handler =
-            FfiActivationHandler::new(activation_handler, activation_handler_userdata);
+        let validator = UpdateValidator::default();
+        let activation_handler = validator.activation_handler(FfiActivationHandler::new(
+            activation_handler,
+            activation_handler_userdata,
+        ));
         let action_handler = FfiActionHandler::new(action_handler, action_handler_userdata);
+        let deactivation_handler = validator.deactivation_handler(FfiDeactivationHandler::new(
+            deactivation_handler,
+            deactivation_handler_userdata,
+        ));
+        let adapter = Adapter::new(activation_handler, action_handler, deactivation_handler);
+        Self::new(adapter, validator, None, AdapterHandle::default())
+    }
+
+    /// Same as `accesskit_unix_adapter_new`, except that each handler comes
+    /// with an optional callback freeing its userdata. It is called once,
+    /// possibly from another thread, when the library drops the handler,
+    /// which may be after `accesskit_unix_adapter_free` returns.
+    #[no_mangle]
+    pub extern "C" fn accesskit_unix_adapter_new_with_destroy_notify(
+        activation_handler: ActivationHandlerCallback,
+        activation_handler_userdata: *mut c_void,
+        activation_handler_destroy_notify: DestroyNotifyCallback,
+        action_handler: ActionHandlerCallback,
+        action_handler_userdata: *mut c_void,
+        action_handler_destroy_notify: DestroyNotifyCallback,
+        deactivation_handler: DeactivationHandlerCallback,
+        deactivation_handler_userdata: *mut c_void,
+        deactivation_handler_destroy_notify: DestroyNotifyCallback,
+    ) -> *mut unix_adapter {
+        let validator = UpdateValidator::default();
+        let activation_handler =
+            validator.activation_handler(FfiActivationHandler::with_destroy_notify(
+                activation_handler,
+                activation_handler_userdata,
+                activation_handler_destroy_notify,
+            ));
+        let action_handler = FfiActionHandler::with_destroy_notify(
+            action_handler,
+            action_handler_userdata,
+            action_handler_destroy_notify,
+        );
         let deactivation_handler =
-            FfiDeactivationHandler::new(deactivation_handler, deactivation_handler_userdata);
+            validator.deactivation_handler(FfiDeactivationHandler::with_destroy_notify(
+                deactivation_handler,
+                deactivation_handler_userdata,
+                deactivation_handler_destroy_notify,
+            ));
         let adapter = Adapter::new(activation_handler, action_handler, deactivation_handler);
-        BoxCastPtr::to_mut_ptr(adapter)
+        Self::new(adapter, validator, None, AdapterHandle::default())
+    }
+
+    /// Creates an adapter that gives every call to its handlers to
+    /// `dispatcher`, which must run them on the thread that owns the
+    /// adapter. The tree returned by the activation handler is given to the
+    /// adapter once the call completes, if the adapter still exists. If
+    /// `dispatcher` is null, this is the same as
+    /// `accesskit_unix_adapter_new_with_destroy_notify`.
+    ///
+    /// The destroy notify callbacks are the same as in
+    /// `accesskit_unix_adapter_new_with_destroy_notify`, and may be null.
+    /// Since tasks hold the handlers, a handler may also be dropped when
+    /// its pending tasks are run or freed.
+    #[no_mangle]
+    pub extern "C" fn accesskit_unix_adapter_new_with_dispatcher(
+        activation_handler: ActivationHandlerCallback,
+        activation_handler_userdata: *mut c_void,
+        activation_handler_destroy_notify: DestroyNotifyCallback,
+        action_handler: ActionHandlerCallback,
+        action_handler_userdata: *mut c_void,
+        action_handler_destroy_notify: DestroyNotifyCallback,
+        deactivation_handler: DeactivationHandlerCallback,
+        deactivation_handler_userdata: *mut c_void,
+        deactivation_handler_destroy_notify: DestroyNotifyCallback,
+        dispatcher: HandlerDispatcherCallback,
+        dispatcher_userdata: *mut c_void,
+    ) -> *mut unix_adapter {
+        if dispatcher.is_none() {
+            return Self::accesskit_unix_adapter_new_with_destroy_notify(
+                activation_handler,
+                activation_handler_userdata,
+                activation_handler_destroy_notify,
+                action_handler,
+                action_handler_userdata,
+                action_handler_destroy_notify,
+                deactivation_handler,
+                deactivation_handler_userdata,
+                deactivation_handler_destroy_notify,
+            );
+        }
+        let dispatcher = TaskDispatcher::new(dispatcher, dispatcher_userdata);
+        let validator = UpdateValidator::default();
+        let handle = AdapterHandle::<Adapter>::default();
+        let deliver = {
+            let validator = validator.clone();
+            let handle = handle.clone();
+            move |update| {
+                if let Some(update) = validator.provide_initial_tree(update) {
+                    handle.with(|adapter| adapter.update_if_active(|| update));
+                }
+            }
+        };
+        let activation_handler = validator.activation_handler(DispatchedActivationHandler::new(
+            FfiActivationHandler::with_destroy_notify(
+                activation_handler,
+                activation_handler_userdata,
+                activation_handler_destroy_notify,
+            ),
+            dispatcher.clone(),
+            deliver,
+        ));
+        let action_handler = DispatchedActionHandler::new(
+            FfiActionHandler::with_destroy_notify(
+                action_handler,
+                action_handler_userdata,
+                action_handler_destroy_notify,
+            ),
+            dispatcher.clone(),
+        );
+        let deactivation_handler =
+            validator.deactivation_handler(DispatchedDeactivationHandler::new(
+                FfiDeactivationHandler::with_destroy_notify(
+                    deactivation_handler,
+                    deactivation_handler_userdata,
+                    deactivation_handler_destroy_notify,
+                ),
+                dispatcher,
+            ));
+        let adapter = Adapter::new(activation_handler, action_handler, deactivation_handler);
+        Self::new(adapter, validator, None, handle)
+    }
+
+    /// Creates an adapter whose handlers push events to a queue instead of
+    /// calling back into the application, so that they can be handled on
+    /// the thread owning the adapter. Take them with
+    /// `accesskit_unix_adapter_poll_event`.
+    ///
+    /// Returns null if the file descriptors signaling pending events
+    /// couldn't be created.
+    #[no_mangle]
+    pub extern "C" fn accesskit_unix_adapter_new_with_event_queue() -> *mut unix_adapter {
+        let Ok(events) = EventQueue::new() else {
+            return ptr::null_mut();
+        };
+        let validator = UpdateValidator::default();
+        let activation_handler = validator.activation_handler(events.clone());
+        let deactivation_handler = validator.deactivation_handler(events.clone());
+        let adapter = Adapter::new(activation_handler, events.clone(), deactivation_handler);
+        Self::new(adapter, validator, Some(events), AdapterHandle::default())
     }
 
     #[no_mangle]
     pub extern "C" fn accesskit_unix_adapter_free(adapter: *mut unix_adapter) {
-        drop(box_from_ptr(adapter));
+        let adapter = box_from_ptr(adapter);
+        adapter.handle.clear();
+        drop(adapter);
+    }
+
+    /// Validates every tree update given to the adapter and reports the
+    /// invalid ones to `handler` instead of applying them. Pass a null
+    /// `handler` to turn validation off.
+    #[no_mangle]
+    pub extern "C" fn accesskit_unix_adapter_set_validation_handler(
+        adapter: *mut unix_adapter,
+        handler: ValidationHandlerCallback,
+        userdata: *mut c_void,
+    ) {
+        let adapter = mut_from_ptr(adapter);
+        adapter.validator.set_handler(handler, userdata);
     }
 
     /// Set the bounds of the top-level window. The outer bounds contain any
@@ -72,10 +284,34 @@ impl unix_adapter {
         update_factory: tree_update_factory,
         update_factory_userdata: *mut c_void,
     ) {
-        let update_factory = update_factory.unwrap();
+        let Some(update_factory) = update_factory else {
+            return;
+        };
         let update_factory_userdata = tree_update_factory_userdata(update_factory_userdata);
         let adapter = mut_from_ptr(adapter);
-        adapter.update_if_active(|| *box_from_ptr(update_factory(update_factory_userdata)));
+        let validator = adapter.validator.clone();
+        adapter.update_if_active(|| {
+            validator.check_update(*box_from_ptr(update_factory(update_factory_userdata)))
+        });
+    }
+
+    /// Gives the adapter the initial tree that its activation handler didn't
+    /// return, which must be a full tree update. Passing one to
+    /// `accesskit_unix_adapter_update_if_active` works too. Takes ownership of
+    /// `update`, which is dropped, returning false, if the adapter isn't
+    /// waiting for its initial tree.
+    #[no_mangle]
+    pub extern "C" fn accesskit_unix_adapter_provide_initial_tree(
+        adapter: *mut unix_adapter,
+        update: *mut tree_update,
+    ) -> bool {
+        let update = *box_from_ptr(update);
+        let adapter = mut_from_ptr(adapter);
+        let Some(update) = adapter.validator.provide_initial_tree(update) else {
+            return false;
+        };
+        adapter.update_if_active(|| update);
+        true
     }
 
     /// Update the tree state based on whether the window is focused.
@@ -88,6 +324,36 @@ impl unix_adapter {
         adapter.update_window_focus_state(is_focused);
     }
 
+    /// Removes the oldest event from the queue of an adapter created with
+    /// `accesskit_unix_adapter_new_with_event_queue` and stores it in
+    /// `event`. Returns false, leaving `event` untouched, if the queue is
+    /// empty or the adapter has no queue.
+    #[no_mangle]
+    pub extern "C" fn accesskit_unix_adapter_poll_event(
+        adapter: *mut unix_adapter,
+        event: *mut adapter_event,
+    ) -> bool {
+        let adapter = mut_from_ptr(adapter);
+        match adapter.events.as_ref().and_then(EventQueue::pop) {
+            Some(next) => {
+                unsafe { event.write(next) };
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns a file descriptor that is readable while the event queue of
+    /// the adapter isn't empty, to be watched by the event loop of the
+    /// application, or -1 if the adapter has no queue. Only wait for it to
+    /// become readable, and call `accesskit_unix_adapter_poll_event` until
+    /// it returns false; the descriptor is owned by the adapter.
+    #[no_mangle]
+    pub extern "C" fn accesskit_unix_adapter_event_fd(adapter: *const unix_adapter) -> c_int {
+        let adapter = ref_from_ptr(adapter);
+        adapter.events.as_ref().map_or(-1, EventQueue::fd)
+    }
+
     /// Caller must call `accesskit_string_free` with the return value.
     #[no_mangle]
     pub extern "C" fn accesskit_unix_adapter_debug(adapter: *const unix_adapter) -> *mut c_char {
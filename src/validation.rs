@@ -0,0 +1,564 @@
+// Copyright 2026 The AccessKit Authors. All rights reserved.
+// Licensed under the Apache License, Version 2.0 (found in
+// the LICENSE-APACHE file) or the MIT license (found in
+// the LICENSE-MIT file), at your option.
+
+use accesskit::{ActivationHandler, DeactivationHandler, Node, NodeId, Role, TreeUpdate};
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::{c_char, c_void, CString},
+    fmt::{self, Debug, Write},
+    ops::{Deref, DerefMut},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+};
+
+use crate::{
+    box_from_ptr, opt_node_id, ref_from_ptr, tree_snapshot, tree_update, BoxCastPtr, CastPtr,
+    TreeSnapshot,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum tree_update_error {
+    /// There is no previous tree and the update doesn't set its `tree`
+    /// field.
+    MissingTree,
+    /// The root node is neither in the update nor in the previous tree.
+    MissingRoot,
+    /// `node` lists `related` as a child, but `related` is neither in the
+    /// update nor in the previous tree.
+    MissingChild,
+    /// `node` was pushed to the update, but isn't in the previous tree and
+    /// can't be reached from the root.
+    UnattachedNode,
+    /// `node` lists `related` as a child, but `related` is `node` itself
+    /// or one of its ancestors.
+    Cycle,
+    /// `node` is listed as a child of `related` while already having
+    /// another parent, or is listed twice by `related`.
+    MultipleParents,
+    /// The focused node `node` can't be reached from the root.
+    MissingFocus,
+}
+
+impl tree_update_error {
+    fn id(self) -> &'static str {
+        match self {
+            Self::MissingTree => "missing-tree",
+            Self::MissingRoot => "missing-root",
+            Self::MissingChild => "missing-child",
+            Self::UnattachedNode => "unattached-node",
+            Self::Cycle => "cycle",
+            Self::MultipleParents => "multiple-parents",
+            Self::MissingFocus => "missing-focus",
+        }
+    }
+}
+
+pub(crate) struct TreeUpdateDiagnostic {
+    error: tree_update_error,
+    node: Option<NodeId>,
+    related: Option<NodeId>,
+    message: CString,
+}
+
+struct Validation<'a> {
+    update: &'a TreeUpdate,
+    previous: Option<&'a TreeSnapshot>,
+    pushed: HashMap<NodeId, &'a Node>,
+    diagnostics: Vec<TreeUpdateDiagnostic>,
+}
+
+impl<'a> Validation<'a> {
+    fn node(&self, id: NodeId) -> Option<&'a Node> {
+        self.pushed
+            .get(&id)
+            .copied()
+            .or_else(|| self.previous.and_then(|previous| previous.node(id)))
+    }
+
+    fn report(
+        &mut self,
+        error: tree_update_error,
+        node: Option<NodeId>,
+        related: Option<NodeId>,
+        message: String,
+    ) {
+        self.diagnostics.push(TreeUpdateDiagnostic {
+            error,
+            node,
+            related,
+            message: CString::new(message).unwrap(),
+        });
+    }
+
+    fn missing_from(&self) -> &'static str {
+        if self.previous.is_some() {
+            "neither in the update nor in the previous tree"
+        } else {
+            "not in the update"
+        }
+    }
+
+    fn run(&mut self) {
+        let root = match (&self.update.tree, self.previous) {
+            (Some(tree), _) => tree.root,
+            (None, Some(previous)) => previous.root_id(),
+            (None, None) => {
+                self.report(
+                    tree_update_error::MissingTree,
+                    None,
+                    None,
+                    "the first update of a tree must set the tree field".into(),
+                );
+                return;
+            }
+        };
+        if self.node(root).is_none() {
+            let message = format!("root #{} is {}", root.0, self.missing_from());
+            self.report(tree_update_error::MissingRoot, Some(root), None, message);
+            return;
+        }
+
+        let mut parents: HashMap<NodeId, NodeId> = HashMap::new();
+        let mut reached = HashSet::from([root]);
+        let mut stack = vec![root];
+        while let Some(id) = stack.pop() {
+            let start = stack.len();
+            for &child in self.node(id).unwrap().children() {
+                if self.node(child).is_none() {
+                    let message = format!(
+                        "#{} lists child #{}, which is {}",
+                        id.0,
+                        child.0,
+                        self.missing_from()
+                    );
+                    self.report(
+                        tree_update_error::MissingChild,
+                        Some(id),
+                        Some(child),
+                        message,
+                    );
+                } else if child == root
+                    || child == id
+                    || std::iter::successors(parents.get(&id), |ancestor| parents.get(ancestor))
+                        .any(|ancestor| *ancestor == child)
+                {
+                    let message = if child == id {
+                        format!("#{} lists itself as a child", id.0)
+                    } else {
+                        format!("#{} lists its ancestor #{} as a child", id.0, child.0)
+                    };
+                    self.report(tree_update_error::Cycle, Some(id), Some(child), message);
+                } else if let Some(&parent) = parents.get(&child) {
+                    let message = if parent == id {
+                        format!("#{} is listed twice as a child of #{}", child.0, id.0)
+                    } else {
+                        format!(
+                            "#{} is listed as a child of both #{} and #{}",
+                            child.0, parent.0, id.0
+                        )
+                    };
+                    self.report(
+                        tree_update_error::MultipleParents,
+                        Some(child),
+                        Some(id),
+                        message,
+                    );
+                } else {
+                    parents.insert(child, id);
+                    reached.insert(child);
+                    stack.push(child);
+                }
+            }
+            stack[start..].reverse();
+        }
+
+        for (id, _) in &self.update.nodes {
+            let previously_present = self
+                .previous
+                .is_some_and(|previous| previous.node(*id).is_some());
+            if !reached.contains(id) && !previously_present {
+                let message = format!(
+                    "#{} was pushed, but can't be reached from root #{}",
+                    id.0, root.0
+                );
+                self.report(tree_update_error::UnattachedNode, Some(*id), None, message);
+            }
+        }
+        let focus = self.update.focus;
+        if !reached.contains(&focus) {
+            let message = format!("focus #{} can't be reached from root #{}", focus.0, root.0);
+            self.report(tree_update_error::MissingFocus, Some(focus), None, message);
+        }
+    }
+}
+
+/// Checks that applying `update` to `previous` results in a well-formed
+/// tree. `previous` is ignored if it holds another tree than the one
+/// `update` is for.
+pub(crate) fn validate(
+    update: &TreeUpdate,
+    previous: Option<&TreeSnapshot>,
+) -> Vec<TreeUpdateDiagnostic> {
+    let mut validation = Validation {
+        update,
+        previous: previous.filter(|previous| previous.tree_id() == update.tree_id),
+        pushed: update.nodes.iter().map(|(id, node)| (*id, node)).collect(),
+        diagnostics: Vec::new(),
+    };
+    validation.run();
+    validation.diagnostics
+}
+
+fn render(diagnostics: &[TreeUpdateDiagnostic]) -> String {
+    let mut output = String::new();
+    for diagnostic in diagnostics {
+        writeln!(
+            output,
+            "[{}] {}",
+            diagnostic.error.id(),
+            diagnostic.message.to_string_lossy()
+        )
+        .unwrap();
+    }
+    output
+}
+
+/// A problem that would make a platform adapter reject a tree update.
+///
+/// `message` is owned by the `accesskit_tree_update_diagnostics` this was
+/// retrieved from.
+#[repr(C)]
+pub struct tree_update_diagnostic {
+    pub error: tree_update_error,
+    pub node: opt_node_id,
+    pub related: opt_node_id,
+    pub message: *const c_char,
+}
+
+pub struct tree_update_diagnostics {
+    _private: [u8; 0],
+}
+
+impl CastPtr for tree_update_diagnostics {
+    type RustType = Vec<TreeUpdateDiagnostic>;
+}
+
+impl BoxCastPtr for tree_update_diagnostics {}
+
+impl tree_update_diagnostics {
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_diagnostics_free(
+        diagnostics: *mut tree_update_diagnostics,
+    ) {
+        drop(box_from_ptr(diagnostics));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_diagnostics_len(
+        diagnostics: *const tree_update_diagnostics,
+    ) -> usize {
+        let diagnostics = ref_from_ptr(diagnostics);
+        diagnostics.len()
+    }
+
+    /// `index` must be lower than the value returned by
+    /// `accesskit_tree_update_diagnostics_len`.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_diagnostics_get(
+        diagnostics: *const tree_update_diagnostics,
+        index: usize,
+    ) -> tree_update_diagnostic {
+        let diagnostics = ref_from_ptr(diagnostics);
+        let diagnostic = &diagnostics[index];
+        tree_update_diagnostic {
+            error: diagnostic.error,
+            node: diagnostic.node.into(),
+            related: diagnostic.related.into(),
+            message: diagnostic.message.as_ptr(),
+        }
+    }
+
+    /// Renders the diagnostics one per line, for instance
+    /// `[missing-child] #1 lists child #7, which is not in the update`.
+    ///
+    /// Caller must call `accesskit_string_free` with the return value.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_diagnostics_to_string(
+        diagnostics: *const tree_update_diagnostics,
+    ) -> *mut c_char {
+        let diagnostics = ref_from_ptr(diagnostics);
+        CString::new(render(diagnostics)).unwrap().into_raw()
+    }
+}
+
+impl tree_update {
+    /// Checks that `update` can be applied to `previous_snapshot`, which
+    /// may be null if `update` describes an initial tree. The checks cover
+    /// children that were never pushed, a root or focus that isn't in the
+    /// resulting tree, cycles, nodes listed under two parents, and pushed
+    /// nodes that can't be reached from the root. `previous_snapshot` is
+    /// ignored if its tree ID differs from the one of `update`.
+    ///
+    /// Returns null if the update is valid.
+    #[no_mangle]
+    pub extern "C" fn accesskit_tree_update_validate(
+        update: *const tree_update,
+        previous_snapshot: *const tree_snapshot,
+    ) -> *mut tree_update_diagnostics {
+        let update = ref_from_ptr(update);
+        let previous = (!previous_snapshot.is_null()).then(|| ref_from_ptr(previous_snapshot));
+        let diagnostics = validate(update, previous);
+        BoxCastPtr::to_nullable_mut_ptr((!diagnostics.is_empty()).then_some(diagnostics))
+    }
+}
+
+type ValidationHandlerCallbackUnwrapped =
+    extern "C" fn(diagnostics: *mut tree_update_diagnostics, userdata: *mut c_void);
+
+/// Called by an adapter with validation turned on when it is given an
+/// invalid tree update, which is checked the same way as by
+/// `accesskit_tree_update_validate`. An invalid initial tree is treated as
+/// if the activation handler had returned null. Other invalid updates are
+/// replaced by one that leaves the tree unchanged, or if the adapter has no
+/// valid tree yet, by a tree made of the root alone. Validation should be
+/// turned on before the adapter is active, since incremental updates can
+/// only be checked against a known tree.
+///
+/// The callback runs on the thread giving the update to the adapter. For an
+/// initial tree returned by the activation handler, that is the thread the
+/// activation handler is called on, which for the Unix adapter is always
+/// another thread than the one owning the adapter.
+///
+/// Ownership of `diagnostics` is transferred to the callback. `diagnostics`
+/// must be freed using `accesskit_tree_update_diagnostics_free`.
+pub type ValidationHandlerCallback =
+    Option<extern "C" fn(diagnostics: *mut tree_update_diagnostics, userdata: *mut c_void)>;
+
+struct ValidationHandlerUserdata(*mut c_void);
+
+unsafe impl Send for ValidationHandlerUserdata {}
+
+struct ValidationState {
+    callback: ValidationHandlerCallbackUnwrapped,
+    userdata: ValidationHandlerUserdata,
+    snapshot: Option<TreeSnapshot>,
+}
+
+/// Replaces the tree with only its root, for an initial tree that failed
+/// validation.
+fn placeholder(update: &TreeUpdate) -> TreeUpdate {
+    let tree = update.tree.clone().unwrap();
+    let root = tree.root;
+    let role = update
+        .nodes
+        .iter()
+        .find(|(id, _)| *id == root)
+        .map_or(Role::Window, |(_, node)| node.role());
+    TreeUpdate {
+        nodes: vec![(root, Node::new(role))],
+        tree: Some(tree),
+        tree_id: update.tree_id,
+        focus: root,
+    }
+}
+
+/// The validation settings of an adapter along with the tree it was last
+/// given, shared with its activation handler.
+#[derive(Clone, Default)]
+pub(crate) struct UpdateValidator {
+    state: Arc<Mutex<Option<ValidationState>>>,
+    /// Set while the adapter is activating and waits for its initial tree,
+    /// either from `provide_initial_tree` or a full tree update.
+    awaiting_initial_tree: Arc<AtomicBool>,
+}
+
+impl UpdateValidator {
+    pub(crate) fn set_handler(&self, callback: ValidationHandlerCallback, userdata: *mut c_void) {
+        *self.state.lock().unwrap() = callback.map(|callback| ValidationState {
+            callback,
+            userdata: ValidationHandlerUserdata(userdata),
+            snapshot: None,
+        });
+    }
+
+    pub(crate) fn activation_handler<H: ActivationHandler>(
+        &self,
+        handler: H,
+    ) -> ValidatingActivationHandler<H> {
+        ValidatingActivationHandler {
+            handler,
+            validator: self.clone(),
+        }
+    }
+
+    // The following exception is needed because deactivation handlers
+    // are only given to the Unix and iOS adapters.
+    #[allow(dead_code)]
+    pub(crate) fn deactivation_handler<H: DeactivationHandler>(
+        &self,
+        handler: H,
+    ) -> ValidatingDeactivationHandler<H> {
+        ValidatingDeactivationHandler {
+            handler,
+            validator: self.clone(),
+        }
+    }
+
+    /// The lock is released before calling the handler, since it may
+    /// change the validation settings.
+    fn report(state: MutexGuard<Option<ValidationState>>, diagnostics: Vec<TreeUpdateDiagnostic>) {
+        let state_ref = state.as_ref().unwrap();
+        let callback = state_ref.callback;
+        let userdata = state_ref.userdata.0;
+        drop(state);
+        callback(BoxCastPtr::to_mut_ptr(diagnostics), userdata);
+    }
+
+    /// Returns `None` instead of an invalid initial tree, so that the
+    /// adapter keeps waiting for one.
+    pub(crate) fn check_initial_tree(&self, update: TreeUpdate) -> Option<TreeUpdate> {
+        let mut state = self.state.lock().unwrap();
+        let Some(state_ref) = state.as_mut() else {
+            return Some(update);
+        };
+        let diagnostics = validate(&update, None);
+        if diagnostics.is_empty() {
+            state_ref.snapshot = TreeSnapshot::new(update.clone());
+            return Some(update);
+        }
+        state_ref.snapshot = None;
+        Self::report(state, diagnostics);
+        None
+    }
+
+    /// Returns the initial tree to give to the adapter, or `None` if the
+    /// adapter isn't waiting for one or the tree is invalid.
+    pub(crate) fn provide_initial_tree(&self, update: TreeUpdate) -> Option<TreeUpdate> {
+        if !self.awaiting_initial_tree.load(Ordering::SeqCst) {
+            return None;
+        }
+        let update = self.check_initial_tree(update)?;
+        self.awaiting_initial_tree.store(false, Ordering::SeqCst);
+        Some(update)
+    }
+
+    /// Replaces an invalid update with one that leaves the tree unchanged,
+    /// or if there is no valid tree yet, with a tree made of its root
+    /// alone. Incremental updates are passed on unchecked if no tree is
+    /// known, as are updates for other trees.
+    ///
+    /// Only called once the adapter is active or activating, so the update
+    /// also ends the wait for an initial tree.
+    pub(crate) fn check_update(&self, update: TreeUpdate) -> TreeUpdate {
+        self.awaiting_initial_tree.store(false, Ordering::SeqCst);
+        let mut state = self.state.lock().unwrap();
+        let Some(state_ref) = state.as_mut() else {
+            return update;
+        };
+        let (diagnostics, replacement) = match state_ref.snapshot.as_mut() {
+            Some(snapshot) if snapshot.tree_id() == update.tree_id => {
+                let diagnostics = validate(&update, Some(snapshot));
+                if diagnostics.is_empty() {
+                    snapshot.apply(update.clone());
+                    return update;
+                }
+                let replacement = TreeUpdate {
+                    nodes: Vec::new(),
+                    tree: None,
+                    tree_id: update.tree_id,
+                    focus: snapshot.focus_id(),
+                };
+                (diagnostics, replacement)
+            }
+            None if update.tree.is_some() => {
+                let diagnostics = validate(&update, None);
+                if diagnostics.is_empty() {
+                    state_ref.snapshot = TreeSnapshot::new(update.clone());
+                    return update;
+                }
+                let replacement = placeholder(&update);
+                state_ref.snapshot = TreeSnapshot::new(replacement.clone());
+                (diagnostics, replacement)
+            }
+            _ => return update,
+        };
+        Self::report(state, diagnostics);
+        replacement
+    }
+}
+
+pub(crate) struct ValidatingActivationHandler<H> {
+    handler: H,
+    validator: UpdateValidator,
+}
+
+impl<H: ActivationHandler> ActivationHandler for ValidatingActivationHandler<H> {
+    /// The adapter is marked as waiting before calling the handler, since
+    /// it may hand the tree to another thread that provides it before the
+    /// handler returns.
+    fn request_initial_tree(&mut self) -> Option<TreeUpdate> {
+        let awaiting = &self.validator.awaiting_initial_tree;
+        awaiting.store(true, Ordering::SeqCst);
+        let result = self
+            .handler
+            .request_initial_tree()
+            .and_then(|update| self.validator.check_initial_tree(update));
+        if result.is_some() {
+            awaiting.store(false, Ordering::SeqCst);
+        }
+        result
+    }
+}
+
+#[allow(dead_code)]
+pub(crate) struct ValidatingDeactivationHandler<H> {
+    handler: H,
+    validator: UpdateValidator,
+}
+
+impl<H: DeactivationHandler> DeactivationHandler for ValidatingDeactivationHandler<H> {
+    fn deactivate_accessibility(&mut self) {
+        self.validator
+            .awaiting_initial_tree
+            .store(false, Ordering::SeqCst);
+        self.handler.deactivate_accessibility();
+    }
+}
+
+/// A platform adapter along with the state needed to validate the tree
+/// updates it is given.
+pub(crate) struct ValidatingAdapter<A> {
+    adapter: A,
+    pub(crate) validator: UpdateValidator,
+}
+
+impl<A> ValidatingAdapter<A> {
+    pub(crate) fn new(adapter: A, validator: UpdateValidator) -> Self {
+        Self { adapter, validator }
+    }
+}
+
+impl<A> Deref for ValidatingAdapter<A> {
+    type Target = A;
+
+    fn deref(&self) -> &A {
+        &self.adapter
+    }
+}
+
+impl<A> DerefMut for ValidatingAdapter<A> {
+    fn deref_mut(&mut self) -> &mut A {
+        &mut self.adapter
+    }
+}
+
+impl<A: Debug> Debug for ValidatingAdapter<A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.adapter.fmt(f)
+    }
+}
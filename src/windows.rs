@@ -4,12 +4,16 @@
 // the LICENSE-MIT file), at your option.
 
 use accesskit_windows::*;
-use std::ffi::{c_char, c_void};
+use std::{
+    ffi::{c_char, c_void},
+    ptr,
+};
 
 use crate::{
-    box_from_ptr, debug_repr_from_ptr, mut_from_ptr, opt_struct, tree_update_factory,
+    box_from_ptr, debug_repr_from_ptr, mut_from_ptr, opt_struct, tree_update, tree_update_factory,
     tree_update_factory_userdata, ActionHandlerCallback, ActivationHandlerCallback, BoxCastPtr,
-    CastPtr, FfiActionHandler, FfiActivationHandler,
+    CastPtr, DestroyNotifyCallback, FfiActionHandler, FfiActivationHandler, UpdateValidator,
+    ValidatingAdapter, ValidationHandlerCallback,
 };
 
 pub struct windows_queued_events {
@@ -38,7 +42,7 @@ pub struct windows_adapter {
 }
 
 impl CastPtr for windows_adapter {
-    type RustType = Adapter;
+    type RustType = ValidatingAdapter<Adapter>;
 }
 
 impl BoxCastPtr for windows_adapter {}
@@ -53,7 +57,29 @@ impl windows_adapter {
     ) -> *mut windows_adapter {
         let action_handler = FfiActionHandler::new(action_handler, action_handler_userdata);
         let adapter = Adapter::new(hwnd, is_window_focused, action_handler);
-        BoxCastPtr::to_mut_ptr(adapter)
+        BoxCastPtr::to_mut_ptr(ValidatingAdapter::new(adapter, UpdateValidator::default()))
+    }
+
+    /// Same as `accesskit_windows_adapter_new`, except that the action
+    /// handler comes with an optional callback freeing its userdata. Since
+    /// the action handler may be called on other threads, it is called
+    /// once, possibly from another thread, when the library drops the
+    /// handler, which may be after `accesskit_windows_adapter_free` returns.
+    #[no_mangle]
+    pub extern "C" fn accesskit_windows_adapter_new_with_destroy_notify(
+        hwnd: HWND,
+        is_window_focused: bool,
+        action_handler: ActionHandlerCallback,
+        action_handler_userdata: *mut c_void,
+        action_handler_destroy_notify: DestroyNotifyCallback,
+    ) -> *mut windows_adapter {
+        let action_handler = FfiActionHandler::with_destroy_notify(
+            action_handler,
+            action_handler_userdata,
+            action_handler_destroy_notify,
+        );
+        let adapter = Adapter::new(hwnd, is_window_focused, action_handler);
+        BoxCastPtr::to_mut_ptr(ValidatingAdapter::new(adapter, UpdateValidator::default()))
     }
 
     #[no_mangle]
@@ -61,6 +87,19 @@ impl windows_adapter {
         drop(box_from_ptr(adapter));
     }
 
+    /// Validates every tree update given to the adapter and reports the
+    /// invalid ones to `handler` instead of applying them. Pass a null
+    /// `handler` to turn validation off.
+    #[no_mangle]
+    pub extern "C" fn accesskit_windows_adapter_set_validation_handler(
+        adapter: *mut windows_adapter,
+        handler: ValidationHandlerCallback,
+        userdata: *mut c_void,
+    ) {
+        let adapter = mut_from_ptr(adapter);
+        adapter.validator.set_handler(handler, userdata);
+    }
+
     /// You must call `accesskit_windows_queued_events_raise` on the returned pointer. It can be null if the adapter is not active.
     #[no_mangle]
     pub extern "C" fn accesskit_windows_adapter_update_if_active(
@@ -68,11 +107,36 @@ impl windows_adapter {
         update_factory: tree_update_factory,
         update_factory_userdata: *mut c_void,
     ) -> *mut windows_queued_events {
-        let update_factory = update_factory.unwrap();
+        let Some(update_factory) = update_factory else {
+            return ptr::null_mut();
+        };
         let update_factory_userdata = tree_update_factory_userdata(update_factory_userdata);
         let adapter = mut_from_ptr(adapter);
-        let events =
-            adapter.update_if_active(|| *box_from_ptr(update_factory(update_factory_userdata)));
+        let validator = adapter.validator.clone();
+        let events = adapter.update_if_active(|| {
+            validator.check_update(*box_from_ptr(update_factory(update_factory_userdata)))
+        });
+        BoxCastPtr::to_nullable_mut_ptr(events)
+    }
+
+    /// Gives the adapter the initial tree that its activation handler didn't
+    /// return, which must be a full tree update. Passing one to
+    /// `accesskit_windows_adapter_update_if_active` works too. Takes ownership
+    /// of `update`, which is dropped if the adapter isn't waiting for its
+    /// initial tree.
+    ///
+    /// You must call `accesskit_windows_queued_events_raise` on the returned pointer. It can be null if the adapter wasn't waiting for its initial tree.
+    #[no_mangle]
+    pub extern "C" fn accesskit_windows_adapter_provide_initial_tree(
+        adapter: *mut windows_adapter,
+        update: *mut tree_update,
+    ) -> *mut windows_queued_events {
+        let update = *box_from_ptr(update);
+        let adapter = mut_from_ptr(adapter);
+        let Some(update) = adapter.validator.provide_initial_tree(update) else {
+            return ptr::null_mut();
+        };
+        let events = adapter.update_if_active(|| update);
         BoxCastPtr::to_nullable_mut_ptr(events)
     }
 
@@ -99,7 +163,12 @@ impl windows_adapter {
     ) -> opt_lresult {
         let adapter = mut_from_ptr(adapter);
         let mut activation_handler =
-            FfiActivationHandler::new(activation_handler, activation_handler_userdata);
+            adapter
+                .validator
+                .activation_handler(FfiActivationHandler::new(
+                    activation_handler,
+                    activation_handler_userdata,
+                ));
         let lresult = adapter.handle_wm_getobject(wparam, lparam, &mut activation_handler);
         opt_lresult::from(lresult)
     }
@@ -118,7 +187,7 @@ pub struct windows_subclassing_adapter {
 }
 
 impl CastPtr for windows_subclassing_adapter {
-    type RustType = SubclassingAdapter;
+    type RustType = ValidatingAdapter<SubclassingAdapter>;
 }
 
 impl BoxCastPtr for windows_subclassing_adapter {}
@@ -143,11 +212,49 @@ impl windows_subclassing_adapter {
         action_handler: ActionHandlerCallback,
         action_handler_userdata: *mut c_void,
     ) -> *mut windows_subclassing_adapter {
-        let activation_handler =
-            FfiActivationHandler::new(activation_handler, activation_handler_userdata);
+        let validator = UpdateValidator::default();
+        let activation_handler = validator.activation_handler(FfiActivationHandler::new(
+            activation_handler,
+            activation_handler_userdata,
+        ));
         let action_handler = FfiActionHandler::new(action_handler, action_handler_userdata);
         let adapter = SubclassingAdapter::new(hwnd, activation_handler, action_handler);
-        BoxCastPtr::to_mut_ptr(adapter)
+        BoxCastPtr::to_mut_ptr(ValidatingAdapter::new(adapter, validator))
+    }
+
+    /// Same as `accesskit_windows_subclassing_adapter_new`, except that each
+    /// handler comes with an optional callback freeing its userdata. It is
+    /// called once, possibly from another thread, when the library drops
+    /// the handler, which may be after
+    /// `accesskit_windows_subclassing_adapter_free` returns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the window is already visible.
+    #[no_mangle]
+    pub extern "C" fn accesskit_windows_subclassing_adapter_new_with_destroy_notify(
+        hwnd: HWND,
+        activation_handler: ActivationHandlerCallback,
+        activation_handler_userdata: *mut c_void,
+        activation_handler_destroy_notify: DestroyNotifyCallback,
+        action_handler: ActionHandlerCallback,
+        action_handler_userdata: *mut c_void,
+        action_handler_destroy_notify: DestroyNotifyCallback,
+    ) -> *mut windows_subclassing_adapter {
+        let validator = UpdateValidator::default();
+        let activation_handler =
+            validator.activation_handler(FfiActivationHandler::with_destroy_notify(
+                activation_handler,
+                activation_handler_userdata,
+                activation_handler_destroy_notify,
+            ));
+        let action_handler = FfiActionHandler::with_destroy_notify(
+            action_handler,
+            action_handler_userdata,
+            action_handler_destroy_notify,
+        );
+        let adapter = SubclassingAdapter::new(hwnd, activation_handler, action_handler);
+        BoxCastPtr::to_mut_ptr(ValidatingAdapter::new(adapter, validator))
     }
 
     #[no_mangle]
@@ -157,6 +264,19 @@ impl windows_subclassing_adapter {
         drop(box_from_ptr(adapter));
     }
 
+    /// Validates every tree update given to the adapter and reports the
+    /// invalid ones to `handler` instead of applying them. Pass a null
+    /// `handler` to turn validation off.
+    #[no_mangle]
+    pub extern "C" fn accesskit_windows_subclassing_adapter_set_validation_handler(
+        adapter: *mut windows_subclassing_adapter,
+        handler: ValidationHandlerCallback,
+        userdata: *mut c_void,
+    ) {
+        let adapter = mut_from_ptr(adapter);
+        adapter.validator.set_handler(handler, userdata);
+    }
+
     /// You must call `accesskit_windows_queued_events_raise` on the returned pointer. It can be null if the adapter is not active.
     #[no_mangle]
     pub extern "C" fn accesskit_windows_subclassing_adapter_update_if_active(
@@ -164,11 +284,36 @@ impl windows_subclassing_adapter {
         update_factory: tree_update_factory,
         update_factory_userdata: *mut c_void,
     ) -> *mut windows_queued_events {
-        let update_factory = update_factory.unwrap();
+        let Some(update_factory) = update_factory else {
+            return ptr::null_mut();
+        };
         let update_factory_userdata = tree_update_factory_userdata(update_factory_userdata);
         let adapter = mut_from_ptr(adapter);
-        let events =
-            adapter.update_if_active(|| *box_from_ptr(update_factory(update_factory_userdata)));
+        let validator = adapter.validator.clone();
+        let events = adapter.update_if_active(|| {
+            validator.check_update(*box_from_ptr(update_factory(update_factory_userdata)))
+        });
+        BoxCastPtr::to_nullable_mut_ptr(events)
+    }
+
+    /// Gives the adapter the initial tree that its activation handler didn't
+    /// return, which must be a full tree update. Passing one to
+    /// `accesskit_windows_subclassing_adapter_update_if_active` works too.
+    /// Takes ownership of `update`, which is dropped if the adapter isn't
+    /// waiting for its initial tree.
+    ///
+    /// You must call `accesskit_windows_queued_events_raise` on the returned pointer. It can be null if the adapter wasn't waiting for its initial tree.
+    #[no_mangle]
+    pub extern "C" fn accesskit_windows_subclassing_adapter_provide_initial_tree(
+        adapter: *mut windows_subclassing_adapter,
+        update: *mut tree_update,
+    ) -> *mut windows_queued_events {
+        let update = *box_from_ptr(update);
+        let adapter = mut_from_ptr(adapter);
+        let Some(update) = adapter.validator.provide_initial_tree(update) else {
+            return ptr::null_mut();
+        };
+        let events = adapter.update_if_active(|| update);
         BoxCastPtr::to_nullable_mut_ptr(events)
     }
 }